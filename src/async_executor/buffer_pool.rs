@@ -0,0 +1,269 @@
+use std::collections::HashMap;
+use std::ops::Deref;
+use std::os::raw::c_void;
+use std::sync::{Arc, Mutex};
+
+use crate::{
+    async_executor::memory_tracker::MemoryTracker,
+    cl_types::{cl_buffer::ClBuffer, cl_context::ClContext, mem_object::MemObject, memory_flags::MemoryFlags},
+    error::{ClError, wrapper_error::WrapperError},
+};
+
+/// Smallest size class, in bytes. Tiny requests share it instead of getting their own class.
+const MIN_SIZE_CLASS: usize = 256;
+
+/// Largest size class, in bytes: the largest power of two a `usize` holds.
+const MAX_SIZE_CLASS: usize = 1 << (usize::BITS - 1);
+
+/// # BufferPoolConfig
+///
+/// Limits of a [`BufferPool`]. Buffers returned while a limit is reached are released
+/// instead of being kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BufferPoolConfig {
+    /// Total bytes the pool may keep idle.
+    pub max_bytes: usize,
+    /// Idle buffers kept per size class and flag combination.
+    pub max_buffers_per_class: usize,
+}
+
+impl Default for BufferPoolConfig {
+    fn default() -> Self {
+        Self { max_bytes: 256 * 1024 * 1024, max_buffers_per_class: 16 }
+    }
+}
+
+/// # BufferPoolStats
+///
+/// Counters of a [`BufferPool`] since it was created.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BufferPoolStats {
+    /// Acquisitions served by an idle buffer.
+    pub hits: u64,
+    /// Acquisitions that had to create a buffer.
+    pub misses: u64,
+    /// Buffers released because a limit was reached or the pool was trimmed.
+    pub evictions: u64,
+    /// Bytes currently kept idle.
+    pub bytes_held: usize,
+    /// Buffers currently kept idle.
+    pub buffers_held: usize,
+}
+
+struct PoolState {
+    config: BufferPoolConfig,
+    free: HashMap<(u64, usize), Vec<ClBuffer>>,
+    stats: BufferPoolStats,
+}
+
+impl PoolState {
+    /// Keeps `buffer` if the limits allow it, otherwise releases it.
+    fn give_back(&mut self, key: (u64, usize), buffer: ClBuffer) {
+        let class = self.free.entry(key).or_default();
+        if class.len() >= self.config.max_buffers_per_class || self.stats.bytes_held + key.1 > self.config.max_bytes {
+            self.stats.evictions += 1;
+            return;
+        }
+        class.push(buffer);
+        self.stats.bytes_held += key.1;
+        self.stats.buffers_held += 1;
+    }
+
+    /// Releases idle buffers, largest classes first, until at most `max_bytes` are held.
+    fn trim_to(&mut self, max_bytes: usize) {
+        let mut keys: Vec<(u64, usize)> = self.free.keys().copied().collect();
+        keys.sort_by_key(|key| std::cmp::Reverse(key.1));
+        for key in keys {
+            let class = self.free.get_mut(&key).unwrap();
+            while self.stats.bytes_held > max_bytes && class.pop().is_some() {
+                self.stats.bytes_held -= key.1;
+                self.stats.buffers_held -= 1;
+                self.stats.evictions += 1;
+            }
+        }
+        self.free.retain(|_, class| !class.is_empty());
+    }
+}
+
+/// # BufferPool
+///
+/// Recycles `ClBuffer`s by size class and memory flags, so workloads that allocate
+/// and release the same sizes repeatedly don't pay for `clCreateBuffer` every time.
+///
+/// Requests are rounded up to the next power of two. An acquired buffer is a
+/// [`PooledBuffer`], which goes back to the pool when dropped. Idle buffers are kept
+/// within the limits of [`BufferPoolConfig`] and can be released with [`BufferPool::trim`].
+///
+/// Pooled buffers are not cleared between uses. Flags that need a host pointer
+/// (`UseHostPtr`, `CopyHostPtr`) can't be pooled.
+///
+/// # Example
+/// ```ignore
+/// let executor = AsyncExecutor::new_best_platform()?.with_buffer_pool(BufferPoolConfig::default());
+/// {
+///     let scratch = executor.acquire_buffer(&[MemoryFlags::ReadWrite], 4096)?;
+///     executor.create_task(&kernel).arg_buffer(0, &scratch).global_work_dims(1024, 1, 1).run().await?;
+/// } // `scratch` goes back to the pool here
+/// println!("{:?}", executor.buffer_pool().unwrap().stats());
+/// ```
+#[derive(Clone)]
+pub struct BufferPool {
+    context: Arc<ClContext>,
+    state: Arc<Mutex<PoolState>>,
+    tracker: Option<MemoryTracker>,
+}
+
+impl BufferPool {
+    pub fn new(context: Arc<ClContext>, config: BufferPoolConfig) -> Self {
+        let state = PoolState { config, free: HashMap::new(), stats: BufferPoolStats::default() };
+        Self { context, state: Arc::new(Mutex::new(state)), tracker: None }
+    }
+
+    /// Counts the buffers the pool creates (idle ones included) in `tracker`.
+    pub(crate) fn with_tracker(mut self, tracker: MemoryTracker) -> Self {
+        self.tracker = Some(tracker);
+        self
+    }
+
+    /// Size class, in bytes, used for a request of `size` bytes, or `None` if rounding
+    /// `size` up to a power of two overflows.
+    pub fn size_class(size: usize) -> Option<usize> {
+        size.max(MIN_SIZE_CLASS).checked_next_power_of_two()
+    }
+
+    /// Returns an idle buffer of at least `size` bytes with exactly `flags`, or creates one.
+    ///
+    /// Fails with `AllocationTooLarge` if `size` has no size class.
+    pub fn acquire(&self, flags: &[MemoryFlags], size: usize) -> Result<PooledBuffer, ClError> {
+        let Some(class) = Self::size_class(size) else {
+            let (max_alloc, in_use) = self
+                .tracker
+                .as_ref()
+                .map_or((MAX_SIZE_CLASS, 0), |tracker| (tracker.usage().max_alloc.min(MAX_SIZE_CLASS), tracker.usage().in_use));
+            return Err(ClError::Wrapper(WrapperError::AllocationTooLarge { requested: size, max_alloc, in_use }));
+        };
+        let key = (MemoryFlags::to_u64(&flags.to_vec()), class);
+
+        let recycled = {
+            let mut state = self.state.lock().unwrap();
+            let buffer = state.free.get_mut(&key).and_then(|class| class.pop());
+            if buffer.is_some() {
+                state.stats.hits += 1;
+                state.stats.bytes_held -= key.1;
+                state.stats.buffers_held -= 1;
+            } else {
+                state.stats.misses += 1;
+            }
+            buffer
+        };
+
+        let buffer = match recycled {
+            Some(buffer) => buffer,
+            None => {
+                let reservation = self.tracker.as_ref().map(|tracker| tracker.reserve(key.1)).transpose()?;
+                let buffer = ClBuffer::new(&self.context, &flags.to_vec(), key.1, std::ptr::null_mut())?;
+                if let Some(reservation) = reservation {
+                    reservation.attach(buffer.as_ptr());
+                }
+                buffer
+            }
+        };
+        Ok(PooledBuffer { buffer: Some(buffer), size, key, pool: Some(self.state.clone()) })
+    }
+
+    pub fn config(&self) -> BufferPoolConfig {
+        self.state.lock().unwrap().config
+    }
+
+    /// Changes the limits. Idle buffers over the new byte limit are released.
+    pub fn set_config(&self, config: BufferPoolConfig) {
+        let mut state = self.state.lock().unwrap();
+        state.config = config;
+        for class in state.free.values_mut() {
+            class.truncate(config.max_buffers_per_class);
+        }
+        let (bytes_held, buffers_held) = state
+            .free
+            .iter()
+            .fold((0, 0), |(bytes, count), (key, class)| (bytes + key.1 * class.len(), count + class.len()));
+        state.stats.evictions += (state.stats.buffers_held - buffers_held) as u64;
+        state.stats.bytes_held = bytes_held;
+        state.stats.buffers_held = buffers_held;
+        state.trim_to(config.max_bytes);
+    }
+
+    pub fn stats(&self) -> BufferPoolStats {
+        self.state.lock().unwrap().stats
+    }
+
+    /// Releases every idle buffer.
+    pub fn trim(&self) {
+        self.trim_to(0);
+    }
+
+    /// Releases idle buffers, largest first, until at most `max_bytes` are held.
+    pub fn trim_to(&self, max_bytes: usize) {
+        self.state.lock().unwrap().trim_to(max_bytes);
+    }
+}
+
+/// # PooledBuffer
+///
+/// A buffer borrowed from a [`BufferPool`]. It dereferences to `ClBuffer` and goes
+/// back to the pool when dropped.
+///
+/// The underlying buffer may be larger than requested; [`PooledBuffer::size`] is the
+/// requested size.
+pub struct PooledBuffer {
+    buffer: Option<ClBuffer>,
+    size: usize,
+    key: (u64, usize),
+    pool: Option<Arc<Mutex<PoolState>>>,
+}
+
+impl PooledBuffer {
+    /// Wraps a buffer that doesn't belong to any pool and is simply released on drop.
+    pub(crate) fn unpooled(buffer: ClBuffer, size: usize) -> Self {
+        Self { buffer: Some(buffer), size, key: (0, size), pool: None }
+    }
+
+    /// Requested size in bytes.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Size in bytes of the underlying buffer.
+    pub fn capacity(&self) -> usize {
+        self.key.1
+    }
+
+    /// Takes the buffer out of the pool for good.
+    pub fn detach(mut self) -> ClBuffer {
+        self.pool = None;
+        self.buffer.take().unwrap()
+    }
+}
+
+impl Deref for PooledBuffer {
+    type Target = ClBuffer;
+
+    fn deref(&self) -> &ClBuffer {
+        self.buffer.as_ref().unwrap()
+    }
+}
+
+impl MemObject for PooledBuffer {
+    fn mem_ptr(&self) -> *mut c_void {
+        self.deref().as_ptr()
+    }
+}
+
+impl Drop for PooledBuffer {
+    fn drop(&mut self) {
+        if let (Some(pool), Some(buffer)) = (self.pool.take(), self.buffer.take())
+            && let Ok(mut state) = pool.lock()
+        {
+            state.give_back(self.key, buffer);
+        }
+    }
+}
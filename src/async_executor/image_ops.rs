@@ -0,0 +1,199 @@
+use crate::{
+    cl_types::{cl_image::ClImage, mem_object_type::MemObjectType},
+    error::{ClError, wrapper_error::WrapperError},
+};
+
+/// # ResizeFilter
+///
+/// How `AsyncExecutor::resize_image` samples the source image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResizeFilter {
+    /// Takes the closest source pixel. Keeps hard edges; use it for masks and labels.
+    Nearest,
+    /// Interpolates the 4 closest source pixels.
+    Bilinear,
+}
+
+/// # ColorConversion
+///
+/// Color space conversions of `AsyncExecutor::convert_color`. YUV is BT.601 full range,
+/// with U and V offset by 0.5 so normalized formats can store them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorConversion {
+    RgbToYuv,
+    YuvToRgb,
+    /// Writes the luma to the red, green and blue channels.
+    RgbToGray,
+}
+
+impl ColorConversion {
+    pub(crate) fn kernel_name(&self) -> &'static str {
+        match self {
+            Self::RgbToYuv => "rgb_to_yuv",
+            Self::YuvToRgb => "yuv_to_rgb",
+            Self::RgbToGray => "rgb_to_gray",
+        }
+    }
+}
+
+/// Kernels of the image operations. Every kernel reads with `read_imagef`, so images need
+/// normalized or float channels. `gray` tells kernels computing a luma that the image has
+/// fewer than 3 channels, whose luma is its first channel.
+pub(crate) const IMAGE_OPS_SOURCE: &str = r#"
+__constant sampler_t nearest = CLK_NORMALIZED_COORDS_FALSE | CLK_ADDRESS_CLAMP_TO_EDGE | CLK_FILTER_NEAREST;
+
+inline float luma(float4 c, int gray) {
+    return gray ? c.x : dot(c.xyz, (float3)(0.299f, 0.587f, 0.114f));
+}
+
+inline float4 yuv_to_rgba(float y, float u, float v, float a) {
+    return (float4)(y + 1.403f * v, y - 0.344f * u - 0.714f * v, y + 1.773f * u, a);
+}
+
+kernel void resize_nearest(read_only image2d_t src, write_only image2d_t dst) {
+    int2 p = (int2)(get_global_id(0), get_global_id(1));
+    float2 scale = convert_float2(get_image_dim(src)) / convert_float2(get_image_dim(dst));
+    int2 s = convert_int2((convert_float2(p) + 0.5f) * scale);
+    write_imagef(dst, p, read_imagef(src, nearest, s));
+}
+
+kernel void resize_bilinear(read_only image2d_t src, write_only image2d_t dst) {
+    int2 p = (int2)(get_global_id(0), get_global_id(1));
+    float2 scale = convert_float2(get_image_dim(src)) / convert_float2(get_image_dim(dst));
+    float2 s = (convert_float2(p) + 0.5f) * scale - 0.5f;
+    float2 f = floor(s);
+    float2 t = s - f;
+    int2 i = convert_int2(f);
+    float4 top = mix(read_imagef(src, nearest, i), read_imagef(src, nearest, i + (int2)(1, 0)), t.x);
+    float4 bottom = mix(read_imagef(src, nearest, i + (int2)(0, 1)), read_imagef(src, nearest, i + (int2)(1, 1)), t.x);
+    write_imagef(dst, p, mix(top, bottom, t.y));
+}
+
+kernel void convolve_1d(read_only image2d_t src, write_only image2d_t dst, global const float* weights, int radius, int dx, int dy) {
+    int2 p = (int2)(get_global_id(0), get_global_id(1));
+    int2 step = (int2)(dx, dy);
+    float4 sum = (float4)(0.0f);
+    for (int k = -radius; k <= radius; k++) {
+        sum += weights[k + radius] * read_imagef(src, nearest, p + k * step);
+    }
+    write_imagef(dst, p, sum);
+}
+
+kernel void sobel(read_only image2d_t src, write_only image2d_t dst, int gray) {
+    int2 p = (int2)(get_global_id(0), get_global_id(1));
+    float l[3][3];
+    for (int y = 0; y < 3; y++) {
+        for (int x = 0; x < 3; x++) {
+            l[y][x] = luma(read_imagef(src, nearest, p + (int2)(x - 1, y - 1)), gray);
+        }
+    }
+    float gx = (l[0][2] + 2.0f * l[1][2] + l[2][2]) - (l[0][0] + 2.0f * l[1][0] + l[2][0]);
+    float gy = (l[2][0] + 2.0f * l[2][1] + l[2][2]) - (l[0][0] + 2.0f * l[0][1] + l[0][2]);
+    float m = sqrt(gx * gx + gy * gy);
+    write_imagef(dst, p, (float4)(m, m, m, 1.0f));
+}
+
+kernel void rgb_to_yuv(read_only image2d_t src, write_only image2d_t dst) {
+    int2 p = (int2)(get_global_id(0), get_global_id(1));
+    float4 c = read_imagef(src, nearest, p);
+    float y = luma(c, 0);
+    write_imagef(dst, p, (float4)(y, (c.z - y) * 0.564f + 0.5f, (c.x - y) * 0.713f + 0.5f, c.w));
+}
+
+kernel void yuv_to_rgb(read_only image2d_t src, write_only image2d_t dst) {
+    int2 p = (int2)(get_global_id(0), get_global_id(1));
+    float4 c = read_imagef(src, nearest, p);
+    write_imagef(dst, p, yuv_to_rgba(c.x, c.y - 0.5f, c.z - 0.5f, c.w));
+}
+
+kernel void rgb_to_gray(read_only image2d_t src, write_only image2d_t dst) {
+    int2 p = (int2)(get_global_id(0), get_global_id(1));
+    float4 c = read_imagef(src, nearest, p);
+    float y = luma(c, 0);
+    write_imagef(dst, p, (float4)(y, y, y, c.w));
+}
+
+inline int luma_bin(float4 c, int gray) {
+    return (int)(clamp(luma(c, gray), 0.0f, 1.0f) * 255.0f + 0.5f);
+}
+
+kernel void luma_histogram(read_only image2d_t src, global uint* bins, int gray) {
+    int2 p = (int2)(get_global_id(0), get_global_id(1));
+    atomic_inc(&bins[luma_bin(read_imagef(src, nearest, p), gray)]);
+}
+
+kernel void equalize(read_only image2d_t src, write_only image2d_t dst, global const float* lut, int gray) {
+    int2 p = (int2)(get_global_id(0), get_global_id(1));
+    float4 c = read_imagef(src, nearest, p);
+    float y = lut[luma_bin(c, gray)];
+    if (gray) {
+        write_imagef(dst, p, (float4)(y, c.y, c.z, c.w));
+    } else {
+        float old_y = luma(c, 0);
+        write_imagef(dst, p, yuv_to_rgba(y, (c.z - old_y) * 0.564f, (c.x - old_y) * 0.713f, c.w));
+    }
+}
+"#;
+
+/// Number of luma levels `equalize_histogram` works with.
+pub(crate) const HISTOGRAM_BINS: usize = 256;
+
+/// Fails unless `image` is a 2D image kernels read as floats.
+pub(crate) fn check_float_image_2d(image: &ClImage, operation: &'static str) -> Result<(), ClError> {
+    let actual = image.get_mem_type()?;
+    if actual != MemObjectType::Image2D {
+        return Err(ClError::Wrapper(WrapperError::ImageTypeMismatch { expected: MemObjectType::Image2D, actual }));
+    }
+    let format = image.get_image_format()?;
+    if !format.image_channel_data_type.reads_as_float() {
+        return Err(ClError::Wrapper(WrapperError::UnsupportedImageOperation { operation, format }));
+    }
+    Ok(())
+}
+
+/// Whether the luma of `image` is its first channel (fewer than 3 channels), as an `int` kernel argument.
+pub(crate) fn gray_arg(image: &ClImage) -> Result<i32, ClError> {
+    let channels = image.get_image_format()?.image_channel_order.channel_count().unwrap_or(4);
+    Ok((channels < 3) as i32)
+}
+
+/// Largest radius of the Gaussian kernel of `gaussian_blur`, in pixels. A larger `sigma` is truncated
+/// to `2 * MAX_BLUR_RADIUS + 1` taps.
+pub(crate) const MAX_BLUR_RADIUS: i32 = 128;
+
+/// Normalized 1D Gaussian kernel of standard deviation `sigma`, `2 * ceil(3 * sigma) + 1` taps long
+/// (at most `2 * MAX_BLUR_RADIUS + 1`). A `sigma` of 0 or less gives the single tap `[1.0]`.
+/// Fails with `InvalidBlurSigma` if `sigma` is NaN or infinite.
+pub(crate) fn gaussian_weights(sigma: f32) -> Result<Vec<f32>, ClError> {
+    if !sigma.is_finite() {
+        return Err(ClError::Wrapper(WrapperError::InvalidBlurSigma { sigma }));
+    }
+    if sigma <= 0.0 {
+        return Ok(vec![1.0]);
+    }
+    let radius = ((3.0 * sigma).ceil() as i32).min(MAX_BLUR_RADIUS);
+    let weights: Vec<f32> = (-radius..=radius).map(|x| (-((x * x) as f32) / (2.0 * sigma * sigma)).exp()).collect();
+    let sum: f32 = weights.iter().sum();
+    Ok(weights.into_iter().map(|w| w / sum).collect())
+}
+
+/// Maps each luma level to its equalized value, from the cumulative distribution of `histogram`.
+pub(crate) fn equalization_lut(histogram: &[u32]) -> Vec<f32> {
+    let total: u64 = histogram.iter().map(|&count| count as u64).sum();
+    let min = histogram.iter().copied().find(|&count| count > 0).unwrap_or(0) as u64;
+    let last = (histogram.len() - 1) as f32;
+    let mut cumulative = 0u64;
+    histogram
+        .iter()
+        .enumerate()
+        .map(|(level, &count)| {
+            cumulative += count as u64;
+            if total == min {
+                // A single level: nothing to spread
+                level as f32 / last
+            } else {
+                cumulative.saturating_sub(min) as f32 / (total - min) as f32
+            }
+        })
+        .collect()
+}
@@ -21,6 +21,12 @@ pub enum KernelArg<'a> {
         arg: Vec<u8>
     },
 
+    /// `__local` memory of `size` bytes, allocated per work-group.
+    Local {
+        arg_index: u32,
+        size: usize
+    },
+
     #[cfg(feature = "CL_VERSION_1_2")]
     Image {
         arg_index: u32,
@@ -37,4 +43,18 @@ pub enum KernelArg<'a> {
 #[cfg(feature = "CL_VERSION_1_1")]
 unsafe impl<'a> Send for KernelArg<'a> {}
 #[cfg(feature = "CL_VERSION_1_1")]
-unsafe impl<'a> Sync for KernelArg<'a> {}
\ No newline at end of file
+unsafe impl<'a> Sync for KernelArg<'a> {}
+#[cfg(feature = "CL_VERSION_1_1")]
+impl KernelArg<'_> {
+    /// The `cl_mem` handle of the argument, if it is a memory object.
+    pub(crate) fn mem_object(&self) -> Option<*mut c_void> {
+        match self {
+            KernelArg::Buffer { arg, .. } => Some(arg.as_ptr()),
+            #[cfg(feature = "CL_VERSION_1_2")]
+            KernelArg::Image { arg, .. } => Some(arg.as_ptr()),
+            #[cfg(feature = "CL_VERSION_2_0")]
+            KernelArg::Pipe { arg, .. } => Some(arg.as_ptr()),
+            _ => None,
+        }
+    }
+}
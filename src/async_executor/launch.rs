@@ -0,0 +1,287 @@
+use crate::{
+    async_executor::{AsyncExecutor, kernel_arg::KernelArg, task_builder::{OutputRead, TaskReport}},
+    cl_types::{cl_device::opencl_version::OpenCLVersion, cl_event::ClEvent, cl_kernel::ClKernel},
+    error::{ClError, wrapper_error::WrapperError},
+};
+use std::os::raw::c_void;
+
+/// A single kernel launch, shared by `TaskBuilder`, `PipelineBuilder` and `StreamBuilder`.
+pub(crate) struct Launch<'l, 'a> {
+    pub kernel: &'a ClKernel,
+    pub kernel_args: &'l [KernelArg<'a>],
+    pub global_work_dims: [usize; 3],
+    pub global_work_offset: [usize; 3],
+    pub local_work_dims: Option<[usize; 3]>,
+    /// Runs the whole range on this device instead of splitting it across all of them.
+    pub device_index: Option<usize>,
+    /// When splitting, migrates the memory objects to each device before its chunk runs.
+    pub pre_migrate: bool,
+}
+
+/// Binds every argument to `kernel`. Handles are passed with the size of a pointer.
+pub(crate) fn set_kernel_args(kernel: &ClKernel, kernel_args: &[KernelArg<'_>]) -> Result<(), ClError> {
+    let handle_size = std::mem::size_of::<*mut c_void>();
+    for arg in kernel_args {
+        match arg {
+            KernelArg::Scalar { arg_index, arg } => unsafe {
+                kernel.set_args(*arg_index, arg.len(), arg.as_ptr() as *const _)?;
+            },
+            KernelArg::Buffer { arg_index, arg } => {
+                let handle = arg.as_ptr();
+                unsafe {
+                    kernel.set_args(*arg_index, handle_size, &handle as *const _ as *const _)?;
+                }
+            }
+            KernelArg::Local { arg_index, size } => unsafe {
+                kernel.set_args(*arg_index, *size, std::ptr::null())?;
+            },
+            #[cfg(feature = "CL_VERSION_1_2")]
+            KernelArg::Image { arg_index, arg } => {
+                let handle = arg.as_ptr();
+                unsafe {
+                    kernel.set_args(*arg_index, handle_size, &handle as *const _ as *const _)?;
+                }
+            }
+            #[cfg(feature = "CL_VERSION_2_0")]
+            KernelArg::Svm { arg_index, arg, len: _ } => unsafe {
+                kernel.set_svm_arg(*arg_index, handle_size, *arg)?;
+            },
+            #[cfg(feature = "CL_VERSION_2_0")]
+            KernelArg::Pipe { arg_index, arg } => {
+                let handle = arg.as_ptr();
+                unsafe {
+                    kernel.set_args(*arg_index, handle_size, &handle as *const _ as *const _)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Number of dimensions implied by the global size and offset.
+fn work_dim(global_work_dims: [usize; 3], global_work_offset: [usize; 3]) -> usize {
+    if global_work_dims[2] > 1 || global_work_offset[2] > 0 {
+        3
+    } else if global_work_dims[1] > 1 || global_work_offset[1] > 0 {
+        2
+    } else {
+        1
+    }
+}
+
+/// Picks a local size for a 1D chunk on the device at `device_index`.
+///
+/// An empty result lets the driver decide, which is always valid when the device
+/// supports non-uniform work-groups. Otherwise the largest divisor of `chunk_size`
+/// that fits in the kernel's work-group limit is used.
+fn auto_local_work_dims(async_executor: &AsyncExecutor, kernel: &ClKernel, device_index: usize, work_dim: usize, chunk_size: usize) -> Vec<usize> {
+    if work_dim != 1 || chunk_size == 0 {
+        return Vec::new();
+    }
+
+    let device = async_executor.devices[device_index].clone();
+    let version = async_executor.get_device_versions()[device_index];
+    if version >= OpenCLVersion::V2_0 && device.get_non_uniform_work_group_support().unwrap_or(false) {
+        return Vec::new();
+    }
+
+    let max = match kernel.get_work_group_size(device) {
+        Ok(max) if max > 0 => max.min(chunk_size),
+        _ => return Vec::new(),
+    };
+    match (1..=max).rev().find(|size| chunk_size.is_multiple_of(*size)) {
+        Some(size) if size > 1 => vec![size],
+        _ => Vec::new(),
+    }
+}
+
+/// Sets the arguments and enqueues the kernel on every device, splitting the first
+/// dimension by device weight. Returns the kernel events in device order.
+pub(crate) async fn launch(
+    async_executor: &AsyncExecutor,
+    launch: &Launch<'_, '_>,
+    wait_list: Option<Vec<ClEvent>>,
+    report: Option<&mut TaskReport>,
+) -> Result<Vec<ClEvent>, ClError> {
+    let num_queues = async_executor.queues.len();
+    if num_queues == 0 {
+        return Err(ClError::Wrapper(WrapperError::PlatformsNotFound));
+    }
+
+    set_kernel_args(launch.kernel, launch.kernel_args)?;
+
+    if let Some(device_index) = launch.device_index {
+        return launch_on_device(async_executor, launch, device_index, wait_list, report).await;
+    }
+
+    let global_work_dims = launch.global_work_dims;
+    let global_work_offset = launch.global_work_offset;
+    let work_dim = work_dim(global_work_dims, global_work_offset);
+    let total_work = global_work_dims[0];
+    let total_weight: u64 = async_executor.weights.iter().sum();
+
+    let mem_objects: Vec<*mut c_void> = if launch.pre_migrate {
+        launch.kernel_args.iter().filter_map(|arg| arg.mem_object()).collect()
+    } else {
+        Vec::new()
+    };
+
+    let mut futures = Vec::new();
+    let mut current_offset = global_work_offset[0];
+
+    for i in 0..num_queues {
+        // The last device takes whatever is left to avoid rounding errors
+        let chunk_size = if i == num_queues - 1 {
+            global_work_offset[0] + total_work - current_offset
+        } else {
+            ((total_work as u128 * async_executor.weights[i] as u128) / total_weight as u128) as usize
+        };
+
+        if chunk_size == 0 {
+            continue;
+        }
+
+        let g_offset = [current_offset, global_work_offset[1], global_work_offset[2]][..work_dim].to_vec();
+        let g_dims = [chunk_size, global_work_dims[1], global_work_dims[2]][..work_dim].to_vec();
+        let l_dims = match launch.local_work_dims {
+            Some(local) => local[..work_dim].to_vec(),
+            None => auto_local_work_dims(async_executor, launch.kernel, i, work_dim, chunk_size),
+        };
+
+        let kernel = launch.kernel;
+        let queue = async_executor.queues[i].clone();
+        let wait_list = wait_list.clone();
+        let mem_objects = &mem_objects;
+        futures.push(async move {
+            let mut migration = None;
+            #[cfg(feature = "CL_VERSION_1_2")]
+            let wait_list = if mem_objects.is_empty() {
+                wait_list
+            } else {
+                let event = queue.enqueue_migrate_mem_objects(mem_objects, 0, wait_list).await?;
+                migration = Some(event.clone());
+                Some(vec![event])
+            };
+            #[cfg(not(feature = "CL_VERSION_1_2"))]
+            let _ = mem_objects;
+
+            let event = queue
+                .enqueue_nd_range_kernel(kernel, work_dim as u32, g_offset, g_dims, l_dims, None, wait_list)
+                .await?;
+            Ok::<(usize, Option<ClEvent>, ClEvent), ClError>((i, migration, event))
+        });
+
+        current_offset += chunk_size;
+    }
+
+    let mut events = Vec::new();
+    let mut report = report;
+    for res in futures::future::join_all(futures).await {
+        let (device_index, migration, event) = res?;
+        if let Some(report) = report.as_deref_mut() {
+            if let Some(migration) = migration {
+                report.push_migration_event(migration, device_index);
+            }
+            report.push_kernel_event(event.clone(), device_index);
+        }
+        events.push(event);
+    }
+
+    Ok(events)
+}
+
+/// Moves the memory objects the kernel consumes to `device_index`, then enqueues the
+/// whole range there. The migration waits on `wait_list`, which may hold events from
+/// other devices' queues, and the kernel waits on the migration.
+async fn launch_on_device(
+    async_executor: &AsyncExecutor,
+    launch: &Launch<'_, '_>,
+    device_index: usize,
+    wait_list: Option<Vec<ClEvent>>,
+    report: Option<&mut TaskReport>,
+) -> Result<Vec<ClEvent>, ClError> {
+    let queue = async_executor
+        .queues
+        .get(device_index)
+        .ok_or(ClError::Wrapper(WrapperError::DeviceIndexOutOfRange))?;
+
+    let mut report = report;
+    #[cfg(feature = "CL_VERSION_1_2")]
+    let wait_list = {
+        let mem_objects: Vec<*mut c_void> = launch.kernel_args.iter().filter_map(|arg| arg.mem_object()).collect();
+        if mem_objects.is_empty() {
+            wait_list
+        } else {
+            let event = queue.enqueue_migrate_mem_objects(&mem_objects, 0, wait_list).await?;
+            if let Some(report) = report.as_deref_mut() {
+                report.push_migration_event(event.clone(), device_index);
+            }
+            Some(vec![event])
+        }
+    };
+
+    let work_dim = work_dim(launch.global_work_dims, launch.global_work_offset);
+    let g_offset = launch.global_work_offset[..work_dim].to_vec();
+    let g_dims = launch.global_work_dims[..work_dim].to_vec();
+    let l_dims = match launch.local_work_dims {
+        Some(local) => local[..work_dim].to_vec(),
+        None => auto_local_work_dims(async_executor, launch.kernel, device_index, work_dim, launch.global_work_dims[0]),
+    };
+
+    let event = queue
+        .enqueue_nd_range_kernel(launch.kernel, work_dim as u32, g_offset, g_dims, l_dims, None, wait_list)
+        .await?;
+    if let Some(report) = report {
+        report.push_kernel_event(event.clone(), device_index);
+    }
+    Ok(vec![event])
+}
+
+/// Checks that every output fits its host slice, so nothing is enqueued if one doesn't.
+pub(crate) fn check_output_reads(reads: &[OutputRead<'_>]) -> Result<(), ClError> {
+    reads.iter().try_for_each(|read| match read {
+        #[cfg(feature = "CL_VERSION_1_2")]
+        OutputRead::Image { image, size, region, .. } => image.check_host_region(*region, *size),
+        _ => Ok(()),
+    })
+}
+
+/// Copies every output back to the host after `wait_list`, on `device_index`
+/// or, when it is `None`, on the most capable device.
+pub(crate) async fn read_outputs(
+    async_executor: &AsyncExecutor,
+    reads: &[OutputRead<'_>],
+    device_index: Option<usize>,
+    wait_list: Option<Vec<ClEvent>>,
+    report: Option<&mut TaskReport>,
+) -> Result<Vec<ClEvent>, ClError> {
+    if reads.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let read_device = device_index.unwrap_or_else(|| async_executor.get_optimal_queue_index());
+    let queue = async_executor
+        .queues
+        .get(read_device)
+        .ok_or(ClError::Wrapper(WrapperError::DeviceIndexOutOfRange))?;
+
+    let mut events = Vec::new();
+    let mut report = report;
+    for read in reads {
+        let event = match read {
+            OutputRead::Buffer { buffer, host_ptr, size } => {
+                queue.enqueue_read_buffer_raw(buffer, None, *host_ptr, *size, wait_list.clone()).await?
+            }
+            #[cfg(feature = "CL_VERSION_1_2")]
+            OutputRead::Image { image, host_ptr, origin, region, .. } => {
+                queue.read_image_raw(image, *origin, *region, 0, 0, *host_ptr, wait_list.clone()).await?
+            }
+        };
+        if let Some(report) = report.as_deref_mut() {
+            report.push_read_event(event.clone(), read_device);
+        }
+        events.push(event);
+    }
+
+    Ok(events)
+}
@@ -0,0 +1,171 @@
+use std::os::raw::c_void;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::{
+    cl_types::cl_device::ClDevice,
+    error::{ClError, wrapper_error::WrapperError},
+};
+
+/// # MemoryUsage
+///
+/// Snapshot of the device memory allocated through an `AsyncExecutor`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryUsage {
+    /// Bytes held by live buffers and images.
+    pub in_use: usize,
+    /// Highest value `in_use` has reached.
+    pub peak: usize,
+    /// Number of live buffers and images.
+    pub live_objects: usize,
+    /// Limit set with `set_memory_budget`, if any.
+    pub budget: Option<usize>,
+    /// Largest single allocation every device of the context accepts (`CL_DEVICE_MAX_MEM_ALLOC_SIZE`).
+    pub max_alloc: usize,
+}
+
+struct TrackerState {
+    in_use: AtomicUsize,
+    peak: AtomicUsize,
+    live_objects: AtomicUsize,
+    /// `usize::MAX` when there is no budget.
+    budget: AtomicUsize,
+    max_alloc: usize,
+}
+
+impl TrackerState {
+    fn release(&self, size: usize) {
+        self.in_use.fetch_sub(size, Ordering::AcqRel);
+    }
+}
+
+/// # MemoryTracker
+///
+/// Counts the bytes of every memory object the executor creates and enforces an
+/// optional budget.
+///
+/// Bytes are reserved before an allocation, so oversized or over-budget requests fail
+/// without reaching the driver, and given back by a `clSetMemObjectDestructorCallback`
+/// once OpenCL actually frees the object (i.e. after its last clone and sub-buffer are dropped).
+/// SVM allocations have no such callback and give their bytes back when they are freed.
+/// Image sizes are computed from their description and format, ignoring driver padding.
+/// Only objects created through the executor are counted, not those created directly
+/// with `ClBuffer::new` or `ClImage::new`.
+#[derive(Clone)]
+pub struct MemoryTracker {
+    state: Arc<TrackerState>,
+}
+
+impl MemoryTracker {
+    /// Tracker whose `max_alloc` is the smallest `CL_DEVICE_MAX_MEM_ALLOC_SIZE` among `devices`.
+    pub(crate) fn new(devices: &[ClDevice]) -> Self {
+        let max_alloc = devices
+            .iter()
+            .filter_map(|device| device.get_max_mem_alloc_size().ok())
+            .min()
+            .map_or(usize::MAX, |size| usize::try_from(size).unwrap_or(usize::MAX));
+        let state = TrackerState {
+            in_use: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+            live_objects: AtomicUsize::new(0),
+            budget: AtomicUsize::new(usize::MAX),
+            max_alloc,
+        };
+        Self { state: Arc::new(state) }
+    }
+
+    pub fn usage(&self) -> MemoryUsage {
+        let budget = self.state.budget.load(Ordering::Acquire);
+        MemoryUsage {
+            in_use: self.state.in_use.load(Ordering::Acquire),
+            peak: self.state.peak.load(Ordering::Acquire),
+            live_objects: self.state.live_objects.load(Ordering::Acquire),
+            budget: (budget != usize::MAX).then_some(budget),
+            max_alloc: self.state.max_alloc,
+        }
+    }
+
+    /// Sets or removes the budget. Memory already in use is never reclaimed;
+    /// a lower budget only makes new allocations fail.
+    pub fn set_budget(&self, budget: Option<usize>) {
+        self.state.budget.store(budget.unwrap_or(usize::MAX), Ordering::Release);
+    }
+
+    /// Counts `size` bytes as in use, or fails if they exceed `max_alloc` or the budget.
+    pub(crate) fn reserve(&self, size: usize) -> Result<Reservation, ClError> {
+        let max_alloc = self.state.max_alloc;
+        if size > max_alloc {
+            let in_use = self.state.in_use.load(Ordering::Acquire);
+            return Err(ClError::Wrapper(WrapperError::AllocationTooLarge { requested: size, max_alloc, in_use }));
+        }
+
+        let budget = self.state.budget.load(Ordering::Acquire);
+        let in_use = self
+            .state
+            .in_use
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |in_use| {
+                in_use.checked_add(size).filter(|total| *total <= budget)
+            })
+            .map_err(|in_use| ClError::Wrapper(WrapperError::MemoryBudgetExceeded { requested: size, in_use, budget }))?;
+        self.state.peak.fetch_max(in_use + size, Ordering::AcqRel);
+
+        Ok(Reservation { state: Some(self.state.clone()), size, held: false })
+    }
+}
+
+/// Bytes counted as in use for an allocation in progress.
+/// Dropping it without `attach` (e.g. because creation failed) gives them back.
+pub(crate) struct Reservation {
+    state: Option<Arc<TrackerState>>,
+    size: usize,
+    /// Counted as a live object, see `hold`.
+    held: bool,
+}
+
+impl Reservation {
+    /// Keeps the bytes counted until OpenCL destroys `mem`.
+    pub(crate) fn attach(mut self, mem: *mut c_void) {
+        let state = self.state.take().unwrap();
+        let user_data = Box::into_raw(Box::new((state.clone(), self.size)));
+        let registered = unsafe {
+            cl3::memory::set_mem_object_destructor_callback(mem, release_on_destroy, user_data as *mut c_void)
+        };
+        match registered {
+            Ok(()) => {
+                state.live_objects.fetch_add(1, Ordering::AcqRel);
+            }
+            Err(_) => {
+                // Untracked from now on
+                drop(unsafe { Box::from_raw(user_data) });
+                state.release(self.size);
+            }
+        }
+    }
+
+    /// Keeps the bytes counted, as a live object, until the reservation is dropped. For
+    /// allocations OpenCL has no destructor callback for, which keep it alongside them (SVM).
+    pub(crate) fn hold(mut self) -> Self {
+        if let Some(state) = &self.state {
+            state.live_objects.fetch_add(1, Ordering::AcqRel);
+        }
+        self.held = true;
+        self
+    }
+}
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        if let Some(state) = self.state.take() {
+            state.release(self.size);
+            if self.held {
+                state.live_objects.fetch_sub(1, Ordering::AcqRel);
+            }
+        }
+    }
+}
+
+extern "C" fn release_on_destroy(_mem: *mut c_void, user_data: *mut c_void) {
+    let (state, size) = *unsafe { Box::from_raw(user_data as *mut (Arc<TrackerState>, usize)) };
+    state.release(size);
+    state.live_objects.fetch_sub(1, Ordering::AcqRel);
+}
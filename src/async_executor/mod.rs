@@ -1,13 +1,28 @@
 pub mod task_builder;
 pub mod kernel_arg;
 pub mod pipeline_task;
+pub mod profiling;
+mod launch;
+pub mod buffer_pool;
+pub mod memory_tracker;
+pub mod stream_task;
+mod program_cache;
+#[cfg(feature = "CL_VERSION_1_2")]
+pub mod image_ops;
+#[cfg(feature = "pipeline_config")]
+pub mod pipeline_config;
 use std::os::raw::c_void;
 use std::sync::Arc;
 
 use crate::{
-    async_executor::{task_builder::TaskBuilder, pipeline_task::PipelineBuilder}, 
+    async_executor::{
+        task_builder::TaskBuilder, pipeline_task::PipelineBuilder, stream_task::StreamBuilder,
+        buffer_pool::{BufferPool, BufferPoolConfig, PooledBuffer},
+        memory_tracker::{MemoryTracker, MemoryUsage, Reservation},
+        program_cache::ProgramCache,
+    }, 
     cl_types::{
-        cl_buffer::ClBuffer,
+        cl_buffer::{ClBuffer, typed_buffer::{TypedBuffer, elements_size}, host_buffer::HostBuffer, buffer_map::{BufferMapGuard, BufferMapGuardMut, MapMode}},
         cl_event::ClEvent,
         cl_command_queue::{ClCommandQueue, command_queue_parameters::{CommandQueueProperties, Version20}},
         cl_context::ClContext, 
@@ -17,11 +32,22 @@ use crate::{
         cl_device::opencl_version::OpenCLVersion,
         cl_program::{ClProgram, Builded, NotBuilded, program_parameters::ProgramParameters},
         cl_image::{ClImage, image_desc::ClImageDesc, image_formats::ClImageFormats},
-        cl_svm_buffer::ClSvmBuffer,
+        cl_svm_buffer::{ClSvmBuffer, svm_mode::SvmMode},
         memory_flags::MemoryFlags,
+        mem_object::{MemObject, MigrationFlags},
     }, 
     error::ClError
 };
+#[cfg(all(feature = "image", feature = "CL_VERSION_1_2"))]
+use crate::cl_types::cl_image::image_interop::ClPixel;
+#[cfg(feature = "CL_VERSION_1_2")]
+use crate::cl_types::cl_command_queue::fill_color::FillColor;
+#[cfg(feature = "CL_VERSION_1_1")]
+use crate::cl_types::cl_image::{format_negotiation::{NegotiatedFormat, PixelLayout}, image_type::ClImageType};
+#[cfg(feature = "CL_VERSION_1_2")]
+use crate::async_executor::image_ops::{ColorConversion, ResizeFilter};
+#[cfg(feature = "CL_VERSION_1_2")]
+use crate::cl_types::cl_image::{pixel::ImagePixel, typed_image::{ClImage2D, ClImage2DArray, ClImage3D}};
 
 /// # AsyncExecutor
 /// 
@@ -38,6 +64,9 @@ pub struct AsyncExecutor {
     profiling_enabled: bool,
     device_versions: Vec<OpenCLVersion>,
     devices: Vec<ClDevice>,
+    buffer_pool: Option<BufferPool>,
+    memory: MemoryTracker,
+    programs: ProgramCache,
 }
 
 #[cfg(feature = "CL_VERSION_1_1")]
@@ -96,26 +125,7 @@ impl AsyncExecutor {
 
         for device in &devices {
             let version = device.get_opencl_version();
-            let mut supports_out_of_order = false;
-
-            let queue = if version >= OpenCLVersion::V2_0 {
-                if let Ok(host_props) = device.get_queue_on_host_properties() {
-                    supports_out_of_order = (host_props as u64 & cl3::command_queue::CL_QUEUE_OUT_OF_ORDER_EXEC_MODE_ENABLE) != 0;
-                }
-                
-                let properties = CommandQueueProperties::<Version20>::new()
-                    .set_cl_queue_properties(supports_out_of_order, profiling_enabled, false, false)
-                    .get_properties();
-                ClCommandQueue::create_command_queue_with_properties(&context, device, &properties)?
-            } else {
-                let mut properties = 0;
-                if profiling_enabled {
-                    properties |= cl3::command_queue::CL_QUEUE_PROFILING_ENABLE;
-                }
-                #[allow(deprecated)]
-                ClCommandQueue::create_command_queue(&context, device, properties)?
-            };
-            
+            let queue = Self::create_device_queue(&context, device, profiling_enabled, true)?;
             queues.push(queue);
             weights.push(Self::measure_device_capacity(device)?);
             device_versions.push(version);
@@ -127,7 +137,10 @@ impl AsyncExecutor {
             weights,
             device_versions,
             profiling_enabled,
+            memory: MemoryTracker::new(&devices),
             devices: devices.into_iter().map(|d| d.clone()).collect(),
+            buffer_pool: None,
+            programs: ProgramCache::default(),
         };
 
         Ok(executor)
@@ -160,26 +173,7 @@ impl AsyncExecutor {
 
         for device in devices {
             let version = device.get_opencl_version();
-            let mut supports_out_of_order = false;
-
-            let queue = if version >= OpenCLVersion::V2_0 {
-                if let Ok(host_props) = device.get_queue_on_host_properties() {
-                    supports_out_of_order = (host_props as u64 & cl3::command_queue::CL_QUEUE_OUT_OF_ORDER_EXEC_MODE_ENABLE) != 0;
-                }
-
-                let properties = CommandQueueProperties::<Version20>::new()
-                    .set_cl_queue_properties(supports_out_of_order, profiling_enabled, false, false)
-                    .get_properties();
-                ClCommandQueue::create_command_queue_with_properties(&context, device, &properties)?
-            } else {
-                let mut properties = 0;
-                if profiling_enabled {
-                    properties |= cl3::command_queue::CL_QUEUE_PROFILING_ENABLE;
-                }
-                #[allow(deprecated)]
-                ClCommandQueue::create_command_queue(&context, device, properties)?
-            };
-
+            let queue = Self::create_device_queue(&context, device, profiling_enabled, true)?;
             queues.push(queue);
             weights.push(Self::measure_device_capacity(device)?);
             device_versions.push(version);
@@ -191,10 +185,40 @@ impl AsyncExecutor {
             weights,
             device_versions,
             profiling_enabled,
+            memory: MemoryTracker::new(&devices_vec),
             devices: devices_vec,
+            buffer_pool: None,
+            programs: ProgramCache::default(),
         })
     }
 
+    /// Enables buffer pooling: `acquire_buffer` recycles buffers by size class and flags.
+    pub fn with_buffer_pool(mut self, config: BufferPoolConfig) -> Self {
+        self.buffer_pool = Some(BufferPool::new(self.context.clone(), config).with_tracker(self.memory.clone()));
+        self
+    }
+
+    /// The pool enabled with `with_buffer_pool`, for statistics and trimming.
+    pub fn buffer_pool(&self) -> Option<&BufferPool> {
+        self.buffer_pool.as_ref()
+    }
+
+    /// Makes allocations fail with `MemoryBudgetExceeded` once `bytes` are in use.
+    pub fn with_memory_budget(self, bytes: usize) -> Self {
+        self.memory.set_budget(Some(bytes));
+        self
+    }
+
+    /// Sets or removes the memory budget.
+    pub fn set_memory_budget(&self, bytes: Option<usize>) {
+        self.memory.set_budget(bytes);
+    }
+
+    /// Bytes held by the buffers and images created through this executor (pooled ones included).
+    pub fn memory_usage(&self) -> MemoryUsage {
+        self.memory.usage()
+    }
+
     pub fn is_profiling_enabled(&self) -> bool {
         self.profiling_enabled
     }
@@ -215,6 +239,17 @@ impl AsyncExecutor {
         &self.queues
     }
 
+    /// Creates an additional in-order command queue on the device at `device_index`.
+    ///
+    /// Useful when independent streams of work (e.g. uploads, kernels and downloads)
+    /// must be able to overlap on the same device.
+    pub fn create_queue(&self, device_index: usize) -> Result<ClCommandQueue, ClError> {
+        let device = self.devices.get(device_index).ok_or(ClError::Wrapper(
+            crate::error::wrapper_error::WrapperError::DeviceIndexOutOfRange,
+        ))?;
+        Self::create_device_queue(&self.context, device, self.profiling_enabled, false)
+    }
+
     //
     // Engine (Self)
     //
@@ -236,6 +271,23 @@ impl AsyncExecutor {
         PipelineBuilder::new(self)
     }
 
+    /// Creates a double-buffered stream that applies a kernel to every chunk of host data.
+    ///
+    /// Uploads, kernel launches and downloads run on separate queues so consecutive
+    /// chunks overlap. See [`StreamBuilder`] for the configuration options.
+    pub fn create_stream<'a>(&'a self, kernel: &'a ClKernel) -> StreamBuilder<'a> {
+        StreamBuilder::new(self, kernel)
+    }
+
+    /// Loads a pipeline described in a `.toml` or `.json` file and creates its
+    /// programs, kernels and buffers on this executor.
+    ///
+    /// See [`pipeline_config::PipelineDefinition`] for the document format.
+    #[cfg(feature = "pipeline_config")]
+    pub fn load_pipeline(&self, path: impl AsRef<std::path::Path>) -> Result<pipeline_config::PipelineInstance<'_>, ClError> {
+        pipeline_config::PipelineDefinition::from_file(path)?.instantiate(self)
+    }
+
     //
     // Facade Methods (Simplifican la creación de recursos)
     //
@@ -350,8 +402,59 @@ impl AsyncExecutor {
     /// Creates a memory Buffer on the GPU.
     /// 
     /// Buffers are "boxes" of data that the GPU can read or write.
+    /// Fails early if `size` exceeds the devices' max allocation size or the memory budget.
     pub fn create_buffer(&self, flags: &[MemoryFlags], size: usize, host_ptr: *mut c_void) -> Result<ClBuffer, ClError> {
-        ClBuffer::new(&self.context, &flags.to_vec(), size, host_ptr)
+        let reservation = self.memory.reserve(size)?;
+        let buffer = ClBuffer::new(&self.context, &flags.to_vec(), size, host_ptr)?;
+        reservation.attach(buffer.as_ptr());
+        Ok(buffer)
+    }
+
+    /// Gets a buffer of at least `size` bytes from the buffer pool, which takes it back
+    /// when the returned `PooledBuffer` is dropped.
+    /// Without `with_buffer_pool` this creates a buffer that is simply released on drop.
+    pub fn acquire_buffer(&self, flags: &[MemoryFlags], size: usize) -> Result<PooledBuffer, ClError> {
+        match &self.buffer_pool {
+            Some(pool) => pool.acquire(flags, size),
+            None => Ok(PooledBuffer::unpooled(self.create_buffer(flags, size, std::ptr::null_mut())?, size)),
+        }
+    }
+
+    /// Creates an uninitialized typed buffer of `len` elements of `T`.
+    /// Its transfers run on the most powerful GPU available.
+    pub fn create_typed_buffer<T: Copy>(&self, flags: &[MemoryFlags], len: usize) -> Result<TypedBuffer<T>, ClError> {
+        let reservation = self.memory.reserve(elements_size::<T>(len)?)?;
+        let buffer = TypedBuffer::new(&self.context, self.get_optimal_queue(), flags, len)?;
+        reservation.attach(buffer.as_ptr());
+        Ok(buffer)
+    }
+
+    /// Creates a typed buffer holding a copy of `data`.
+    pub fn typed_buffer_from_slice<T: Copy>(&self, flags: &[MemoryFlags], data: &[T]) -> Result<TypedBuffer<T>, ClError> {
+        let reservation = self.memory.reserve(std::mem::size_of_val(data))?;
+        let buffer = TypedBuffer::from_slice(&self.context, self.get_optimal_queue(), flags, data)?;
+        reservation.attach(buffer.as_ptr());
+        Ok(buffer)
+    }
+
+    /// Creates a buffer that uses `data` (a `Vec<T>` or `Box<[T]>`) as its storage and owns it.
+    /// The memory is freed when OpenCL destroys the buffer, or returned by `HostBuffer::into_vec`.
+    pub fn buffer_from_vec<T: Copy + Send + 'static>(&self, flags: &[MemoryFlags], data: impl Into<Vec<T>>) -> Result<HostBuffer<T>, ClError> {
+        let data: Vec<T> = data.into();
+        let reservation = self.memory.reserve(std::mem::size_of_val(data.as_slice()))?;
+        let buffer = HostBuffer::from_vec(&self.context, flags, data)?;
+        reservation.attach(buffer.as_ptr());
+        Ok(buffer)
+    }
+
+    /// Creates a read-only buffer that uses shared host data as its storage,
+    /// keeping it alive until OpenCL destroys the buffer. The host can only read it too.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub fn buffer_from_arc<T: Copy + Send + Sync + 'static>(&self, flags: &[MemoryFlags], data: std::sync::Arc<[T]>) -> Result<HostBuffer<T>, ClError> {
+        let reservation = self.memory.reserve(std::mem::size_of_val(&*data))?;
+        let buffer = HostBuffer::from_arc(&self.context, flags, data)?;
+        reservation.attach(buffer.as_ptr());
+        Ok(buffer)
     }
 
     /// Creates an OpenCL Image (requires OpenCL 1.2+).
@@ -364,14 +467,489 @@ impl AsyncExecutor {
         desc: &ClImageDesc, 
         host_ptr: *mut c_void
     ) -> Result<ClImage, ClError> {
-        ClImage::new(&self.context, &flags.to_vec(), format, desc, host_ptr)
+        let reservation = self.reserve_image(format, desc)?;
+        let image = ClImage::new(&self.context, &flags.to_vec(), format, desc, host_ptr)?;
+        reservation.attach(image.as_ptr());
+        Ok(image)
+    }
+
+    /// Reserves the bytes of an image of `format` and `desc` before it is created.
+    /// Fails with `InvalidImageFormat` if the format's pixel size is unknown.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    fn reserve_image(&self, format: &ClImageFormats, desc: &ClImageDesc) -> Result<Reservation, ClError> {
+        use crate::error::wrapper_error::WrapperError;
+
+        let bytes_per_pixel = format.bytes_per_pixel().ok_or(ClError::Wrapper(WrapperError::InvalidImageFormat {
+            channel_order: format.image_channel_order,
+            channel_type: format.image_channel_data_type,
+        }))?;
+        self.memory.reserve(desc.byte_size(bytes_per_pixel))
+    }
+
+    /// Reserves the bytes of a typed image of `size` pixels of `P` before it is created.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    fn reserve_pixels<P: ImagePixel>(&self, size: [usize; 3]) -> Result<Reservation, ClError> {
+        self.memory.reserve(size.iter().fold(std::mem::size_of::<P>(), |bytes, dim| bytes.saturating_mul(*dim)))
+    }
+
+    /// Creates an `Image1DBuffer` of `width` pixels over the memory of `buffer`, without copying.
+    /// See [`ClImage::from_buffer_1d`].
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub fn image_from_buffer_1d(&self, flags: &[MemoryFlags], format: &ClImageFormats, buffer: &ClBuffer, width: usize) -> Result<ClImage, ClError> {
+        ClImage::from_buffer_1d(&self.context, flags, format, buffer, width)
+    }
+
+    /// Creates a 2D image over the memory of `buffer`, without copying.
+    /// See [`ClImage::from_buffer_2d`].
+    #[cfg(feature = "CL_VERSION_2_0")]
+    pub fn image_from_buffer_2d(
+        &self,
+        flags: &[MemoryFlags],
+        format: &ClImageFormats,
+        buffer: &ClBuffer,
+        width: usize,
+        height: usize,
+        row_pitch: Option<usize>,
+    ) -> Result<ClImage, ClError> {
+        ClImage::from_buffer_2d(&self.context, flags, format, buffer, width, height, row_pitch)
+    }
+
+    /// Creates an uninitialized 2D image of `P` pixels (e.g. `Rgba<u8>`).
+    /// Its transfers run on the most powerful GPU available.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub fn create_image_2d<P: ImagePixel>(&self, flags: &[MemoryFlags], width: usize, height: usize) -> Result<ClImage2D<P>, ClError> {
+        let reservation = self.reserve_pixels::<P>([width, height, 1])?;
+        let image = ClImage2D::new(&self.context, self.get_optimal_queue(), flags, width, height)?;
+        reservation.attach(image.as_ptr());
+        Ok(image)
+    }
+
+    /// Creates a 2D image holding a copy of `pixels`, row after row.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub fn image_2d_from_pixels<P: ImagePixel>(&self, flags: &[MemoryFlags], width: usize, height: usize, pixels: &[P]) -> Result<ClImage2D<P>, ClError> {
+        let reservation = self.reserve_pixels::<P>([width, height, 1])?;
+        let image = ClImage2D::from_pixels(&self.context, self.get_optimal_queue(), flags, width, height, pixels)?;
+        reservation.attach(image.as_ptr());
+        Ok(image)
+    }
+
+    /// Creates an uninitialized 3D image of `P` pixels, `size` being `[width, height, depth]`.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub fn create_image_3d<P: ImagePixel>(&self, flags: &[MemoryFlags], size: [usize; 3]) -> Result<ClImage3D<P>, ClError> {
+        let reservation = self.reserve_pixels::<P>(size)?;
+        let image = ClImage3D::new(&self.context, self.get_optimal_queue(), flags, size)?;
+        reservation.attach(image.as_ptr());
+        Ok(image)
+    }
+
+    /// Creates a 3D image holding a copy of `pixels`, slice after slice.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub fn image_3d_from_pixels<P: ImagePixel>(&self, flags: &[MemoryFlags], size: [usize; 3], pixels: &[P]) -> Result<ClImage3D<P>, ClError> {
+        let reservation = self.reserve_pixels::<P>(size)?;
+        let image = ClImage3D::from_pixels(&self.context, self.get_optimal_queue(), flags, size, pixels)?;
+        reservation.attach(image.as_ptr());
+        Ok(image)
+    }
+
+    /// Creates `layers` uninitialized 2D images of `P` pixels as one image array.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub fn create_image_2d_array<P: ImagePixel>(
+        &self,
+        flags: &[MemoryFlags],
+        width: usize,
+        height: usize,
+        layers: usize,
+    ) -> Result<ClImage2DArray<P>, ClError> {
+        let reservation = self.reserve_pixels::<P>([width, height, layers])?;
+        let image = ClImage2DArray::new(&self.context, self.get_optimal_queue(), flags, width, height, layers)?;
+        reservation.attach(image.as_ptr());
+        Ok(image)
+    }
+
+    /// Creates an image array holding a copy of `pixels`, layer after layer.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub fn image_2d_array_from_pixels<P: ImagePixel>(
+        &self,
+        flags: &[MemoryFlags],
+        width: usize,
+        height: usize,
+        layers: usize,
+        pixels: &[P],
+    ) -> Result<ClImage2DArray<P>, ClError> {
+        let reservation = self.reserve_pixels::<P>([width, height, layers])?;
+        let image = ClImage2DArray::from_pixels(&self.context, self.get_optimal_queue(), flags, width, height, layers, pixels)?;
+        reservation.attach(image.as_ptr());
+        Ok(image)
+    }
+
+    /// Picks the image format the context supports for pixels in `layout`, and reports
+    /// whether host data needs swizzling or conversion to use it.
+    #[cfg(feature = "CL_VERSION_1_1")]
+    pub fn negotiate_image_format(&self, flags: &[MemoryFlags], image_type: ClImageType, layout: PixelLayout) -> Result<NegotiatedFormat, ClError> {
+        ClImage::negotiate_format(&self.context, flags, image_type, layout)
+    }
+
+    /// Creates a 2D image holding a copy of `image`, in the pixel's own format when the
+    /// devices support it and RGBA otherwise.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let photo = image::open("photo.png")?.to_rgba8();
+    /// let cl_image = executor.upload_image(&[MemoryFlags::ReadOnly], &photo)?;
+    /// ```
+    #[cfg(all(feature = "image", feature = "CL_VERSION_1_2"))]
+    pub fn upload_image<P: ClPixel>(
+        &self,
+        flags: &[MemoryFlags],
+        image: &image::ImageBuffer<P, Vec<P::Subpixel>>,
+    ) -> Result<ClImage, ClError> {
+        let desc = ClImageDesc {
+            image_type: ClImageType::Image2D,
+            image_width: Some(image.width() as usize),
+            image_height: Some(image.height() as usize),
+            ..Default::default()
+        };
+        let reservation = self.reserve_image(&ClImage::image_buffer_format::<P>(&self.context, flags)?, &desc)?;
+        let image = ClImage::from_image_buffer(&self.context, flags, image)?;
+        reservation.attach(image.as_ptr());
+        Ok(image)
+    }
+
+    /// Like `upload_image`, for any `DynamicImage` variant.
+    #[cfg(all(feature = "image", feature = "CL_VERSION_1_2"))]
+    pub fn upload_dynamic_image(&self, flags: &[MemoryFlags], image: &image::DynamicImage) -> Result<ClImage, ClError> {
+        use image::DynamicImage;
+
+        // Same dispatch as `ClImage::from_dynamic_image`, so each variant is reserved in its format
+        match image {
+            DynamicImage::ImageLuma8(b) => self.upload_image(flags, b),
+            DynamicImage::ImageLumaA8(b) => self.upload_image(flags, b),
+            DynamicImage::ImageRgb8(b) => self.upload_image(flags, b),
+            DynamicImage::ImageRgba8(b) => self.upload_image(flags, b),
+            DynamicImage::ImageLuma16(b) => self.upload_image(flags, b),
+            DynamicImage::ImageLumaA16(b) => self.upload_image(flags, b),
+            DynamicImage::ImageRgb16(b) => self.upload_image(flags, b),
+            DynamicImage::ImageRgba16(b) => self.upload_image(flags, b),
+            DynamicImage::ImageRgb32F(b) => self.upload_image(flags, b),
+            DynamicImage::ImageRgba32F(b) => self.upload_image(flags, b),
+            other => self.upload_image(flags, &other.to_rgba32f()),
+        }
+    }
+
+    /// Reads a whole 2D image back into an `ImageBuffer` of `P`, converting from RGBA if needed.
+    #[cfg(all(feature = "image", feature = "CL_VERSION_1_2"))]
+    pub async fn download_image<P: ClPixel>(&self, image: &ClImage) -> Result<image::ImageBuffer<P, Vec<P::Subpixel>>, ClError> {
+        image.to_image_buffer(self.get_optimal_queue()).await
+    }
+
+    /// Reads a whole 2D image back into the `DynamicImage` variant matching its format.
+    #[cfg(all(feature = "image", feature = "CL_VERSION_1_2"))]
+    pub async fn download_dynamic_image(&self, image: &ClImage) -> Result<image::DynamicImage, ClError> {
+        image.to_dynamic_image(self.get_optimal_queue()).await
+    }
+
+    /// Reads the whole of mip level `level` into `host_memory`, tightly packed.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub async fn read_image_level<T: Sized>(&self, image: &ClImage, level: u32, host_memory: &mut [T]) -> Result<ClEvent, ClError> {
+        let region = image.mip_level_region(level)?;
+        image.check_host_region(region, std::mem::size_of_val(host_memory))?;
+        let queue = self.get_optimal_queue();
+        queue.read_image_level_raw(image, level, [0, 0, 0], region, 0, 0, host_memory.as_mut_ptr() as *mut c_void, None).await
+    }
+
+    /// Writes the whole of mip level `level` from tightly packed `host_memory`.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub async fn write_image_level<T: Sized>(&self, image: &ClImage, level: u32, host_memory: &[T]) -> Result<ClEvent, ClError> {
+        let region = image.mip_level_region(level)?;
+        image.check_host_region(region, std::mem::size_of_val(host_memory))?;
+        let queue = self.get_optimal_queue();
+        queue.write_image_level_raw(image, level, [0, 0, 0], region, 0, 0, host_memory.as_ptr() as *const c_void, None).await
+    }
+
+    /// Copies mip level `level` into a new single-level image, e.g. to bind that level
+    /// alone to a kernel.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub async fn extract_mip_level(&self, image: &ClImage, level: u32) -> Result<ClImage, ClError> {
+        let region = image.mip_level_region(level)?;
+        let flags = image.get_flags()?.into_iter().filter(|flag| !matches!(flag, MemoryFlags::UseHostPtr | MemoryFlags::CopyHostPtr)).collect::<Vec<_>>();
+        let level_image = self.create_image(&flags, &image.get_image_format()?, &image.mip_level_desc(level)?, std::ptr::null_mut())?;
+        let queue = self.get_optimal_queue();
+        queue.copy_image_levels(image, level, &level_image, 0, [0, 0, 0], [0, 0, 0], region, None).await?;
+        Ok(level_image)
+    }
+
+    /// Fills mip levels 1.. of `image` from level 0.
+    ///
+    /// Uses `cl_img_generate_mipmap` when the feature is enabled and the device reports it.
+    /// Otherwise each level is averaged from the previous one with a built-in 2x2 box
+    /// kernel, which requires a 2D image with normalized or float channels.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub async fn generate_mipmaps(&self, image: &ClImage) -> Result<(), ClError> {
+        use crate::cl_types::{cl_image::mipmap::DOWNSAMPLE_SOURCE, mem_object_type::MemObjectType};
+
+        let levels = image.get_num_mip_levels()?;
+        if levels <= 1 {
+            return Ok(());
+        }
+        let queue_index = self.get_optimal_queue_index();
+        let queue = &self.queues[queue_index];
+
+        #[cfg(feature = "cl_img_generate_mipmap")]
+        if self.devices[queue_index].get_extensions()?.split_whitespace().any(|ext| ext == "cl_img_generate_mipmap") {
+            queue.generate_mipmap_img(image, None).await?;
+            return Ok(());
+        }
+
+        let format = image.get_image_format()?;
+        if !format.image_channel_data_type.reads_as_float() || image.get_mem_type()? != MemObjectType::Image2D {
+            return Err(ClError::Wrapper(crate::error::wrapper_error::WrapperError::MipmapGenerationUnsupported));
+        }
+
+        let program = self.programs.get_or_build("downsample", || self.build_program(DOWNSAMPLE_SOURCE.to_string(), None))?;
+        let kernel = self.create_kernel(&program, "downsample_2x2")?;
+        // Levels can't be bound on their own, so each one goes through a single-level image
+        let flags = vec![MemoryFlags::ReadWrite];
+        let mut previous = self.create_image(&flags, &format, &image.mip_level_desc(0)?, std::ptr::null_mut())?;
+        queue.copy_image_levels(image, 0, &previous, 0, [0, 0, 0], [0, 0, 0], image.mip_level_region(0)?, None).await?;
+        for level in 1..levels {
+            let region = image.mip_level_region(level)?;
+            let next = self.create_image(&flags, &format, &image.mip_level_desc(level)?, std::ptr::null_mut())?;
+            self.create_task(&kernel)
+                .arg_image(0, &previous)
+                .arg_image(1, &next)
+                .global_work_dims(region[0], region[1], 1)
+                .on_device(queue_index)
+                .run()
+                .await?;
+            queue.copy_image_levels(&next, 0, image, level, [0, 0, 0], [0, 0, 0], region, None).await?;
+            previous = next;
+        }
+        Ok(())
+    }
+
+    /// Resizes a 2D image to `width` x `height`. Returns a new image of the same format.
+    ///
+    /// Like every built-in image operation, it needs normalized or float channels
+    /// (`UnsupportedImageOperation` otherwise), and its kernels are built once per executor.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub async fn resize_image(&self, image: &ClImage, width: usize, height: usize, filter: ResizeFilter) -> Result<ClImage, ClError> {
+        image_ops::check_float_image_2d(image, "resize_image")?;
+        let kernel = self.image_ops_kernel(match filter {
+            ResizeFilter::Nearest => "resize_nearest",
+            ResizeFilter::Bilinear => "resize_bilinear",
+        })?;
+        let output = self.image_ops_output(image, width, height)?;
+        self.create_task(&kernel)
+            .arg_image(0, image)
+            .arg_image(1, &output)
+            .global_work_dims(width, height, 1)
+            .run()
+            .await?;
+        Ok(output)
+    }
+
+    /// Blurs a 2D image with a Gaussian of standard deviation `sigma` (in pixels), as two
+    /// separable passes. Edges are clamped. The kernel is at most `2 * 128 + 1` taps long, so
+    /// a `sigma` above about 42 is truncated. Fails with `InvalidBlurSigma` if `sigma` is NaN or infinite.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub async fn gaussian_blur(&self, image: &ClImage, sigma: f32) -> Result<ClImage, ClError> {
+        image_ops::check_float_image_2d(image, "gaussian_blur")?;
+        let (width, height) = (image.get_width()?, image.get_height()?);
+        let weights = image_ops::gaussian_weights(sigma)?;
+        let radius = (weights.len() / 2) as i32;
+        let weights = self.typed_buffer_from_slice(&[MemoryFlags::ReadOnly], &weights)?;
+        let kernel = self.image_ops_kernel("convolve_1d")?;
+        let horizontal = self.image_ops_output(image, width, height)?;
+        let output = self.image_ops_output(image, width, height)?;
+        for (src, dst, (dx, dy)) in [(image, &horizontal, (1i32, 0i32)), (&horizontal, &output, (0, 1))] {
+            self.create_task(&kernel)
+                .arg_image(0, src)
+                .arg_image(1, dst)
+                .arg_buffer(2, &weights)
+                .arg_scalar(3, radius)
+                .arg_scalar(4, dx)
+                .arg_scalar(5, dy)
+                .global_work_dims(width, height, 1)
+                .run()
+                .await?;
+        }
+        Ok(output)
+    }
+
+    /// Sobel edge detection on the luma of a 2D image. Each pixel of the result holds the
+    /// gradient magnitude in its color channels and 1 in alpha; normalized formats clamp it to 1.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub async fn sobel(&self, image: &ClImage) -> Result<ClImage, ClError> {
+        image_ops::check_float_image_2d(image, "sobel")?;
+        let (width, height) = (image.get_width()?, image.get_height()?);
+        let kernel = self.image_ops_kernel("sobel")?;
+        let output = self.image_ops_output(image, width, height)?;
+        self.create_task(&kernel)
+            .arg_image(0, image)
+            .arg_image(1, &output)
+            .arg_scalar(2, image_ops::gray_arg(image)?)
+            .global_work_dims(width, height, 1)
+            .run()
+            .await?;
+        Ok(output)
+    }
+
+    /// Converts a 2D image with at least 3 channels between RGB, YUV and gray.
+    /// Returns a new image of the same format; alpha is kept.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub async fn convert_color(&self, image: &ClImage, conversion: ColorConversion) -> Result<ClImage, ClError> {
+        image_ops::check_float_image_2d(image, "convert_color")?;
+        if image_ops::gray_arg(image)? != 0 {
+            let format = image.get_image_format()?;
+            return Err(ClError::Wrapper(crate::error::wrapper_error::WrapperError::UnsupportedImageOperation { operation: "convert_color", format }));
+        }
+        let (width, height) = (image.get_width()?, image.get_height()?);
+        let kernel = self.image_ops_kernel(conversion.kernel_name())?;
+        let output = self.image_ops_output(image, width, height)?;
+        self.create_task(&kernel)
+            .arg_image(0, image)
+            .arg_image(1, &output)
+            .global_work_dims(width, height, 1)
+            .run()
+            .await?;
+        Ok(output)
+    }
+
+    /// Equalizes the luma histogram of a 2D image (256 levels), spreading its brightness
+    /// over the whole range. Color images keep their chroma. Values are clamped to [0, 1] first.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub async fn equalize_histogram(&self, image: &ClImage) -> Result<ClImage, ClError> {
+        image_ops::check_float_image_2d(image, "equalize_histogram")?;
+        let (width, height) = (image.get_width()?, image.get_height()?);
+        let gray = image_ops::gray_arg(image)?;
+
+        let bins = self.typed_buffer_from_slice(&[MemoryFlags::ReadWrite], &[0u32; image_ops::HISTOGRAM_BINS])?;
+        let histogram = self.image_ops_kernel("luma_histogram")?;
+        self.create_task(&histogram)
+            .arg_image(0, image)
+            .arg_buffer(1, &bins)
+            .arg_scalar(2, gray)
+            .global_work_dims(width, height, 1)
+            // Split across devices, the `atomic_inc`s of each share would race on the bins
+            .on_device(self.get_optimal_queue_index())
+            .run()
+            .await?;
+        let lut = image_ops::equalization_lut(&bins.to_vec().await?);
+        let lut = self.typed_buffer_from_slice(&[MemoryFlags::ReadOnly], &lut)?;
+
+        let kernel = self.image_ops_kernel("equalize")?;
+        let output = self.image_ops_output(image, width, height)?;
+        self.create_task(&kernel)
+            .arg_image(0, image)
+            .arg_image(1, &output)
+            .arg_buffer(2, &lut)
+            .arg_scalar(3, gray)
+            .global_work_dims(width, height, 1)
+            .run()
+            .await?;
+        Ok(output)
+    }
+
+    /// Kernel `name` of the built-in image operations, whose program is built on first use.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    fn image_ops_kernel(&self, name: &str) -> Result<ClKernel, ClError> {
+        let program = self.programs.get_or_build("image_ops", || self.build_program(image_ops::IMAGE_OPS_SOURCE.to_string(), None))?;
+        self.create_kernel(&program, name)
+    }
+
+    /// A `width` x `height` 2D image of the same format as `image`.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    fn image_ops_output(&self, image: &ClImage, width: usize, height: usize) -> Result<ClImage, ClError> {
+        let desc = ClImageDesc {
+            image_type: crate::cl_types::cl_image::image_type::ClImageType::Image2D,
+            image_width: Some(width),
+            image_height: Some(height),
+            ..Default::default()
+        };
+        self.create_image(&[MemoryFlags::ReadWrite], &image.get_image_format()?, &desc, std::ptr::null_mut())
     }
 
     /// Creates an SVM Buffer (Shared Virtual Memory). (Requires OpenCL 2.0+).
     /// Allows sharing pointers directly between CPU and GPU without manual copies.
     #[cfg(feature = "CL_VERSION_2_0")]
     pub fn create_svm_buffer<T>(&self, flags: &[MemoryFlags], len: usize) -> Result<ClSvmBuffer<T>, ClError> {
-        ClSvmBuffer::<T>::new(&self.context, &flags.to_vec(), len, 0)
+        let reservation = self.memory.reserve(elements_size::<T>(len)?)?;
+        Ok(ClSvmBuffer::<T>::new(&self.context, &flags.to_vec(), len, 0)?.with_reservation(reservation))
+    }
+
+    /// Creates an SVM buffer shared as `mode` describes, after checking that every device
+    /// supports it. Fine-grain buffers can be accessed from the host without mapping.
+    #[cfg(feature = "CL_VERSION_2_0")]
+    pub fn create_svm_buffer_with_mode<T>(&self, flags: &[MemoryFlags], len: usize, mode: SvmMode) -> Result<ClSvmBuffer<T>, ClError> {
+        let reservation = self.memory.reserve(elements_size::<T>(len)?)?;
+        Ok(ClSvmBuffer::<T>::new_with_mode(&self.context, &flags.to_vec(), len, 0, mode)?.with_reservation(reservation))
+    }
+
+    /// Copies `data` into `buffer` starting at element `offset`, without mapping it.
+    /// Runs on the most powerful GPU available.
+    #[cfg(feature = "CL_VERSION_2_0")]
+    pub async fn svm_write<T: Copy>(&self, buffer: &mut ClSvmBuffer<T>, offset: usize, data: &[T]) -> Result<ClEvent, ClError> {
+        buffer.check_range(offset, data.len())?;
+        let queue = self.get_optimal_queue();
+        queue.svm_memcpy(buffer.element_ptr(offset), data.as_ptr() as *const c_void, std::mem::size_of_val(data), None).await
+    }
+
+    /// Copies elements of `buffer` starting at `offset` into `host_memory`, without mapping it.
+    #[cfg(feature = "CL_VERSION_2_0")]
+    pub async fn svm_read<T: Copy>(&self, buffer: &ClSvmBuffer<T>, offset: usize, host_memory: &mut [T]) -> Result<ClEvent, ClError> {
+        buffer.check_range(offset, host_memory.len())?;
+        let queue = self.get_optimal_queue();
+        let size = std::mem::size_of_val(host_memory);
+        queue.svm_memcpy(host_memory.as_mut_ptr() as *mut c_void, buffer.element_ptr(offset), size, None).await
+    }
+
+    /// Copies `len` elements between two SVM buffers (offsets in elements).
+    #[cfg(feature = "CL_VERSION_2_0")]
+    pub async fn svm_copy<T: Copy>(
+        &self,
+        src: &ClSvmBuffer<T>,
+        src_offset: usize,
+        dst: &mut ClSvmBuffer<T>,
+        dst_offset: usize,
+        len: usize,
+    ) -> Result<ClEvent, ClError> {
+        src.check_range(src_offset, len)?;
+        dst.check_range(dst_offset, len)?;
+        let queue = self.get_optimal_queue();
+        queue.svm_memcpy(dst.element_ptr(dst_offset), src.element_ptr(src_offset), len * std::mem::size_of::<T>(), None).await
+    }
+
+    /// Fills the whole SVM buffer with a repeated value, e.g. `svm_fill(&mut buf, 0u32)` to clear it.
+    /// `size_of::<T>()` must be a power of two up to 128.
+    #[cfg(feature = "CL_VERSION_2_0")]
+    pub async fn svm_fill<T: Copy>(&self, buffer: &mut ClSvmBuffer<T>, pattern: T) -> Result<ClEvent, ClError> {
+        let queue = self.get_optimal_queue();
+        queue.svm_mem_fill(buffer.as_ptr(), pattern, buffer.len * std::mem::size_of::<T>(), None).await
+    }
+
+    /// Moves an SVM buffer to the device of `target_queue`, like `migrate_mem_objects` does
+    /// for buffers and images.
+    #[cfg(feature = "CL_VERSION_2_1")]
+    pub async fn migrate_svm<T>(&self, buffer: &ClSvmBuffer<T>, target_queue: &ClCommandQueue, flags: &[MigrationFlags]) -> Result<ClEvent, ClError> {
+        let size = buffer.len * std::mem::size_of::<T>();
+        target_queue.svm_migrate_mem(&[buffer.as_ptr() as *const c_void], &[size], MigrationFlags::to_u64(flags), None).await
+    }
+
+    /// Frees an SVM buffer after the commands already enqueued on the most powerful GPU,
+    /// instead of when it is dropped.
+    #[cfg(feature = "CL_VERSION_2_0")]
+    pub async fn free_svm<T>(&self, mut buffer: ClSvmBuffer<T>) -> Result<ClEvent, ClError> {
+        let queue = self.get_optimal_queue();
+        // Counted until the free has run
+        let _reservation = buffer.take_reservation();
+        let context = buffer.context().clone();
+        let memory = buffer.into_raw();
+        let result = queue.svm_free(&[memory], None).await;
+        if result.is_err() {
+            // The free was never enqueued: release the allocation now instead of leaking it
+            let _ = unsafe { cl3::memory::svm_free(context.as_ptr(), memory) };
+        }
+        result
     }
 
     /// Reads data from a buffer to host memory.
@@ -397,7 +975,37 @@ impl AsyncExecutor {
          queue.write_buffer(buffer, host_memory.as_mut_ptr() as *mut c_void, 0, size, None).await
     }
     
+    /// Maps the elements of `buffer` selected by `range` (in elements of `T`) into host memory for reading.
+    ///
+    /// The returned guard dereferences to a slice and unmaps the region when dropped.
+    /// On `AllocHostPtr` buffers of integrated GPUs and CPU devices this avoids any copy.
+    pub async fn map_buffer<'a, T: 'a>(
+        &self,
+        buffer: &'a ClBuffer,
+        range: impl std::ops::RangeBounds<usize>,
+    ) -> Result<BufferMapGuard<'a, T>, ClError> {
+        BufferMapGuard::new(self.get_optimal_queue(), buffer, range).await
+    }
+
+    /// Like `map_buffer`, for writing. The guard borrows `buffer` mutably and dereferences to a mutable slice.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let mut view = executor.map_buffer_mut::<f32>(&mut buffer, .., MapMode::Write).await?;
+    /// view[0] = 1.0;
+    /// view.unmap().await?;
+    /// ```
+    pub async fn map_buffer_mut<'a, T: 'a>(
+        &self,
+        buffer: &'a mut ClBuffer,
+        range: impl std::ops::RangeBounds<usize>,
+        mode: MapMode,
+    ) -> Result<BufferMapGuardMut<'a, T>, ClError> {
+        BufferMapGuardMut::new(self.get_optimal_queue(), buffer, range, mode).await
+    }
+
     /// Reads data from an image to host memory.
+    /// Fails with `HostBufferTooSmall` if `host_memory` can't hold `region` tightly packed.
     #[cfg(feature = "CL_VERSION_1_2")]
     pub async fn read_image<T: Sized>(
         &self,
@@ -406,6 +1014,7 @@ impl AsyncExecutor {
         origin: [usize; 3],
         region: [usize; 3],
     ) -> Result<ClEvent, ClError> {
+        image.check_host_region(region, std::mem::size_of_val(host_memory))?;
         let queue = self.get_optimal_queue();
         queue.read_image_raw(
             image, 
@@ -419,6 +1028,7 @@ impl AsyncExecutor {
     }
 
     /// Writes data from host memory to an image.
+    /// Fails with `HostBufferTooSmall` if `host_memory` doesn't hold `region` tightly packed.
     #[cfg(feature = "CL_VERSION_1_2")]
     pub async fn write_image<T: Sized>(
         &self,
@@ -427,6 +1037,7 @@ impl AsyncExecutor {
          origin: [usize; 3],
          region: [usize; 3],
     ) -> Result<ClEvent, ClError> {
+        image.check_host_region(region, std::mem::size_of_val(host_memory))?;
         let queue = self.get_optimal_queue();
         queue.write_image_raw(
             image,
@@ -439,12 +1050,245 @@ impl AsyncExecutor {
         ).await
     }
 
+    /// Reads a `width`-wide tile of a row-major 2D buffer into `host_memory`.
+    ///
+    /// The buffer holds rows of `buffer_row_len` elements of `T`; `origin` is the
+    /// `[column, row]` of the tile's top-left element. `host_memory` is tightly packed
+    /// and its length must be a multiple of `width`, giving the number of rows.
+    ///
+    /// # Example
+    /// ```ignore
+    /// // 16x16 tile at column 32, row 8 of a 1024-wide matrix
+    /// let mut tile = vec![0.0f32; 16 * 16];
+    /// executor.read_buffer_rect(&matrix, 1024, [32, 8], &mut tile, 16).await?;
+    /// ```
+    #[cfg(feature = "CL_VERSION_1_1")]
+    pub async fn read_buffer_rect<T: Copy>(
+        &self,
+        buffer: &ClBuffer,
+        buffer_row_len: usize,
+        origin: [usize; 2],
+        host_memory: &mut [T],
+        width: usize,
+    ) -> Result<ClEvent, ClError> {
+        let (buffer_origin, region, buffer_row_pitch) =
+            Self::rect_region::<T>(buffer, buffer_row_len, origin, host_memory.len(), width)?;
+        let queue = self.get_optimal_queue();
+        queue.read_buffer_rect(
+            buffer,
+            buffer_origin,
+            [0, 0, 0],
+            region,
+            buffer_row_pitch,
+            0,
+            region[0],
+            0,
+            host_memory.as_mut_ptr() as *mut c_void,
+            None
+        ).await
+    }
+
+    /// Writes the tightly packed tile `host_memory`, `width` elements wide, into a
+    /// row-major 2D buffer at `origin` (`[column, row]`). See [`AsyncExecutor::read_buffer_rect`].
+    #[cfg(feature = "CL_VERSION_1_1")]
+    pub async fn write_buffer_rect<T: Copy>(
+        &self,
+        buffer: &ClBuffer,
+        buffer_row_len: usize,
+        origin: [usize; 2],
+        host_memory: &[T],
+        width: usize,
+    ) -> Result<ClEvent, ClError> {
+        let (buffer_origin, region, buffer_row_pitch) =
+            Self::rect_region::<T>(buffer, buffer_row_len, origin, host_memory.len(), width)?;
+        let queue = self.get_optimal_queue();
+        queue.write_buffer_rect(
+            buffer,
+            buffer_origin,
+            [0, 0, 0],
+            region,
+            buffer_row_pitch,
+            0,
+            region[0],
+            0,
+            host_memory.as_ptr() as *const c_void,
+            None
+        ).await
+    }
+
+    /// Checks a tile against the buffer and converts it to OpenCL's byte-based
+    /// origin, region and row pitch. Zero-sized `T` fails with `ZeroSizedElement`.
+    #[cfg(feature = "CL_VERSION_1_1")]
+    fn rect_region<T>(
+        buffer: &ClBuffer,
+        buffer_row_len: usize,
+        origin: [usize; 2],
+        host_len: usize,
+        width: usize,
+    ) -> Result<([usize; 3], [usize; 3], usize), ClError> {
+        if width == 0 || !host_len.is_multiple_of(width) {
+            return Err(ClError::Wrapper(crate::error::wrapper_error::WrapperError::BufferLengthMismatch {
+                expected: host_len.next_multiple_of(width.max(1)),
+                actual: host_len,
+            }));
+        }
+        let rows = host_len / width;
+        if origin[0].checked_add(width).is_none_or(|end| end > buffer_row_len) {
+            return Err(ClError::Wrapper(crate::error::wrapper_error::WrapperError::BufferRangeOutOfBounds { offset: origin[0], len: width, buffer_len: buffer_row_len }));
+        }
+        let elem = std::mem::size_of::<T>();
+        if elem == 0 {
+            return Err(ClError::Wrapper(crate::error::wrapper_error::WrapperError::ZeroSizedElement));
+        }
+        // A row too long to address can't be in the buffer, not even once
+        let Some(row_pitch) = buffer_row_len.checked_mul(elem) else {
+            return Err(ClError::Wrapper(crate::error::wrapper_error::WrapperError::BufferRangeOutOfBounds { offset: origin[1], len: rows, buffer_len: 0 }));
+        };
+        let buffer_rows = buffer.get_size()? / row_pitch;
+        if origin[1].checked_add(rows).is_none_or(|end| end > buffer_rows) {
+            return Err(ClError::Wrapper(crate::error::wrapper_error::WrapperError::BufferRangeOutOfBounds { offset: origin[1], len: rows, buffer_len: buffer_rows }));
+        }
+
+        Ok(([origin[0] * elem, origin[1], 0], [width * elem, rows, 1], row_pitch))
+    }
+
+    /// Moves memory objects to the device of `target_queue` (or to the host with
+    /// `MigrationFlags::ToHost`) ahead of their use, instead of letting the driver
+    /// migrate them lazily on first access.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let second_gpu = &executor.get_queues()[1];
+    /// executor.migrate_mem_objects(&[&input, &lut_image], second_gpu, &[]).await?;
+    /// // `output` is overwritten by the next kernel: skip copying its contents
+    /// executor.migrate_mem_objects(&[&output], second_gpu, &[MigrationFlags::ContentUndefined]).await?;
+    /// ```
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub async fn migrate_mem_objects(
+        &self,
+        mem_objects: &[&dyn MemObject],
+        target_queue: &ClCommandQueue,
+        flags: &[MigrationFlags],
+    ) -> Result<ClEvent, ClError> {
+        let mem_ptrs: Vec<*mut c_void> = mem_objects.iter().map(|m| m.mem_ptr()).collect();
+        target_queue.enqueue_migrate_mem_objects(&mem_ptrs, MigrationFlags::to_u64(flags), None).await
+    }
+
+    /// Copies `size` bytes between two buffers without going through host memory.
+    pub async fn copy_buffer(
+        &self,
+        src: &ClBuffer,
+        dst: &ClBuffer,
+        src_offset: usize,
+        dst_offset: usize,
+        size: usize,
+    ) -> Result<ClEvent, ClError> {
+        let queue = self.get_optimal_queue();
+        queue.copy_buffer(src, dst, src_offset, dst_offset, size, None).await
+    }
+
+    /// Copies a 2D/3D region between two buffers without going through host memory.
+    /// Origins and region are `[x in bytes, y in rows, z in slices]`; pitches are in bytes.
+    pub async fn copy_buffer_rect(
+        &self,
+        src: &ClBuffer,
+        dst: &ClBuffer,
+        src_origin: [usize; 3],
+        dst_origin: [usize; 3],
+        region: [usize; 3],
+        src_pitches: (usize, usize),
+        dst_pitches: (usize, usize),
+    ) -> Result<ClEvent, ClError> {
+        let queue = self.get_optimal_queue();
+        queue.copy_buffer_rect(
+            src,
+            dst,
+            src_origin,
+            dst_origin,
+            region,
+            src_pitches.0,
+            src_pitches.1,
+            dst_pitches.0,
+            dst_pitches.1,
+            None
+        ).await
+    }
+
+    /// Fills the whole buffer with a repeated value, e.g. `fill_buffer(&buf, 0.0f32)` to clear it.
+    /// The buffer size must be a multiple of `size_of::<T>()`.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub async fn fill_buffer<T: Copy>(&self, buffer: &ClBuffer, pattern: T) -> Result<ClEvent, ClError> {
+        let size = buffer.get_size()?;
+        let queue = self.get_optimal_queue();
+        queue.fill_buffer(buffer, pattern, 0, size, None).await
+    }
+
+    /// Copies a region between two images of the same format.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub async fn copy_image(
+        &self,
+        src: &ClImage,
+        dst: &ClImage,
+        src_origin: [usize; 3],
+        dst_origin: [usize; 3],
+        region: [usize; 3],
+    ) -> Result<ClEvent, ClError> {
+        let queue = self.get_optimal_queue();
+        queue.copy_image(src, dst, src_origin, dst_origin, region, None).await
+    }
+
+    /// Fills a region of an image with one RGBA color
+    /// (`f32` for normalized/float formats, `i32`/`u32` for integer formats).
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub async fn fill_image<T: FillColor>(
+        &self,
+        image: &ClImage,
+        fill_color: [T; 4],
+        origin: [usize; 3],
+        region: [usize; 3],
+    ) -> Result<ClEvent, ClError> {
+        let queue = self.get_optimal_queue();
+        queue.fill_image(image, fill_color, origin, region, None).await
+    }
+
+    /// Copies a region of an image into a buffer as tightly packed pixels.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub async fn copy_image_to_buffer(
+        &self,
+        src: &ClImage,
+        dst: &ClBuffer,
+        src_origin: [usize; 3],
+        region: [usize; 3],
+        dst_offset: usize,
+    ) -> Result<ClEvent, ClError> {
+        let queue = self.get_optimal_queue();
+        queue.copy_image_to_buffer(src, dst, src_origin, region, dst_offset, None).await
+    }
+
+    /// Copies tightly packed pixels from a buffer into a region of an image.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub async fn copy_buffer_to_image(
+        &self,
+        src: &ClBuffer,
+        dst: &ClImage,
+        src_offset: usize,
+        dst_origin: [usize; 3],
+        region: [usize; 3],
+    ) -> Result<ClEvent, ClError> {
+        let queue = self.get_optimal_queue();
+        queue.copy_buffer_to_image(src, dst, src_offset, dst_origin, region, None).await
+    }
+
     //
     //
     // Utils
     //
     //
     fn get_optimal_queue(&self) -> &ClCommandQueue {
+        &self.queues[self.get_optimal_queue_index()]
+    }
+
+    fn get_optimal_queue_index(&self) -> usize {
         let mut max_weight = 0;
         let mut idx = 0;
         for (i, &weight) in self.weights.iter().enumerate() {
@@ -453,7 +1297,36 @@ impl AsyncExecutor {
                 idx = i;
             }
         }
-        &self.queues[idx]
+        idx
+    }
+
+    fn create_device_queue(
+        context: &ClContext,
+        device: &ClDevice,
+        profiling_enabled: bool,
+        allow_out_of_order: bool,
+    ) -> Result<ClCommandQueue, ClError> {
+        let version = device.get_opencl_version();
+
+        if version >= OpenCLVersion::V2_0 {
+            let supports_out_of_order = allow_out_of_order
+                && device
+                    .get_queue_on_host_properties()
+                    .map(|host_props| (host_props as u64 & cl3::command_queue::CL_QUEUE_OUT_OF_ORDER_EXEC_MODE_ENABLE) != 0)
+                    .unwrap_or(false);
+
+            let properties = CommandQueueProperties::<Version20>::new()
+                .set_cl_queue_properties(supports_out_of_order, profiling_enabled, false, false)
+                .get_properties();
+            ClCommandQueue::create_command_queue_with_properties(context, device, &properties)
+        } else {
+            let mut properties = 0;
+            if profiling_enabled {
+                properties |= cl3::command_queue::CL_QUEUE_PROFILING_ENABLE;
+            }
+            #[allow(deprecated)]
+            ClCommandQueue::create_command_queue(context, device, properties)
+        }
     }
 
     fn measure_platform_capacity(platform: &ClPlatform) -> Result<u64, ClError> {
@@ -0,0 +1,526 @@
+use std::collections::HashMap;
+use std::os::raw::c_void;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::{
+    async_executor::{AsyncExecutor, pipeline_task::{PipelineBuilder, PipelineReport, StageBuilder}},
+    cl_types::{
+        cl_buffer::ClBuffer,
+        cl_kernel::ClKernel,
+        cl_program::{ClProgram, Builded},
+        memory_flags::MemoryFlags,
+    },
+    error::{ClError, wrapper_error::WrapperError},
+};
+
+fn invalid(entry: impl Into<String>, reason: impl Into<String>) -> ClError {
+    ClError::Wrapper(WrapperError::InvalidPipelineDefinition { entry: entry.into(), reason: reason.into() })
+}
+
+/// # ScalarType
+///
+/// The host type a scalar argument is converted to before it is passed to the kernel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScalarType {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    I64,
+    U64,
+    F32,
+    F64,
+}
+
+impl ScalarType {
+    pub fn size(&self) -> usize {
+        match self {
+            Self::I8 | Self::U8 => 1,
+            Self::I16 | Self::U16 => 2,
+            Self::I32 | Self::U32 | Self::F32 => 4,
+            Self::I64 | Self::U64 | Self::F64 => 8,
+        }
+    }
+}
+
+/// A number as written in the document.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum ScalarValue {
+    Int(i64),
+    Float(f64),
+}
+
+/// # ProgramDefinition
+///
+/// An OpenCL program, given either as a path to a `.cl` file or as inline source.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProgramDefinition {
+    pub name: String,
+    /// Path to the kernel source. Relative paths are resolved against the definition file.
+    pub path: Option<PathBuf>,
+    /// Inline kernel source, as an alternative to `path`.
+    pub source: Option<String>,
+    /// Build options passed to the OpenCL compiler (e.g. `-cl-fast-relaxed-math`).
+    pub options: Option<String>,
+}
+
+/// # BufferDefinition
+///
+/// A named device buffer. The size is either given in bytes (`size`) or as
+/// a number of `elements` of a scalar `type`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BufferDefinition {
+    pub name: String,
+    pub size: Option<usize>,
+    pub elements: Option<usize>,
+    #[serde(rename = "type")]
+    pub element_type: Option<ScalarType>,
+    /// Memory flag names, e.g. `["ReadWrite"]`. Defaults to `ReadWrite`.
+    #[serde(default)]
+    pub flags: Vec<String>,
+}
+
+impl BufferDefinition {
+    /// Size in bytes, or why it can't be computed.
+    fn byte_size(&self) -> Result<usize, String> {
+        match (self.size, self.elements, self.element_type) {
+            (Some(size), None, None) => Ok(size),
+            (None, Some(elements), Some(element_type)) => elements
+                .checked_mul(element_type.size())
+                .ok_or_else(|| format!("{} elements of {:?} overflow the address space", elements, element_type)),
+            _ => Err("give either 'size' in bytes or both 'elements' and 'type'".to_string()),
+        }
+    }
+}
+
+/// # ArgDefinition
+///
+/// A kernel argument of a stage. Arguments are bound in order unless an explicit `index` is given.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase", deny_unknown_fields)]
+pub enum ArgDefinition {
+    /// A buffer declared in the `buffers` section.
+    Buffer { name: String, index: Option<u32> },
+    /// A simple value converted to `type`.
+    Scalar {
+        #[serde(rename = "type")]
+        scalar_type: ScalarType,
+        value: ScalarValue,
+        index: Option<u32>,
+    },
+}
+
+impl ArgDefinition {
+    fn index(&self) -> Option<u32> {
+        match self {
+            Self::Buffer { index, .. } | Self::Scalar { index, .. } => *index,
+        }
+    }
+}
+
+/// # StageDefinition
+///
+/// One kernel launch of the pipeline. `global`, `local` and `offset` take one to three dimensions.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StageDefinition {
+    pub program: String,
+    pub kernel: String,
+    pub global: Vec<usize>,
+    pub local: Option<Vec<usize>>,
+    pub offset: Option<Vec<usize>>,
+    #[serde(default)]
+    pub args: Vec<ArgDefinition>,
+}
+
+/// # PipelineDefinition
+///
+/// A pipeline described in a TOML or JSON document, so processing chains can be
+/// changed without recompiling.
+///
+/// # Example
+/// ```toml
+/// [[programs]]
+/// name = "math"
+/// path = "kernels/math.cl"
+/// options = "-cl-fast-relaxed-math"
+///
+/// [[buffers]]
+/// name = "data"
+/// elements = 1024
+/// type = "f32"
+/// flags = ["ReadWrite"]
+///
+/// [[stages]]
+/// program = "math"
+/// kernel = "scale"
+/// global = [1024]
+/// args = [
+///     { kind = "buffer", name = "data" },
+///     { kind = "scalar", type = "f32", value = 2.0 },
+/// ]
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PipelineDefinition {
+    #[serde(default)]
+    pub programs: Vec<ProgramDefinition>,
+    #[serde(default)]
+    pub buffers: Vec<BufferDefinition>,
+    #[serde(default)]
+    pub stages: Vec<StageDefinition>,
+    /// Directory relative program paths are resolved against.
+    #[serde(skip)]
+    pub base_dir: Option<PathBuf>,
+}
+
+impl PipelineDefinition {
+    /// Parses and validates a TOML document.
+    pub fn from_toml_str(document: &str) -> Result<Self, ClError> {
+        let definition: Self = toml::from_str(document).map_err(|e| invalid("document", e.to_string()))?;
+        definition.validate()?;
+        Ok(definition)
+    }
+
+    /// Parses and validates a JSON document.
+    pub fn from_json_str(document: &str) -> Result<Self, ClError> {
+        let definition: Self = serde_json::from_str(document).map_err(|e| invalid("document", e.to_string()))?;
+        definition.validate()?;
+        Ok(definition)
+    }
+
+    /// Loads a `.toml` or `.json` file. Program paths are resolved relative to the file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ClError> {
+        let path = path.as_ref();
+        let document = std::fs::read_to_string(path).map_err(|_| ClError::Wrapper(WrapperError::FileIOError))?;
+
+        let mut definition = match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Self::from_json_str(&document)?,
+            Some("toml") => Self::from_toml_str(&document)?,
+            _ => return Err(invalid("document", format!("unsupported file extension for '{}', expected .toml or .json", path.display()))),
+        };
+        definition.base_dir = path.parent().map(Path::to_path_buf);
+        Ok(definition)
+    }
+
+    /// Checks names, sizes, flags and argument references without touching the device.
+    pub fn validate(&self) -> Result<(), ClError> {
+        for (i, program) in self.programs.iter().enumerate() {
+            let entry = format!("programs[{}]", i);
+            if program.name.is_empty() {
+                return Err(invalid(entry, "name must not be empty"));
+            }
+            if self.programs[..i].iter().any(|p| p.name == program.name) {
+                return Err(invalid(entry, format!("duplicate program name '{}'", program.name)));
+            }
+            if program.path.is_some() == program.source.is_some() {
+                return Err(invalid(entry, "exactly one of 'path' or 'source' must be given"));
+            }
+        }
+
+        for (i, buffer) in self.buffers.iter().enumerate() {
+            let entry = format!("buffers[{}]", i);
+            if buffer.name.is_empty() {
+                return Err(invalid(entry, "name must not be empty"));
+            }
+            if self.buffers[..i].iter().any(|b| b.name == buffer.name) {
+                return Err(invalid(entry, format!("duplicate buffer name '{}'", buffer.name)));
+            }
+            let size = buffer.byte_size().map_err(|reason| invalid(&entry, reason))?;
+            if size == 0 {
+                return Err(invalid(entry, "size must be greater than zero"));
+            }
+            for (j, flag) in buffer.flags.iter().enumerate() {
+                match flag.parse::<MemoryFlags>() {
+                    Ok(MemoryFlags::CopyHostPtr | MemoryFlags::UseHostPtr) => {
+                        return Err(invalid(
+                            format!("buffers.{}.flags", buffer.name),
+                            format!("'{}' needs a host pointer, which pipeline buffers don't have", flag),
+                        ));
+                    }
+                    Ok(_) => {}
+                    Err(_) => {
+                        return Err(invalid(format!("buffers[{}].flags[{}]", i, j), format!("unknown memory flag '{}'", flag)));
+                    }
+                }
+            }
+        }
+
+        for (i, stage) in self.stages.iter().enumerate() {
+            let entry = format!("stages[{}]", i);
+            if !self.programs.iter().any(|p| p.name == stage.program) {
+                return Err(invalid(format!("{}.program", entry), format!("unknown program '{}'", stage.program)));
+            }
+            if stage.kernel.is_empty() {
+                return Err(invalid(format!("{}.kernel", entry), "kernel name must not be empty"));
+            }
+            if stage.global.is_empty() || stage.global.len() > 3 {
+                return Err(invalid(format!("{}.global", entry), "expected 1 to 3 dimensions"));
+            }
+            if stage.global.contains(&0) {
+                return Err(invalid(format!("{}.global", entry), "work sizes must be greater than zero"));
+            }
+            if let Some(local) = &stage.local {
+                if local.len() != stage.global.len() {
+                    return Err(invalid(format!("{}.local", entry), "must have the same number of dimensions as 'global'"));
+                }
+                if local.contains(&0) {
+                    return Err(invalid(format!("{}.local", entry), "work sizes must be greater than zero"));
+                }
+            }
+            if let Some(offset) = &stage.offset
+                && offset.len() != stage.global.len()
+            {
+                return Err(invalid(format!("{}.offset", entry), "must have the same number of dimensions as 'global'"));
+            }
+
+            let mut used_indices = Vec::new();
+            for (j, arg) in stage.args.iter().enumerate() {
+                let arg_entry = format!("{}.args[{}]", entry, j);
+                let index = arg.index().unwrap_or(j as u32);
+                if used_indices.contains(&index) {
+                    return Err(invalid(arg_entry, format!("argument index {} is bound twice", index)));
+                }
+                used_indices.push(index);
+
+                match arg {
+                    ArgDefinition::Buffer { name, .. } => {
+                        if !self.buffers.iter().any(|b| &b.name == name) {
+                            return Err(invalid(arg_entry, format!("unknown buffer '{}'", name)));
+                        }
+                    }
+                    ArgDefinition::Scalar { scalar_type, value, .. } => {
+                        ScalarArg::convert(*scalar_type, *value).map_err(|reason| invalid(arg_entry, reason))?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Builds the programs, creates the kernels and allocates the buffers on `async_executor`.
+    pub fn instantiate<'a>(&self, async_executor: &'a AsyncExecutor) -> Result<PipelineInstance<'a>, ClError> {
+        self.validate()?;
+
+        let mut programs: HashMap<&str, ClProgram<Builded>> = HashMap::new();
+        for (i, program) in self.programs.iter().enumerate() {
+            let source = match (&program.path, &program.source) {
+                (Some(path), _) => {
+                    let path = match &self.base_dir {
+                        Some(base_dir) if path.is_relative() => base_dir.join(path),
+                        _ => path.clone(),
+                    };
+                    std::fs::read_to_string(&path).map_err(|e| {
+                        invalid(format!("programs[{}].path", i), format!("cannot read '{}': {}", path.display(), e))
+                    })?
+                }
+                (None, Some(source)) => source.clone(),
+                (None, None) => unreachable!("checked by validate"),
+            };
+            let built = async_executor
+                .build_program(source, program.options.as_deref())
+                .map_err(|e| invalid(format!("programs.{}", program.name), format!("cannot build program: {}", e)))?;
+            programs.insert(&program.name, built);
+        }
+
+        let mut buffers = HashMap::new();
+        for (i, buffer) in self.buffers.iter().enumerate() {
+            let size = buffer.byte_size().map_err(|reason| invalid(format!("buffers[{}]", i), reason))?;
+            let mut flags: Vec<MemoryFlags> = buffer.flags.iter().filter_map(|f| f.parse().ok()).collect();
+            if flags.is_empty() {
+                flags.push(MemoryFlags::ReadWrite);
+            }
+            let cl_buffer = async_executor
+                .create_buffer(&flags, size, std::ptr::null_mut())
+                .map_err(|e| invalid(format!("buffers.{}", buffer.name), format!("cannot create buffer of {} bytes: {}", size, e)))?;
+            buffers.insert(buffer.name.clone(), (cl_buffer, size));
+        }
+
+        let mut stages = Vec::new();
+        for (i, stage) in self.stages.iter().enumerate() {
+            let kernel = ClKernel::new(&programs[stage.program.as_str()], &stage.kernel)
+                .map_err(|e| invalid(format!("stages[{}].kernel", i), format!("cannot create kernel '{}': {}", stage.kernel, e)))?;
+
+            let args = stage
+                .args
+                .iter()
+                .enumerate()
+                .map(|(j, arg)| {
+                    let index = arg.index().unwrap_or(j as u32);
+                    match arg {
+                        ArgDefinition::Buffer { name, .. } => (index, ResolvedArg::Buffer(name.clone())),
+                        ArgDefinition::Scalar { scalar_type, value, .. } => {
+                            // Already checked by validate.
+                            let scalar = ScalarArg::convert(*scalar_type, *value).unwrap_or(ScalarArg::I32(0));
+                            (index, ResolvedArg::Scalar(scalar))
+                        }
+                    }
+                })
+                .collect();
+
+            let dims = |v: &[usize], fill: usize| -> [usize; 3] {
+                let mut out = [fill; 3];
+                out[..v.len()].copy_from_slice(v);
+                out
+            };
+
+            stages.push(ResolvedStage {
+                kernel,
+                args,
+                global: dims(&stage.global, 1),
+                local: stage.local.as_deref().map(|l| dims(l, 1)),
+                offset: dims(stage.offset.as_deref().unwrap_or(&[]), 0),
+            });
+        }
+
+        Ok(PipelineInstance { async_executor, buffers, stages })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ScalarArg {
+    I8(i8),
+    U8(u8),
+    I16(i16),
+    U16(u16),
+    I32(i32),
+    U32(u32),
+    I64(i64),
+    U64(u64),
+    F32(f32),
+    F64(f64),
+}
+
+impl ScalarArg {
+    fn convert(scalar_type: ScalarType, value: ScalarValue) -> Result<Self, String> {
+        let int = |value: ScalarValue| match value {
+            ScalarValue::Int(v) => Ok(v),
+            ScalarValue::Float(v) => Err(format!("expected an integer for type {:?}, got {}", scalar_type, v)),
+        };
+        let out_of_range = |v: i64| format!("value {} is out of range for type {:?}", v, scalar_type);
+        let float = match value {
+            ScalarValue::Int(v) => v as f64,
+            ScalarValue::Float(v) => v,
+        };
+
+        Ok(match scalar_type {
+            ScalarType::I8 => { let v = int(value)?; Self::I8(v.try_into().map_err(|_| out_of_range(v))?) }
+            ScalarType::U8 => { let v = int(value)?; Self::U8(v.try_into().map_err(|_| out_of_range(v))?) }
+            ScalarType::I16 => { let v = int(value)?; Self::I16(v.try_into().map_err(|_| out_of_range(v))?) }
+            ScalarType::U16 => { let v = int(value)?; Self::U16(v.try_into().map_err(|_| out_of_range(v))?) }
+            ScalarType::I32 => { let v = int(value)?; Self::I32(v.try_into().map_err(|_| out_of_range(v))?) }
+            ScalarType::U32 => { let v = int(value)?; Self::U32(v.try_into().map_err(|_| out_of_range(v))?) }
+            ScalarType::I64 => Self::I64(int(value)?),
+            ScalarType::U64 => { let v = int(value)?; Self::U64(v.try_into().map_err(|_| out_of_range(v))?) }
+            ScalarType::F32 => Self::F32(float as f32),
+            ScalarType::F64 => Self::F64(float),
+        })
+    }
+
+    fn bind<'a>(self, stage: StageBuilder<'a>, index: u32) -> StageBuilder<'a> {
+        match self {
+            Self::I8(v) => stage.arg_scalar(index, v),
+            Self::U8(v) => stage.arg_scalar(index, v),
+            Self::I16(v) => stage.arg_scalar(index, v),
+            Self::U16(v) => stage.arg_scalar(index, v),
+            Self::I32(v) => stage.arg_scalar(index, v),
+            Self::U32(v) => stage.arg_scalar(index, v),
+            Self::I64(v) => stage.arg_scalar(index, v),
+            Self::U64(v) => stage.arg_scalar(index, v),
+            Self::F32(v) => stage.arg_scalar(index, v),
+            Self::F64(v) => stage.arg_scalar(index, v),
+        }
+    }
+}
+
+enum ResolvedArg {
+    Buffer(String),
+    Scalar(ScalarArg),
+}
+
+struct ResolvedStage {
+    kernel: ClKernel,
+    args: Vec<(u32, ResolvedArg)>,
+    global: [usize; 3],
+    local: Option<[usize; 3]>,
+    offset: [usize; 3],
+}
+
+/// # PipelineInstance
+///
+/// A [`PipelineDefinition`] whose programs, kernels and buffers exist on an executor.
+/// It can be run any number of times; buffers keep their contents between runs.
+pub struct PipelineInstance<'a> {
+    async_executor: &'a AsyncExecutor,
+    buffers: HashMap<String, (ClBuffer, usize)>,
+    stages: Vec<ResolvedStage>,
+}
+
+impl<'a> PipelineInstance<'a> {
+    /// Returns the buffer declared under `name`.
+    pub fn buffer(&self, name: &str) -> Option<&ClBuffer> {
+        self.buffers.get(name).map(|(buffer, _)| buffer)
+    }
+
+    fn sized_buffer<T>(&self, name: &str, host_len: usize) -> Result<(&ClBuffer, usize), ClError> {
+        let (buffer, size) = self
+            .buffers
+            .get(name)
+            .ok_or_else(|| invalid(format!("buffers.{}", name), "unknown buffer"))?;
+        let bytes = host_len
+            .checked_mul(std::mem::size_of::<T>())
+            .ok_or_else(|| invalid(format!("buffers.{}", name), format!("{} host elements overflow the address space", host_len)))?;
+        if bytes > *size {
+            return Err(invalid(format!("buffers.{}", name), format!("host data is {} bytes but the buffer holds {}", bytes, size)));
+        }
+        Ok((buffer, bytes))
+    }
+
+    /// Uploads `data` to the start of the buffer declared under `name`.
+    pub async fn write_buffer<T: Sized>(&self, name: &str, data: &[T]) -> Result<(), ClError> {
+        let (buffer, bytes) = self.sized_buffer::<T>(name, data.len())?;
+        self.async_executor
+            .get_optimal_queue()
+            .write_buffer(buffer, data.as_ptr() as *mut c_void, 0, bytes, None)
+            .await?;
+        Ok(())
+    }
+
+    /// Downloads the start of the buffer declared under `name` into `host_memory`.
+    pub async fn read_buffer<T: Sized>(&self, name: &str, host_memory: &mut [T]) -> Result<(), ClError> {
+        let (buffer, _) = self.sized_buffer::<T>(name, host_memory.len())?;
+        self.async_executor.read_buffer(buffer, host_memory).await?;
+        Ok(())
+    }
+
+    /// Runs every stage in order.
+    pub async fn run(&self) -> Result<PipelineReport, ClError> {
+        let mut pipeline = PipelineBuilder::new(self.async_executor);
+        for stage in &self.stages {
+            let [x, y, z] = stage.global;
+            let mut builder = pipeline
+                .add_stage(&stage.kernel, x, y, z)
+                .global_work_offset(stage.offset[0], stage.offset[1], stage.offset[2]);
+            if let Some([lx, ly, lz]) = stage.local {
+                builder = builder.local_work_dims(lx, ly, lz);
+            }
+            for (index, arg) in &stage.args {
+                builder = match arg {
+                    ResolvedArg::Buffer(name) => builder.arg_buffer(*index, &self.buffers[name].0),
+                    ResolvedArg::Scalar(scalar) => scalar.bind(builder, *index),
+                };
+            }
+            pipeline = builder.finish();
+        }
+        pipeline.run().await
+    }
+}
@@ -1,16 +1,16 @@
 use crate::{
-    async_executor::{kernel_arg::KernelArg, AsyncExecutor, task_builder::{TaskReport, OutputRead}},
+    async_executor::{kernel_arg::KernelArg, AsyncExecutor, launch::{self, Launch}, task_builder::{TaskReport, OutputRead}, profiling::{CommandKind, ProfileReport}},
     cl_types::{
         cl_event::ClEvent,
         cl_kernel::ClKernel,
         cl_buffer::ClBuffer,
         cl_image::ClImage,
         cl_svm_buffer::ClSvmBuffer,
+        cl_pipe::ClPipe,
     },
     error::ClError,
 };
 use std::os::raw::c_void;
-use futures;
 
 /// # PipelineStage
 /// 
@@ -22,6 +22,7 @@ pub struct PipelineStage<'a> {
     pub(crate) global_work_dims: [usize; 3],
     pub(crate) global_work_offset: [usize; 3],
     pub(crate) local_work_dims: Option<[usize; 3]>,
+    pub(crate) device_index: Option<usize>,
 }
 
 /// # PipelineReport
@@ -30,12 +31,33 @@ pub struct PipelineStage<'a> {
 #[cfg(feature = "CL_VERSION_1_1")]
 pub struct PipelineReport {
     pub stage_reports: Vec<TaskReport>,
+    /// Events of the reads performed after the last stage.
+    pub final_read_events: Vec<ClEvent>,
+    /// Index of the device each final read ran on.
+    pub final_read_device_indices: Vec<usize>,
 }
 
 impl PipelineReport {
     pub fn total_kernel_duration_ns(&self) -> u64 {
         self.stage_reports.iter().map(|r| r.total_kernel_duration_ns()).sum()
     }
+
+    pub fn total_read_duration_ns(&self) -> u64 {
+        let stage_reads: u64 = self.stage_reports.iter().map(|r| r.total_read_duration_ns()).sum();
+        let final_reads: u64 = self.final_read_events.iter().filter_map(|e| e.get_duration_nanos().ok()).sum();
+        stage_reads + final_reads
+    }
+
+    /// Breaks the pipeline down into queued/submit/start/end timings per stage, device and command.
+    /// Final reads are reported with `stage: None`. Requires profiling to be enabled.
+    pub fn detailed_report(&self) -> Result<ProfileReport, ClError> {
+        let mut profile = ProfileReport::default();
+        for (i, stage) in self.stage_reports.iter().enumerate() {
+            stage.append_to(&mut profile, Some(i))?;
+        }
+        profile.push_events(None, CommandKind::Read, &self.final_read_events, &self.final_read_device_indices)?;
+        Ok(profile)
+    }
 }
 
 /// # PipelineBuilder
@@ -68,6 +90,7 @@ impl<'a> PipelineBuilder<'a> {
             global_work_dims: [x, y, z],
             global_work_offset: [0, 0, 0],
             local_work_dims: None,
+            device_index: None,
         }
     }
 
@@ -81,132 +104,67 @@ impl<'a> PipelineBuilder<'a> {
         self
     }
 
+    /// Like `read_buffer`, for a region of an image. `run` fails with `HostBufferTooSmall`
+    /// before running any stage if `host_memory` can't hold `region` tightly packed.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub fn read_image<T>(mut self, image: &'a ClImage, host_memory: &mut [T], origin: [usize; 3], region: [usize; 3]) -> Self {
+        self.final_reads.push(OutputRead::Image {
+            image,
+            host_ptr: host_memory.as_mut_ptr() as *mut c_void,
+            size: std::mem::size_of_val(host_memory),
+            origin,
+            region,
+        });
+        self
+    }
+
     pub async fn run(mut self) -> Result<PipelineReport, ClError> {
-        let mut report = PipelineReport { stage_reports: Vec::new() };
+        launch::check_output_reads(&self.final_reads)?;
+
+        let mut report = PipelineReport {
+            stage_reports: Vec::new(),
+            final_read_events: Vec::new(),
+            final_read_device_indices: Vec::new(),
+        };
         let mut last_events: Option<Vec<ClEvent>> = None;
 
         let stages = std::mem::take(&mut self.stages);
+        // Final reads run where the last stage ran, so they don't need another migration.
+        let read_device = stages.last().and_then(|stage| stage.device_index);
 
         for stage in stages {
             // We use the last stage's event as the wait list for this stage.
-            let res = self.run_stage(stage, last_events).await?;
-            last_events = Some(res.kernel_execution_events.clone());
+            let (res, events) = self.run_stage(stage, last_events).await?;
+            last_events = Some(events);
             report.stage_reports.push(res);
         }
 
         // Final Reads
-        if !self.final_reads.is_empty() {
-            let queue = self.async_executor.get_optimal_queue();
-            for read in self.final_reads {
-                match read {
-                    OutputRead::Buffer { buffer, host_ptr, size } => {
-                        queue.enqueue_read_buffer_raw(buffer, None, host_ptr, size, last_events.clone()).await?;
-                    }
-                    #[cfg(feature = "CL_VERSION_1_2")]
-                    OutputRead::Image { image, host_ptr, origin, region } => {
-                        queue.read_image_raw(image, origin, region, 0, 0, host_ptr, last_events.clone()).await?;
-                    }
-                }
-            }
-        }
+        let mut reads = TaskReport::new();
+        let profile = if self.profiling_enabled { Some(&mut reads) } else { None };
+        launch::read_outputs(self.async_executor, &self.final_reads, read_device, last_events, profile).await?;
+        report.final_read_events = reads.read_events;
+        report.final_read_device_indices = reads.read_device_indices;
 
         Ok(report)
     }
 
-    async fn run_stage(&self, stage: PipelineStage<'a>, wait_list: Option<Vec<ClEvent>>) -> Result<TaskReport, ClError> {
-        // This is essentially a copy of TaskBuilder::run but with an external wait_list.
-        // TODO: Refactor TaskBuilder to avoid this duplication.
+    async fn run_stage(&self, stage: PipelineStage<'a>, wait_list: Option<Vec<ClEvent>>) -> Result<(TaskReport, Vec<ClEvent>), ClError> {
         let mut report = TaskReport::new();
-        let num_queues = self.async_executor.queues.len();
-        
-        let mut g_offset = stage.global_work_offset[0];
-        let total_work = stage.global_work_dims[0];
-        let total_weight: u64 = self.async_executor.weights.iter().sum();
-        
-        let mut futures = Vec::new();
-
-        for i in 0..num_queues {
-            let weight = self.async_executor.weights[i];
-            let chunk_size = if i == num_queues - 1 {
-                stage.global_work_offset[0] + total_work - g_offset
-            } else {
-                ((total_work as u128 * weight as u128) / total_weight as u128) as usize
-            };
-
-            if chunk_size == 0 && i != num_queues - 1 { continue; }
-
-            let work_dim = if stage.global_work_dims[2] > 1 || stage.global_work_offset[2] > 0 { 3 }
-                          else if stage.global_work_dims[1] > 1 || stage.global_work_offset[1] > 0 { 2 }
-                          else { 1 };
-
-            let g_offset_trimmed = vec![g_offset, stage.global_work_offset[1], stage.global_work_offset[2]][..work_dim].to_vec();
-            let g_dims_trimmed = vec![chunk_size, stage.global_work_dims[1], stage.global_work_dims[2]][..work_dim].to_vec();
-            
-            let kernel = stage.kernel;
-            // In a pipeline, we MUST set args for EVERY enqueuing because we share the kernel object
-            // across stages potentially, OR different queues.
-            // Actually, OpenCL kernels hold their state. Set args must happen before enqueue.
-            for arg in &stage.kernel_args {
-                match arg {
-                    KernelArg::Scalar { arg_index, arg } => {
-                        unsafe { kernel.set_args(*arg_index, arg.len(), arg.as_ptr() as *const _)?; }
-                    }
-                    KernelArg::Buffer { arg_index, arg } => {
-                        let handle = arg.as_ptr();
-                        unsafe { kernel.set_args(*arg_index, 8, &handle as *const _ as *const _)?; }
-                    }
-                    #[cfg(feature = "CL_VERSION_1_2")]
-                    KernelArg::Image { arg_index, arg } => {
-                        let handle = arg.as_ptr();
-                        unsafe { kernel.set_args(*arg_index, 8, &handle as *const _ as *const _)?; }
-                    }
-                    #[cfg(feature = "CL_VERSION_2_0")]
-                    KernelArg::Svm { arg_index, arg, len: _ } => {
-                        unsafe { kernel.set_svm_arg(*arg_index, 8, *arg)?; }
-                    }
-                    #[cfg(feature = "CL_VERSION_2_0")]
-                    KernelArg::Pipe { arg_index, arg } => {
-                        let handle = arg.as_ptr();
-                        unsafe { kernel.set_args(*arg_index, 8, &handle as *const _ as *const _)?; }
-                    }
-                }
-            }
-
-            let queue = self.async_executor.queues[i].clone();
-            let wl = wait_list.clone();
-            
-            // local_work_dims logic logic
-            let l_dims_vec = if let Some(ld) = stage.local_work_dims {
-                ld[..work_dim].to_vec()
-            } else {
-                 // Auto-tune logic (simplified version of TaskBuilder's)
-                 Vec::new()
-            };
+        let launch = Launch {
+            kernel: stage.kernel,
+            kernel_args: &stage.kernel_args,
+            global_work_dims: stage.global_work_dims,
+            global_work_offset: stage.global_work_offset,
+            local_work_dims: stage.local_work_dims,
+            device_index: stage.device_index,
+            pre_migrate: false,
+        };
 
-            futures.push(async move {
-                queue.enqueue_nd_range_kernel(
-                    &kernel, 
-                    work_dim as u32, 
-                    g_offset_trimmed, 
-                    g_dims_trimmed, 
-                    l_dims_vec, 
-                    None, 
-                    wl
-                ).await
-            });
+        let profile = if self.profiling_enabled { Some(&mut report) } else { None };
+        let events = launch::launch(self.async_executor, &launch, wait_list, profile).await?;
 
-            g_offset += chunk_size;
-        }
-
-        let results = futures::future::join_all(futures).await;
-        for res in results {
-            let event = res?;
-            if self.profiling_enabled {
-                report.kernel_execution_events.push(event);
-            }
-        }
-
-        Ok(report)
+        Ok((report, events))
     }
 }
 
@@ -220,6 +178,7 @@ pub struct StageBuilder<'a> {
     global_work_dims: [usize; 3],
     global_work_offset: [usize; 3],
     local_work_dims: Option<[usize; 3]>,
+    device_index: Option<usize>,
 }
 
 impl<'a> StageBuilder<'a> {
@@ -248,6 +207,18 @@ impl<'a> StageBuilder<'a> {
         self
     }
 
+    #[cfg(feature = "CL_VERSION_2_0")]
+    pub fn arg_pipe(mut self, index: u32, pipe: &'a ClPipe) -> Self {
+        self.kernel_args.push(KernelArg::Pipe { arg_index: index, arg: pipe });
+        self
+    }
+
+    /// Reserves `size` bytes of `__local` memory for the argument, per work-group.
+    pub fn arg_local(mut self, index: u32, size: usize) -> Self {
+        self.kernel_args.push(KernelArg::Local { arg_index: index, size });
+        self
+    }
+
     pub fn local_work_dims(mut self, x: usize, y: usize, z: usize) -> Self {
         self.local_work_dims = Some([x, y, z]);
         self
@@ -258,6 +229,16 @@ impl<'a> StageBuilder<'a> {
         self
     }
 
+    /// Runs this stage on the device at `device_index` (an index into the executor's queues)
+    /// instead of splitting it across every device.
+    ///
+    /// The memory objects the stage uses are migrated to that device after the previous
+    /// stage completes, so e.g. stage 1 can run on a CPU and stage 2 on a GPU.
+    pub fn on_device(mut self, device_index: usize) -> Self {
+        self.device_index = Some(device_index);
+        self
+    }
+
     /// Finalizes this stage and returns to the pipeline builder.
     pub fn finish(self) -> PipelineBuilder<'a> {
         let mut pb = self.pipeline_builder;
@@ -267,6 +248,7 @@ impl<'a> StageBuilder<'a> {
             global_work_dims: self.global_work_dims,
             global_work_offset: self.global_work_offset,
             local_work_dims: self.local_work_dims,
+            device_index: self.device_index,
         };
         pb.stages.push(stage);
         pb
@@ -0,0 +1,258 @@
+use crate::{cl_types::cl_event::ClEvent, error::ClError};
+
+/// # CommandKind
+///
+/// The type of work an enqueued command performed on the device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CommandKind {
+    Kernel,
+    Read,
+    /// Moved the task's memory objects to the device its kernel runs on
+    /// (`on_device`, `pre_migrate`).
+    Migration,
+}
+
+/// # CommandTiming
+///
+/// The four raw profiling timestamps (in device nanoseconds) of a single command.
+///
+/// - `queued`: the host enqueued the command.
+/// - `submit`: the command was handed to the device.
+/// - `start`: the device started executing it.
+/// - `end`: the device finished executing it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandTiming {
+    pub queued: u64,
+    pub submit: u64,
+    pub start: u64,
+    pub end: u64,
+}
+
+impl CommandTiming {
+    /// Reads all four timestamps from an event (requires profiling to be enabled on its queue).
+    #[cfg(feature = "CL_VERSION_1_1")]
+    pub fn from_event(event: &ClEvent) -> Result<Self, ClError> {
+        Ok(Self {
+            queued: event.get_profiling_command_queued()?,
+            submit: event.get_profiling_command_submit()?,
+            start: event.get_profiling_command_start()?,
+            end: event.get_profiling_command_end()?,
+        })
+    }
+
+    /// Time the command spent in the host queue before being submitted.
+    pub fn queue_latency_ns(&self) -> u64 {
+        self.submit.saturating_sub(self.queued)
+    }
+
+    /// Time between submission and the moment the device started executing it.
+    pub fn submit_latency_ns(&self) -> u64 {
+        self.start.saturating_sub(self.submit)
+    }
+
+    /// Time the device spent executing the command.
+    pub fn execution_ns(&self) -> u64 {
+        self.end.saturating_sub(self.start)
+    }
+
+    /// Time from enqueue to completion.
+    pub fn total_ns(&self) -> u64 {
+        self.end.saturating_sub(self.queued)
+    }
+}
+
+/// # CommandProfile
+///
+/// The timing of one command, tagged with where it ran.
+///
+/// `stage` is `None` for commands that do not belong to a pipeline stage
+/// (plain tasks and the final reads of a pipeline).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandProfile {
+    pub stage: Option<usize>,
+    pub device_index: usize,
+    pub kind: CommandKind,
+    pub timing: CommandTiming,
+}
+
+/// # ProfileReport
+///
+/// A detailed, per-command breakdown of a single task or pipeline run.
+#[derive(Debug, Clone, Default)]
+pub struct ProfileReport {
+    pub commands: Vec<CommandProfile>,
+}
+
+impl ProfileReport {
+    pub(crate) fn push_events(
+        &mut self,
+        stage: Option<usize>,
+        kind: CommandKind,
+        events: &[ClEvent],
+        device_indices: &[usize],
+    ) -> Result<(), ClError> {
+        for (event, device_index) in events.iter().zip(device_indices) {
+            self.commands.push(CommandProfile {
+                stage,
+                device_index: *device_index,
+                kind,
+                timing: CommandTiming::from_event(event)?,
+            });
+        }
+        Ok(())
+    }
+
+    /// Commands that belong to the given pipeline stage (`None` for non-stage commands).
+    pub fn stage(&self, stage: Option<usize>) -> impl Iterator<Item = &CommandProfile> {
+        self.commands.iter().filter(move |c| c.stage == stage)
+    }
+
+    /// Commands that ran on the given device.
+    pub fn device(&self, device_index: usize) -> impl Iterator<Item = &CommandProfile> {
+        self.commands.iter().filter(move |c| c.device_index == device_index)
+    }
+
+    /// Sum of the execution time of every command of the given kind.
+    pub fn total_execution_ns(&self, kind: CommandKind) -> u64 {
+        self.commands
+            .iter()
+            .filter(|c| c.kind == kind)
+            .map(|c| c.timing.execution_ns())
+            .sum()
+    }
+
+    /// Wall-clock time covered by the run, from the first enqueue to the last completion.
+    pub fn span_ns(&self) -> u64 {
+        let first = self.commands.iter().map(|c| c.timing.queued).min();
+        let last = self.commands.iter().map(|c| c.timing.end).max();
+        match (first, last) {
+            (Some(first), Some(last)) => last.saturating_sub(first),
+            _ => 0,
+        }
+    }
+}
+
+/// # DurationStats
+///
+/// Summary statistics (in nanoseconds) of a set of samples.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DurationStats {
+    pub count: usize,
+    pub min: u64,
+    pub max: u64,
+    pub mean: f64,
+    pub std_dev: f64,
+}
+
+impl DurationStats {
+    pub fn from_samples(samples: &[u64]) -> Self {
+        if samples.is_empty() {
+            return Self { count: 0, min: 0, max: 0, mean: 0.0, std_dev: 0.0 };
+        }
+
+        let count = samples.len();
+        let mean = samples.iter().map(|s| *s as f64).sum::<f64>() / count as f64;
+        let variance = samples
+            .iter()
+            .map(|s| (*s as f64 - mean).powi(2))
+            .sum::<f64>()
+            / count as f64;
+
+        Self {
+            count,
+            min: *samples.iter().min().unwrap_or(&0),
+            max: *samples.iter().max().unwrap_or(&0),
+            mean,
+            std_dev: variance.sqrt(),
+        }
+    }
+}
+
+/// # ProfileSummaryEntry
+///
+/// Aggregated timings of every command with the same stage, device and kind.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProfileSummaryEntry {
+    pub stage: Option<usize>,
+    pub device_index: usize,
+    pub kind: CommandKind,
+    pub queue_latency: DurationStats,
+    pub submit_latency: DurationStats,
+    pub execution: DurationStats,
+}
+
+/// # ProfileSummary
+///
+/// Statistics across repeated runs of the same task or pipeline.
+///
+/// # Example
+/// ```ignore
+/// let mut reports = Vec::new();
+/// for _ in 0..10 {
+///     reports.push(build_pipeline(&executor).run().await?.detailed_report()?);
+/// }
+/// let summary = ProfileSummary::from_reports(&reports);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ProfileSummary {
+    pub entries: Vec<ProfileSummaryEntry>,
+}
+
+impl ProfileSummary {
+    pub fn from_reports(reports: &[ProfileReport]) -> Self {
+        let mut keys: Vec<(Option<usize>, usize, CommandKind)> = Vec::new();
+        for command in reports.iter().flat_map(|r| r.commands.iter()) {
+            let key = (command.stage, command.device_index, command.kind);
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+
+        let entries = keys
+            .into_iter()
+            .map(|(stage, device_index, kind)| {
+                let matching: Vec<&CommandProfile> = reports
+                    .iter()
+                    .flat_map(|r| r.commands.iter())
+                    .filter(|c| c.stage == stage && c.device_index == device_index && c.kind == kind)
+                    .collect();
+                let collect = |f: fn(&CommandTiming) -> u64| -> Vec<u64> {
+                    matching.iter().map(|c| f(&c.timing)).collect()
+                };
+
+                ProfileSummaryEntry {
+                    stage,
+                    device_index,
+                    kind,
+                    queue_latency: DurationStats::from_samples(&collect(CommandTiming::queue_latency_ns)),
+                    submit_latency: DurationStats::from_samples(&collect(CommandTiming::submit_latency_ns)),
+                    execution: DurationStats::from_samples(&collect(CommandTiming::execution_ns)),
+                }
+            })
+            .collect();
+
+        Self { entries }
+    }
+}
+
+impl std::fmt::Display for ProfileSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{:<8} {:<7} {:<7} {:>6} {:>14} {:>14} {:>14} {:>14}", "stage", "device", "kind", "runs", "queue (ns)", "submit (ns)", "exec (ns)", "exec sd (ns)")?;
+        for entry in &self.entries {
+            let stage = entry.stage.map(|s| s.to_string()).unwrap_or_else(|| "-".to_string());
+            writeln!(
+                f,
+                "{:<8} {:<7} {:<7} {:>6} {:>14.0} {:>14.0} {:>14.0} {:>14.0}",
+                stage,
+                entry.device_index,
+                format!("{:?}", entry.kind),
+                entry.execution.count,
+                entry.queue_latency.mean,
+                entry.submit_latency.mean,
+                entry.execution.mean,
+                entry.execution.std_dev,
+            )?;
+        }
+        Ok(())
+    }
+}
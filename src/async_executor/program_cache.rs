@@ -0,0 +1,37 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::{
+    cl_types::cl_program::{Builded, ClProgram},
+    error::ClError,
+};
+
+/// # ProgramCache
+///
+/// Programs built from the library's own kernel sources (mipmap generation, image
+/// operations, ...), built on first use and shared by every later call.
+///
+/// Kernels are created per call from the cached program, since a kernel's arguments
+/// can't be set from several tasks at once.
+#[derive(Default)]
+pub(crate) struct ProgramCache {
+    programs: Mutex<HashMap<&'static str, Arc<ClProgram<Builded>>>>,
+}
+
+impl ProgramCache {
+    /// The program cached under `name`, built with `build` if there is none yet.
+    /// A failed build is not cached.
+    pub(crate) fn get_or_build(
+        &self,
+        name: &'static str,
+        build: impl FnOnce() -> Result<ClProgram<Builded>, ClError>,
+    ) -> Result<Arc<ClProgram<Builded>>, ClError> {
+        let mut programs = self.programs.lock().unwrap();
+        if let Some(program) = programs.get(name) {
+            return Ok(program.clone());
+        }
+        let program = Arc::new(build()?);
+        programs.insert(name, program.clone());
+        Ok(program)
+    }
+}
@@ -0,0 +1,262 @@
+use crate::{
+    async_executor::{AsyncExecutor, kernel_arg::KernelArg, launch},
+    cl_types::{
+        cl_buffer::ClBuffer,
+        cl_command_queue::ClCommandQueue,
+        cl_kernel::ClKernel,
+        memory_flags::MemoryFlags,
+    },
+    error::ClError,
+};
+use futures::{Stream, StreamExt};
+use std::os::raw::c_void;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// A set of device buffers used by one in-flight chunk.
+struct StreamSlot {
+    input: Option<(ClBuffer, usize)>,
+    output: Option<(ClBuffer, usize)>,
+}
+
+impl StreamSlot {
+    /// Returns a buffer of at least `size` bytes, reallocating only when the current one is too small.
+    /// New buffers count against the executor's memory budget.
+    fn ensure<'s>(
+        executor: &AsyncExecutor,
+        current: &'s mut Option<(ClBuffer, usize)>,
+        flags: MemoryFlags,
+        size: usize,
+    ) -> Result<&'s ClBuffer, ClError> {
+        let slot = match current.take() {
+            Some((buffer, capacity)) if capacity >= size => (buffer, capacity),
+            _ => (executor.create_buffer(&[flags], size, std::ptr::null_mut())?, size),
+        };
+        Ok(&current.insert(slot).0)
+    }
+}
+
+/// Shared state of a running stream.
+struct StreamState<'a> {
+    async_executor: &'a AsyncExecutor,
+    kernel: &'a ClKernel,
+    input_arg: u32,
+    output_arg: u32,
+    kernel_args: Vec<KernelArg<'a>>,
+    local_work_size: Option<usize>,
+    output_len: Box<dyn Fn(usize) -> usize + Send + Sync + 'a>,
+    global_work_size: Box<dyn Fn(usize) -> usize + Send + Sync + 'a>,
+    upload_queue: ClCommandQueue,
+    compute_queue: ClCommandQueue,
+    download_queue: ClCommandQueue,
+    slots: Vec<Mutex<StreamSlot>>,
+}
+
+impl<'a> StreamState<'a> {
+    async fn process<T: Copy, R: Copy>(&self, index: usize, chunk: &[T]) -> Result<Vec<R>, ClError> {
+        let output_len = (self.output_len)(chunk.len());
+        if chunk.is_empty() || output_len == 0 {
+            return Ok(Vec::new());
+        }
+
+        let in_bytes = std::mem::size_of_val(chunk);
+        let out_bytes = output_len * std::mem::size_of::<R>();
+
+        // Slot `index % depth` is only reused once the chunk that previously held it has been yielded.
+        let mut slot = self.slots[index % self.slots.len()].lock().await;
+        let slot = &mut *slot;
+        let input = StreamSlot::ensure(self.async_executor, &mut slot.input, MemoryFlags::ReadOnly, in_bytes)?;
+        let output = StreamSlot::ensure(self.async_executor, &mut slot.output, MemoryFlags::WriteOnly, out_bytes)?;
+
+        // 1. Upload
+        let upload_event = self
+            .upload_queue
+            .write_buffer(input, chunk.as_ptr() as *mut c_void, 0, in_bytes, None)
+            .await?;
+
+        // 2. Compute. Arguments are set and the kernel enqueued within the same poll,
+        // so chunks sharing the kernel object can't interleave their arguments.
+        let kernel_event = {
+            self.set_args(input, output)?;
+            let global = (self.global_work_size)(chunk.len());
+            let local = self.local_work_size.map(|l| vec![l]).unwrap_or_default();
+            self.compute_queue
+                .enqueue_nd_range_kernel(self.kernel, 1, Vec::new(), vec![global], local, None, Some(vec![upload_event]))
+                .await?
+        };
+
+        // 3. Download
+        let mut result: Vec<R> = Vec::with_capacity(output_len);
+        self.download_queue
+            .enqueue_read_buffer_raw(output, None, result.as_mut_ptr() as *mut c_void, out_bytes, Some(vec![kernel_event]))
+            .await?;
+        unsafe {
+            result.set_len(output_len);
+        }
+
+        Ok(result)
+    }
+
+    fn set_args(&self, input: &ClBuffer, output: &ClBuffer) -> Result<(), ClError> {
+        let pointer_size = std::mem::size_of::<*mut c_void>();
+        for (arg_index, buffer) in [(self.input_arg, input), (self.output_arg, output)] {
+            let handle = buffer.as_ptr();
+            unsafe {
+                self.kernel.set_args(arg_index, pointer_size, &handle as *const _ as *const _)?;
+            }
+        }
+
+        launch::set_kernel_args(self.kernel, &self.kernel_args)?;
+        Ok(())
+    }
+}
+
+/// # StreamBuilder
+///
+/// Configures a kernel that is applied to every chunk of an asynchronous stream of host data.
+///
+/// Each chunk goes through three steps on three separate queues of the same device:
+/// upload, kernel and download. With a depth of `n`, up to `n` chunks are in flight at
+/// once, so chunk N+1 is being uploaded while chunk N computes and chunk N-1 downloads.
+/// Results are yielded in the same order as the input chunks.
+///
+/// # Example
+/// ```ignore
+/// let results = executor.create_stream(&kernel)
+///     .input_arg(0)
+///     .output_arg(1)
+///     .arg_scalar(2, 2.0f32)
+///     .depth(3)
+///     .run::<f32, f32, _, _>(chunks)?;
+/// ```
+#[cfg(feature = "CL_VERSION_1_1")]
+pub struct StreamBuilder<'a> {
+    async_executor: &'a AsyncExecutor,
+    kernel: &'a ClKernel,
+    input_arg: u32,
+    output_arg: u32,
+    kernel_args: Vec<KernelArg<'a>>,
+    depth: usize,
+    device_index: Option<usize>,
+    local_work_size: Option<usize>,
+    output_len: Box<dyn Fn(usize) -> usize + Send + Sync + 'a>,
+    global_work_size: Box<dyn Fn(usize) -> usize + Send + Sync + 'a>,
+}
+
+impl<'a> StreamBuilder<'a> {
+    pub fn new(async_executor: &'a AsyncExecutor, kernel: &'a ClKernel) -> Self {
+        Self {
+            async_executor,
+            kernel,
+            input_arg: 0,
+            output_arg: 1,
+            kernel_args: Vec::new(),
+            depth: 3,
+            device_index: None,
+            local_work_size: None,
+            output_len: Box::new(|len| len),
+            global_work_size: Box::new(|len| len),
+        }
+    }
+
+    /// Kernel argument that receives the device copy of each chunk (default `0`).
+    pub fn input_arg(mut self, arg_index: u32) -> Self {
+        self.input_arg = arg_index;
+        self
+    }
+
+    /// Kernel argument that receives the output buffer of each chunk (default `1`).
+    pub fn output_arg(mut self, arg_index: u32) -> Self {
+        self.output_arg = arg_index;
+        self
+    }
+
+    /// Passes a simple value that stays the same for every chunk.
+    pub fn arg_scalar<T>(mut self, arg_index: u32, scalar: T) -> Self {
+        let arg = unsafe {
+            std::slice::from_raw_parts(&scalar as *const T as *const u8, std::mem::size_of::<T>()).to_vec()
+        };
+        self.kernel_args.push(KernelArg::Scalar { arg_index, arg });
+        self
+    }
+
+    /// Passes a buffer that stays the same for every chunk (e.g. coefficients).
+    pub fn arg_buffer(mut self, arg_index: u32, buffer: &'a ClBuffer) -> Self {
+        self.kernel_args.push(KernelArg::Buffer { arg_index, arg: buffer });
+        self
+    }
+
+    /// How many chunks may be in flight at once (default `3`, minimum `1`).
+    /// Each in-flight chunk owns its own pair of device buffers. The default lets the
+    /// upload, kernel and download of consecutive chunks all overlap; `2` saves a pair
+    /// of buffers at the cost of one of the three steps waiting.
+    pub fn depth(mut self, depth: usize) -> Self {
+        self.depth = depth.max(1);
+        self
+    }
+
+    /// Runs the stream on the device at `device_index` instead of the most powerful one.
+    pub fn on_device(mut self, device_index: usize) -> Self {
+        self.device_index = Some(device_index);
+        self
+    }
+
+    pub fn local_work_size(mut self, size: usize) -> Self {
+        self.local_work_size = Some(size);
+        self
+    }
+
+    /// Number of output elements produced for an input chunk of `len` elements (default: `len`).
+    pub fn output_len(mut self, f: impl Fn(usize) -> usize + Send + Sync + 'a) -> Self {
+        self.output_len = Box::new(f);
+        self
+    }
+
+    /// Global work size used for an input chunk of `len` elements (default: `len`).
+    pub fn global_work_size(mut self, f: impl Fn(usize) -> usize + Send + Sync + 'a) -> Self {
+        self.global_work_size = Box::new(f);
+        self
+    }
+
+    /// Starts processing `chunks` and returns the stream of results.
+    ///
+    /// Nothing is enqueued until the returned stream is polled.
+    pub fn run<T, R, C, S>(self, chunks: S) -> Result<impl Stream<Item = Result<Vec<R>, ClError>> + 'a, ClError>
+    where
+        T: Copy + 'a,
+        R: Copy + 'a,
+        C: AsRef<[T]> + 'a,
+        S: Stream<Item = C> + 'a,
+    {
+        let device_index = self
+            .device_index
+            .unwrap_or_else(|| self.async_executor.get_optimal_queue_index());
+
+        let state = Arc::new(StreamState {
+            async_executor: self.async_executor,
+            kernel: self.kernel,
+            input_arg: self.input_arg,
+            output_arg: self.output_arg,
+            kernel_args: self.kernel_args,
+            local_work_size: self.local_work_size,
+            output_len: self.output_len,
+            global_work_size: self.global_work_size,
+            upload_queue: self.async_executor.create_queue(device_index)?,
+            compute_queue: self.async_executor.create_queue(device_index)?,
+            download_queue: self.async_executor.create_queue(device_index)?,
+            slots: (0..self.depth)
+                .map(|_| Mutex::new(StreamSlot { input: None, output: None }))
+                .collect(),
+        });
+
+        let stream = chunks
+            .enumerate()
+            .map(move |(index, chunk)| {
+                let state = state.clone();
+                async move { state.process::<T, R>(index, chunk.as_ref()).await }
+            })
+            .buffered(self.depth);
+
+        Ok(stream)
+    }
+}
@@ -1,6 +1,5 @@
-use crate::{async_executor::{AsyncExecutor, kernel_arg::KernelArg}, cl_types::{cl_buffer::ClBuffer, cl_device::opencl_version::OpenCLVersion, cl_event::ClEvent, cl_image::ClImage, cl_kernel::ClKernel, cl_svm_buffer::ClSvmBuffer, cl_pipe::ClPipe}, error::ClError};
+use crate::{async_executor::{AsyncExecutor, kernel_arg::KernelArg, launch::{self, Launch}, profiling::{CommandKind, ProfileReport}}, cl_types::{cl_buffer::ClBuffer, cl_event::ClEvent, cl_image::ClImage, cl_kernel::ClKernel, cl_svm_buffer::ClSvmBuffer, cl_pipe::ClPipe}, error::ClError};
 use std::os::raw::c_void;
-use futures;
 
 #[cfg(feature = "CL_VERSION_1_1")]
 pub enum OutputRead<'a> {
@@ -13,6 +12,8 @@ pub enum OutputRead<'a> {
     Image {
         image: &'a ClImage,
         host_ptr: *mut c_void,
+        /// Size of the host slice in bytes.
+        size: usize,
         origin: [usize; 3],
         region: [usize; 3],
     }
@@ -33,6 +34,14 @@ pub struct TaskReport {
     pub kernel_execution_events: Vec<ClEvent>,
     /// Memory read events (when you use .read_buffer()).
     pub read_events: Vec<ClEvent>,
+    /// Index (into the executor's queues) of the device each kernel event ran on.
+    pub kernel_device_indices: Vec<usize>,
+    /// Index (into the executor's queues) of the device each read event ran on.
+    pub read_device_indices: Vec<usize>,
+    /// Events migrating the kernel's memory objects before it ran (with `on_device` or `pre_migrate`).
+    pub migration_events: Vec<ClEvent>,
+    /// Index (into the executor's queues) of the device each migration moved the objects to.
+    pub migration_device_indices: Vec<usize>,
 }
 
 #[cfg(feature = "CL_VERSION_1_1")]
@@ -41,9 +50,28 @@ impl TaskReport {
         Self {
             kernel_execution_events: Vec::new(),
             read_events: Vec::new(),
+            kernel_device_indices: Vec::new(),
+            read_device_indices: Vec::new(),
+            migration_events: Vec::new(),
+            migration_device_indices: Vec::new(),
         }
     }
 
+    pub(crate) fn push_kernel_event(&mut self, event: ClEvent, device_index: usize) {
+        self.kernel_execution_events.push(event);
+        self.kernel_device_indices.push(device_index);
+    }
+
+    pub(crate) fn push_read_event(&mut self, event: ClEvent, device_index: usize) {
+        self.read_events.push(event);
+        self.read_device_indices.push(device_index);
+    }
+
+    pub(crate) fn push_migration_event(&mut self, event: ClEvent, device_index: usize) {
+        self.migration_events.push(event);
+        self.migration_device_indices.push(device_index);
+    }
+
     /// Calculates the total time the kernels were running on the GPU.
     pub fn total_kernel_duration_ns(&self) -> u64 {
         self.kernel_execution_events.iter().filter_map(|e| e.get_duration_nanos().ok()).sum()
@@ -53,6 +81,20 @@ impl TaskReport {
     pub fn total_read_duration_ns(&self) -> u64 {
         self.read_events.iter().filter_map(|e| e.get_duration_nanos().ok()).sum()
     }
+
+    /// Breaks the task down into queued/submit/start/end timings per command and device.
+    /// Requires profiling to be enabled.
+    pub fn detailed_report(&self) -> Result<ProfileReport, ClError> {
+        let mut profile = ProfileReport::default();
+        self.append_to(&mut profile, None)?;
+        Ok(profile)
+    }
+
+    pub(crate) fn append_to(&self, profile: &mut ProfileReport, stage: Option<usize>) -> Result<(), ClError> {
+        profile.push_events(stage, CommandKind::Migration, &self.migration_events, &self.migration_device_indices)?;
+        profile.push_events(stage, CommandKind::Kernel, &self.kernel_execution_events, &self.kernel_device_indices)?;
+        profile.push_events(stage, CommandKind::Read, &self.read_events, &self.read_device_indices)
+    }
 }
 
 /// # TaskBuilder
@@ -72,6 +114,8 @@ pub struct TaskBuilder<'a> {
     output_reads: Vec<OutputRead<'a>>,
     wait_list: Option<Vec<ClEvent>>,
     profiling_enabled: bool,
+    device_index: Option<usize>,
+    pre_migrate: bool,
 }
 
 
@@ -87,6 +131,8 @@ impl<'a> TaskBuilder<'a> {
             output_reads: Vec::new(),
             wait_list: None,
             profiling_enabled: async_executor.is_profiling_enabled(),
+            device_index: None,
+            pre_migrate: false,
         }
     }
 
@@ -119,6 +165,12 @@ impl<'a> TaskBuilder<'a> {
         self.add_pipe(arg_index, pipe)
     }
 
+    /// Reserves `size` bytes of `__local` memory for the argument, per work-group.
+    pub fn arg_local(mut self, arg_index: u32, size: usize) -> Self {
+        self.kernel_args.push(KernelArg::Local { arg_index, size });
+        self
+    }
+
     /// Indicates that when the kernel finishes, you want this buffer to be copied
     /// automatically to your program's memory (host_memory).
     pub fn read_buffer<T>(mut self, buffer: &'a ClBuffer, host_memory: &mut [T]) -> Self {
@@ -130,11 +182,14 @@ impl<'a> TaskBuilder<'a> {
         self
     }
 
+    /// Like `read_buffer`, for a region of an image. `run` fails with `HostBufferTooSmall`
+    /// before launching anything if `host_memory` can't hold `region` tightly packed.
     #[cfg(feature = "CL_VERSION_1_2")]
     pub fn read_image<T>(mut self, image: &'a ClImage, host_memory: &mut [T], origin: [usize; 3], region: [usize; 3]) -> Self {
         self.output_reads.push(OutputRead::Image {
             image,
             host_ptr: host_memory.as_mut_ptr() as *mut c_void,
+            size: std::mem::size_of_val(host_memory),
             origin,
             region,
         });
@@ -196,6 +251,21 @@ impl<'a> TaskBuilder<'a> {
         self
     }
 
+    /// Runs the whole task on the device at `device_index` instead of splitting it
+    /// across every device. The buffers it uses are migrated there first.
+    pub fn on_device(mut self, device_index: usize) -> Self {
+        self.device_index = Some(device_index);
+        self
+    }
+
+    /// When the task is split across devices, migrates its buffers, images and pipes
+    /// to each device before that device's share starts, instead of letting the
+    /// driver move them on first access. Requires OpenCL 1.2; ignored otherwise.
+    pub fn pre_migrate(mut self) -> Self {
+        self.pre_migrate = true;
+        self
+    }
+
     pub fn add_wait_list(mut self, wait_list: Vec<ClEvent>) -> Self {
         self.wait_list = Some(wait_list);
         self
@@ -210,159 +280,24 @@ impl<'a> TaskBuilder<'a> {
     /// 4. Waits for them to finish (without blocking your CPU thread).
     /// 5. Reads the results back.
     pub async fn run(self) -> Result<TaskReport, ClError> {
+        launch::check_output_reads(&self.output_reads)?;
         let mut report = TaskReport::new();
-        let num_queues = self.async_executor.queues.len();
-        if num_queues == 0 {
-            return Err(ClError::Wrapper(crate::error::wrapper_error::WrapperError::PlatformsNotFound));
-        }
-
-        // Set kernel arguments
-        for arg in &self.kernel_args {
-            match arg {
-                KernelArg::Scalar { arg_index, arg } => {
-                    unsafe {
-                        self.kernel.set_args(*arg_index, arg.len(), arg.as_ptr() as *const _)?;
-                    }
-                }
-                KernelArg::Buffer { arg_index, arg } => {
-                    let handle = arg.as_ptr();
-                    unsafe {
-                        self.kernel.set_args(*arg_index, std::mem::size_of::<*mut std::os::raw::c_void>(), &handle as *const _ as *const _)?;
-                    }
-                }
-                KernelArg::Svm { arg_index, arg, len } => {
-                    unsafe {
-                        self.kernel.set_svm_arg(*arg_index, *len, *arg)?;
-                    }
-                }
-                KernelArg::Image { arg_index, arg } => {
-                    let handle = arg.as_ptr();
-                    unsafe {
-                        self.kernel.set_args(*arg_index, std::mem::size_of::<*mut std::os::raw::c_void>(), &handle as *const _ as *const _)?;
-                    }
-                }
-                #[cfg(feature = "CL_VERSION_2_0")]
-                KernelArg::Pipe { arg_index, arg } => {
-                    let handle = arg.as_ptr();
-                    unsafe {
-                        self.kernel.set_args(*arg_index, std::mem::size_of::<*mut std::os::raw::c_void>(), &handle as *const _ as *const _)?;
-                    }
-                }
-            }
-        }
-
-        let global_work_dims = self.global_work_dims.unwrap_or([1, 1, 1]);
-        let global_work_offset = self.global_work_offset.unwrap_or([0, 0, 0]);
-
-        let total_work = global_work_dims[0];
-        let total_weight: u64 = self.async_executor.weights.iter().sum();
-        
-        let mut futures = Vec::new();
-        let mut current_offset = global_work_offset[0];
-
-        for i in 0..num_queues {
-            let weight = self.async_executor.weights[i];
-            
-            // Calculate chunk size based on weight
-            // The last device takes whatever is left to avoid rounding errors
-            let chunk_size = if i == num_queues - 1 {
-                global_work_offset[0] + total_work - current_offset
-            } else {
-                ((total_work as u128 * weight as u128) / total_weight as u128) as usize
-            };
-
-            if chunk_size == 0 && i != num_queues - 1 {
-                continue;
-            }
-
-            let g_offset = vec![current_offset, global_work_offset[1], global_work_offset[2]];
-            let g_dims = vec![chunk_size, global_work_dims[1], global_work_dims[2]];
-            
-            // Infer work_dim from specified dims
-            let work_dim = if global_work_dims[2] > 1 || global_work_offset[2] > 0 {
-                3
-            } else if global_work_dims[1] > 1 || global_work_offset[1] > 0 {
-                2
-            } else {
-                1
-            };
-
-            let g_offset_trimmed = g_offset[..work_dim].to_vec();
-            let g_dims_trimmed = g_dims[..work_dim].to_vec();
-            let kernel_ref = self.kernel;
-            let queue = self.async_executor.queues[i].clone();
-            let wait_list = self.wait_list.clone();
-
-            let l_dims_trimmed = if let Some(ld) = self.local_work_dims {
-                ld[..work_dim].to_vec()
-            } else {
-                // Auto-tune logic
-                let version = self.async_executor.get_device_versions()[i];
-                let device_res = self.async_executor.get_devices();
-                
-                if let Ok(devices) = device_res {
-                    let device = devices[i].clone();
-                    if version >= OpenCLVersion::V2_0 && device.get_non_uniform_work_group_support().unwrap_or(false) {
-                        Vec::new() // NULL will let the driver decide with non-uniform support
-                    } else if let Ok(preferred) = kernel_ref.get_work_group_size(device) {
-                        // For simplicity, if 1D we use the preferred size
-                        if work_dim == 1 {
-                             vec![preferred]
-                        } else {
-                             Vec::new() // Safest choice for 2D/3D if we don't have factorization logic
-                        }
-                    } else {
-                        Vec::new()
-                    }
-                } else {
-                    Vec::new()
-                }
-            };
-
-            futures.push(async move {
-                let event = queue.enqueue_nd_range_kernel(
-                    kernel_ref,
-                    work_dim as u32,
-                    g_offset_trimmed,
-                    g_dims_trimmed,
-                    l_dims_trimmed,
-                    None,
-                    wait_list
-                ).await?;
-                Ok::<ClEvent, ClError>(event)
-            });
-
-            current_offset += chunk_size;
-        }
+        let launch = Launch {
+            kernel: self.kernel,
+            kernel_args: &self.kernel_args,
+            global_work_dims: self.global_work_dims.unwrap_or([1, 1, 1]),
+            global_work_offset: self.global_work_offset.unwrap_or([0, 0, 0]),
+            local_work_dims: self.local_work_dims,
+            device_index: self.device_index,
+            pre_migrate: self.pre_migrate,
+        };
 
-        let results = futures::future::join_all(futures).await;
-        for res in results {
-            let event = res?;
-            if self.profiling_enabled {
-                report.kernel_execution_events.push(event);
-            }
-        }
+        let profile = if self.profiling_enabled { Some(&mut report) } else { None };
+        let kernel_events = launch::launch(self.async_executor, &launch, self.wait_list.clone(), profile).await?;
 
         // Automatic Reads
-        if !self.output_reads.is_empty() {
-            let queue = self.async_executor.queues.first().ok_or(ClError::Wrapper(crate::error::wrapper_error::WrapperError::PlatformsNotFound))?;
-            for read in &self.output_reads {
-                match read {
-                    OutputRead::Buffer { buffer, host_ptr, size } => {
-                        let event = queue.enqueue_read_buffer_raw(*buffer, None, *host_ptr, *size, None).await?;
-                        if self.profiling_enabled {
-                            report.read_events.push(event);
-                        }
-                    }
-                    OutputRead::Image { image, host_ptr, origin, region } => {
-                        let event = queue.read_image_raw(*image, *origin, *region, 0, 0, *host_ptr, None).await?;
-                        if self.profiling_enabled {
-                            report.read_events.push(event);
-                        }
-                    }
-                }
-            }
-        }
+        let profile = if self.profiling_enabled { Some(&mut report) } else { None };
+        launch::read_outputs(self.async_executor, &self.output_reads, self.device_index, Some(kernel_events), profile).await?;
 
         Ok(report)
     }
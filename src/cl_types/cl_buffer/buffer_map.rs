@@ -0,0 +1,211 @@
+use std::marker::PhantomData;
+use std::ops::{Bound, RangeBounds};
+use std::os::raw::c_void;
+use std::ptr::null;
+
+use crate::{
+    cl_types::{cl_buffer::ClBuffer, cl_command_queue::ClCommandQueue, cl_event::ClEvent},
+    error::{ClError, wrapper_error::WrapperError},
+};
+
+/// # MapMode
+///
+/// How the host intends to write a region mapped with `BufferMapGuardMut`.
+/// Read-only mappings are made with `BufferMapGuard`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapMode {
+    /// The host reads and writes; changes are visible to the device after unmap.
+    Write,
+    /// The host overwrites the whole region. Its previous contents are not copied
+    /// to the host, which avoids a transfer on discrete GPUs.
+    WriteInvalidate,
+}
+
+impl MapMode {
+    pub fn to_u64(&self) -> u64 {
+        match self {
+            MapMode::Write => cl3::memory::CL_MAP_READ | cl3::memory::CL_MAP_WRITE,
+            MapMode::WriteInvalidate => cl3::memory::CL_MAP_WRITE_INVALIDATE_REGION,
+        }
+    }
+}
+
+/// A mapped region, unmapped on drop. Shared by the read and write guards.
+#[cfg(feature = "CL_VERSION_1_1")]
+struct Mapping<T> {
+    ptr: *mut T,
+    len: usize,
+    queue: ClCommandQueue,
+    buffer: *mut c_void,
+    unmapped: bool,
+}
+
+#[cfg(feature = "CL_VERSION_1_1")]
+impl<T> Mapping<T> {
+    /// Maps the elements of `buffer` selected by `range` (in elements of `T`) with `map_flags`.
+    async fn new(queue: &ClCommandQueue, buffer: &ClBuffer, range: impl RangeBounds<usize>, map_flags: u64) -> Result<Self, ClError> {
+        let elem = std::mem::size_of::<T>();
+        if elem == 0 {
+            return Err(ClError::Wrapper(WrapperError::ZeroSizedElement));
+        }
+        let buffer_len = buffer.get_size()? / elem;
+        let start = match range.start_bound() {
+            Bound::Included(start) => *start,
+            Bound::Excluded(start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(end) => end + 1,
+            Bound::Excluded(end) => *end,
+            Bound::Unbounded => buffer_len,
+        };
+        if start > end || end > buffer_len {
+            return Err(ClError::Wrapper(WrapperError::BufferRangeOutOfBounds {
+                offset: start,
+                len: end.saturating_sub(start),
+                buffer_len,
+            }));
+        }
+
+        let len = end - start;
+        let (_, mapped) = queue
+            .enqueue_map_buffer(buffer, map_flags, start * elem, len * elem, None)
+            .await?;
+
+        Ok(Self { ptr: mapped as *mut T, len, queue: queue.clone(), buffer: buffer.as_ptr(), unmapped: false })
+    }
+
+    async fn unmap(mut self) -> Result<ClEvent, ClError> {
+        self.unmapped = true;
+        self.queue
+            .enqueue_unmap_mem_object(self.buffer, self.ptr as *mut c_void, None)
+            .await
+    }
+
+    fn as_slice(&self) -> &[T] {
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr, self.len) }
+    }
+}
+
+#[cfg(feature = "CL_VERSION_1_1")]
+impl<T> Drop for Mapping<T> {
+    fn drop(&mut self) {
+        if self.unmapped {
+            return;
+        }
+        unsafe {
+            if let Ok(event) = cl3::command_queue::enqueue_unmap_mem_object(
+                self.queue.as_ptr(),
+                self.buffer,
+                self.ptr as *mut c_void,
+                0,
+                null(),
+            ) {
+                let _ = cl3::event::release_event(event);
+            }
+        }
+    }
+}
+
+/// # BufferMapGuard
+///
+/// A region of a `ClBuffer` mapped read-only into host memory, viewed as a slice of `T`.
+///
+/// On buffers created with `AllocHostPtr` (integrated GPUs, CPU devices) mapping
+/// usually shares memory with the device instead of copying it.
+/// Dropping the guard enqueues the unmap without waiting for it; call
+/// [`BufferMapGuard::unmap`] to wait for it. Use [`BufferMapGuardMut`] to write.
+#[cfg(feature = "CL_VERSION_1_1")]
+pub struct BufferMapGuard<'a, T> {
+    mapping: Mapping<T>,
+    _marker: PhantomData<&'a [T]>,
+}
+
+#[cfg(feature = "CL_VERSION_1_1")]
+impl<'a, T> BufferMapGuard<'a, T> {
+    /// Maps the elements of `buffer` selected by `range` (in elements of `T`) for reading.
+    ///
+    /// Fails with `ZeroSizedElement` if `T` is zero-sized.
+    pub async fn new(queue: &ClCommandQueue, buffer: &'a ClBuffer, range: impl RangeBounds<usize>) -> Result<Self, ClError> {
+        let mapping = Mapping::new(queue, buffer, range, cl3::memory::CL_MAP_READ).await?;
+        Ok(Self { mapping, _marker: PhantomData })
+    }
+
+    /// Unmaps the region and waits until it is released.
+    pub async fn unmap(self) -> Result<ClEvent, ClError> {
+        self.mapping.unmap().await
+    }
+}
+
+#[cfg(feature = "CL_VERSION_1_1")]
+impl<T> std::ops::Deref for BufferMapGuard<'_, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.mapping.as_slice()
+    }
+}
+
+/// # BufferMapGuardMut
+///
+/// A region of a `ClBuffer` mapped for writing, viewed as a mutable slice of `T`.
+///
+/// It borrows the buffer mutably, so no other mapping of it can alias the slice.
+/// Dropping the guard enqueues the unmap without waiting for it; call
+/// [`BufferMapGuardMut::unmap`] to wait until the device sees the changes.
+#[cfg(feature = "CL_VERSION_1_1")]
+pub struct BufferMapGuardMut<'a, T> {
+    mapping: Mapping<T>,
+    mode: MapMode,
+    _marker: PhantomData<&'a mut [T]>,
+}
+
+#[cfg(feature = "CL_VERSION_1_1")]
+impl<'a, T> BufferMapGuardMut<'a, T> {
+    /// Maps the elements of `buffer` selected by `range` (in elements of `T`) for writing.
+    ///
+    /// Fails with `ZeroSizedElement` if `T` is zero-sized.
+    pub async fn new(
+        queue: &ClCommandQueue,
+        buffer: &'a mut ClBuffer,
+        range: impl RangeBounds<usize>,
+        mode: MapMode,
+    ) -> Result<Self, ClError> {
+        let mapping = Mapping::new(queue, buffer, range, mode.to_u64()).await?;
+        Ok(Self { mapping, mode, _marker: PhantomData })
+    }
+
+    pub fn mode(&self) -> MapMode {
+        self.mode
+    }
+
+    /// Unmaps the region and waits until the device can use the buffer again.
+    pub async fn unmap(self) -> Result<ClEvent, ClError> {
+        self.mapping.unmap().await
+    }
+}
+
+#[cfg(feature = "CL_VERSION_1_1")]
+impl<T> std::ops::Deref for BufferMapGuardMut<'_, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.mapping.as_slice()
+    }
+}
+
+#[cfg(feature = "CL_VERSION_1_1")]
+impl<T> std::ops::DerefMut for BufferMapGuardMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.mapping.as_mut_slice()
+    }
+}
+
+#[cfg(feature = "CL_VERSION_1_1")]
+unsafe impl<T: Sync> Sync for Mapping<T> {}
+#[cfg(feature = "CL_VERSION_1_1")]
+unsafe impl<T: Send> Send for Mapping<T> {}
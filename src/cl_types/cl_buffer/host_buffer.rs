@@ -0,0 +1,171 @@
+use std::future::Future;
+use std::ops::Deref;
+use std::os::raw::c_void;
+use std::sync::Arc;
+
+use tokio::sync::oneshot;
+
+use crate::{
+    cl_types::{cl_buffer::ClBuffer, cl_context::ClContext, mem_object::MemObject, memory_flags::MemoryFlags},
+    error::{ClError, api_error::ApiError, wrapper_error::WrapperError},
+};
+
+/// Host memory kept alive for a `UseHostPtr` buffer until OpenCL destroys it.
+enum HostData<T> {
+    Owned(Vec<T>),
+    Shared(#[allow(dead_code)] Arc<[T]>),
+}
+
+struct HostOwner<T> {
+    data: HostData<T>,
+    /// Receives the `Vec` back if `HostBuffer::into_vec` is waiting for it.
+    give_back: Option<oneshot::Sender<Vec<T>>>,
+}
+
+/// Called by OpenCL once the buffer is destroyed and the host memory is no longer used.
+extern "C" fn release_host_data<T>(_mem: *mut c_void, user_data: *mut c_void) {
+    let owner = unsafe { Box::from_raw(user_data as *mut HostOwner<T>) };
+    if let (HostData::Owned(data), Some(give_back)) = (owner.data, owner.give_back) {
+        // If nobody is waiting, the Vec is dropped here
+        let _ = give_back.send(data);
+    }
+}
+
+/// # HostBuffer
+///
+/// A `UseHostPtr` buffer that owns the host memory behind it.
+///
+/// The data (a `Vec<T>`, `Box<[T]>` or `Arc<[T]>`) is handed to a
+/// `clSetMemObjectDestructorCallback` and only freed when OpenCL destroys the buffer,
+/// so it outlives every clone, sub-buffer and pending command. Owned data can be taken
+/// back with [`HostBuffer::into_vec`].
+///
+/// It dereferences to `ClBuffer`. Read the contents through `read_buffer` or a
+/// mapping, not through the original allocation: the device may cache it.
+///
+/// # Example
+/// ```ignore
+/// let buffer = executor.buffer_from_vec(&[MemoryFlags::ReadWrite], vec![0.0f32; 1024])?;
+/// executor.create_task(&kernel).arg_buffer(0, &buffer).global_work_dims(1024, 1, 1).run().await?;
+/// let data: Vec<f32> = buffer.into_vec().ok().unwrap().await?;
+/// ```
+#[cfg(feature = "CL_VERSION_1_1")]
+pub struct HostBuffer<T> {
+    buffer: ClBuffer,
+    len: usize,
+    given_back: Option<oneshot::Receiver<Vec<T>>>,
+}
+
+#[cfg(feature = "CL_VERSION_1_1")]
+impl<T: Copy + Send + 'static> HostBuffer<T> {
+    /// Creates a buffer over `data` (a `Vec<T>` or `Box<[T]>`), which it takes ownership of.
+    /// `UseHostPtr` is added to `flags`.
+    pub fn from_vec(context: &ClContext, flags: &[MemoryFlags], data: impl Into<Vec<T>>) -> Result<Self, ClError> {
+        let mut data: Vec<T> = data.into();
+        let len = data.len();
+        let ptr = data.as_mut_ptr() as *mut c_void;
+        let (give_back, given_back) = oneshot::channel();
+        let owner = HostOwner { data: HostData::Owned(data), give_back: Some(give_back) };
+        let buffer = Self::create(context, flags.to_vec(), ptr, len, owner)?;
+        Ok(Self { buffer, len, given_back: Some(given_back) })
+    }
+
+    /// Creates a read-only buffer over shared data.
+    ///
+    /// Neither the device nor the host may write into memory other owners can read, so the
+    /// access flags in `flags` are replaced by `ReadOnly` and `HostReadOnly` (or `HostNoAccess`
+    /// if given): `write_buffer` and write mappings fail with `CL_INVALID_OPERATION`.
+    /// Don't use it as the destination of `copy_buffer` or `fill_buffer`, which OpenCL doesn't restrict.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub fn from_arc(context: &ClContext, flags: &[MemoryFlags], data: Arc<[T]>) -> Result<Self, ClError>
+    where
+        T: Sync,
+    {
+        let mut flags: Vec<MemoryFlags> = flags
+            .iter()
+            .copied()
+            .filter(|f| !matches!(f, MemoryFlags::ReadWrite | MemoryFlags::WriteOnly | MemoryFlags::HostWriteOnly))
+            .collect();
+        #[cfg(feature = "CL_VERSION_2_0")]
+        flags.retain(|f| *f != MemoryFlags::KernelReadAndWrite);
+        if !flags.contains(&MemoryFlags::ReadOnly) {
+            flags.push(MemoryFlags::ReadOnly);
+        }
+        if !flags.iter().any(|f| matches!(f, MemoryFlags::HostReadOnly | MemoryFlags::HostNoAccess)) {
+            flags.push(MemoryFlags::HostReadOnly);
+        }
+
+        let len = data.len();
+        let ptr = data.as_ptr() as *mut c_void;
+        let owner = HostOwner { data: HostData::Shared(data), give_back: None };
+        let buffer = Self::create(context, flags, ptr, len, owner)?;
+        Ok(Self { buffer, len, given_back: None })
+    }
+
+    fn create(context: &ClContext, mut flags: Vec<MemoryFlags>, ptr: *mut c_void, len: usize, owner: HostOwner<T>) -> Result<ClBuffer, ClError> {
+        if !flags.contains(&MemoryFlags::UseHostPtr) {
+            flags.push(MemoryFlags::UseHostPtr);
+        }
+        // On failure `owner` is simply dropped with the error
+        let buffer = ClBuffer::new(context, &flags, len * std::mem::size_of::<T>(), ptr)?;
+
+        let user_data = Box::into_raw(Box::new(owner)) as *mut c_void;
+        if let Err(code) = unsafe { cl3::memory::set_mem_object_destructor_callback(buffer.as_ptr(), release_host_data::<T>, user_data) } {
+            // Release the buffer before the memory it points to
+            drop(buffer);
+            drop(unsafe { Box::from_raw(user_data as *mut HostOwner<T>) });
+            return Err(ClError::Api(ApiError::get_error(code)));
+        }
+        Ok(buffer)
+    }
+
+    /// Number of elements.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The untyped buffer.
+    pub fn as_buffer(&self) -> &ClBuffer {
+        &self.buffer
+    }
+
+    /// Destroys the buffer and returns a future that resolves to the `Vec` once OpenCL has
+    /// released it, i.e. after every pending command that uses the buffer has finished.
+    ///
+    /// Gives `self` back if the data is shared (`from_arc`) or if the buffer is not
+    /// [`ClBuffer::is_unique`] (clones, sub-buffers, image views), since it couldn't be destroyed yet.
+    /// The future fails with `HostDataLost` if OpenCL destroys the buffer without the data.
+    pub fn into_vec(mut self) -> Result<impl Future<Output = Result<Vec<T>, ClError>> + Send, Self> {
+        if !self.buffer.is_unique() {
+            return Err(self);
+        }
+        let Some(given_back) = self.given_back.take() else {
+            return Err(self);
+        };
+        drop(self.buffer);
+        Ok(async move { given_back.await.map_err(|_| ClError::Wrapper(WrapperError::HostDataLost)) })
+    }
+}
+
+#[cfg(feature = "CL_VERSION_1_1")]
+impl<T> Deref for HostBuffer<T> {
+    type Target = ClBuffer;
+
+    fn deref(&self) -> &ClBuffer {
+        &self.buffer
+    }
+}
+
+#[cfg(feature = "CL_VERSION_1_1")]
+impl<T> MemObject for HostBuffer<T> {
+    fn mem_ptr(&self) -> *mut c_void {
+        self.buffer.as_ptr()
+    }
+}
+
+unsafe impl<T: Send> Send for HostBuffer<T> {}
+unsafe impl<T: Sync> Sync for HostBuffer<T> {}
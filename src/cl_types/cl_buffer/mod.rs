@@ -1,8 +1,13 @@
+pub mod typed_buffer;
+pub mod buffer_map;
+pub mod host_buffer;
 use std::os::raw::c_void;
+use std::sync::Arc;
 
 use crate::{
-    cl_types::{memory_flags::MemoryFlags, cl_context::ClContext, releaseable::Releaseable},
-    error::{ClError, api_error::ApiError},
+    cl_types::{memory_flags::MemoryFlags, mem_object::MemObject, mem_object_type::MemObjectType, cl_context::ClContext, releaseable::Releaseable},
+    error::{ClError, api_error::ApiError, wrapper_error::WrapperError},
+    cl_mem_generate_getters,
 };
 
 /// # ClBuffer
@@ -11,12 +16,17 @@ use crate::{
 /// It's the most common resource for passing data to a kernel.
 pub struct ClBuffer {
     value: *mut c_void,
+    /// The buffer this one is a view of, kept alive as long as the view exists.
+    parent: Option<Box<ClBuffer>>,
+    /// Shared by the clones of this handle (including those held by sub-buffers and image
+    /// views), since OpenCL's reference count is only meant for debugging.
+    handles: Arc<()>,
 }
 
 impl ClBuffer {
     #[cfg(feature = "CL_VERSION_1_1")]
     pub fn from_ptr(value: *mut c_void) -> Self {
-        Self { value }
+        Self { value, parent: None, handles: Arc::new(()) }
     }
 
     #[cfg(feature = "CL_VERSION_1_1")]
@@ -40,10 +50,84 @@ impl ClBuffer {
         let raw_ptr =
             unsafe { cl3::memory::create_buffer(context.as_ptr(), flags, buffer_size, host_ptr) }
                 .map_err(|code| ClError::Api(ApiError::get_error(code)))?;
-        Ok(Self { value: raw_ptr })
+        Ok(Self { value: raw_ptr, parent: None, handles: Arc::new(()) })
     }
 
-    
+    /// Creates a view of `size` bytes of this buffer starting at byte `origin`.
+    ///
+    /// `origin` must be a multiple of `CL_DEVICE_MEM_BASE_ADDR_ALIGN` for every device
+    /// of the buffer's context, so the view can be used on any of them. The returned
+    /// buffer keeps this one alive and can be used anywhere a `ClBuffer` is.
+    #[cfg(feature = "CL_VERSION_1_1")]
+    pub fn create_sub_buffer(&self, flags: &[MemoryFlags], origin: usize, size: usize) -> Result<ClBuffer, ClError> {
+        let buffer_len = self.get_size()?;
+        if size == 0 || origin.checked_add(size).is_none_or(|end| end > buffer_len) {
+            return Err(ClError::Wrapper(WrapperError::BufferRangeOutOfBounds { offset: origin, len: size, buffer_len }));
+        }
+
+        let alignment = self.get_base_addr_align()?;
+        if !origin.is_multiple_of(alignment) {
+            return Err(ClError::Wrapper(WrapperError::MisalignedSubBufferOffset { origin, alignment }));
+        }
+
+        let region = cl3::types::cl_buffer_region { origin, size };
+        let raw_ptr = unsafe {
+            cl3::memory::create_sub_buffer(
+                self.value,
+                MemoryFlags::to_u64(&flags.to_vec()),
+                cl3::memory::CL_BUFFER_CREATE_TYPE_REGION,
+                &region as *const _ as *const c_void,
+            )
+        }
+        .map_err(|code| match ApiError::get_error(code) {
+            ApiError::ClMisalignedSubBufferOffset => ClError::Wrapper(WrapperError::MisalignedSubBufferOffset { origin, alignment }),
+            error => ClError::Api(error),
+        })?;
+
+        Ok(Self { value: raw_ptr, parent: Some(Box::new(self.clone())), handles: Arc::new(()) })
+    }
+
+    /// The buffer this one was created from with `create_sub_buffer`, if any.
+    pub fn parent(&self) -> Option<&ClBuffer> {
+        self.parent.as_deref()
+    }
+
+    /// Whether this is the only handle to the buffer: it has no clones, and no sub-buffer or
+    /// image view was created from it. Handles made from the raw pointer (`from_ptr`,
+    /// `get_associated_memobject`) are not counted.
+    pub fn is_unique(&self) -> bool {
+        Arc::strong_count(&self.handles) == 1
+    }
+
+    #[cfg(feature = "CL_VERSION_1_1")]
+    cl_mem_generate_getters!(
+        (get_mem_type, MemObjectType, cl3::memory::CL_MEM_TYPE),
+        (get_flags, Vec<MemoryFlags>, cl3::memory::CL_MEM_FLAGS),
+        (get_size, usize, cl3::memory::CL_MEM_SIZE),
+        (get_host_ptr, *mut c_void, cl3::memory::CL_MEM_HOST_PTR),
+        (get_map_count, u32, cl3::memory::CL_MEM_MAP_COUNT),
+        (get_reference_count, u32, cl3::memory::CL_MEM_REFERENCE_COUNT),
+        (get_context, ClContext, cl3::memory::CL_MEM_CONTEXT),
+        (get_associated_memobject, Option<ClBuffer>, cl3::memory::CL_MEM_ASSOCIATED_MEMOBJECT),
+        (get_offset, usize, cl3::memory::CL_MEM_OFFSET),
+    );
+
+    #[cfg(feature = "CL_VERSION_2_0")]
+    cl_mem_generate_getters!(
+        (get_uses_svm_pointer, bool, cl3::memory::CL_MEM_USES_SVM_POINTER),
+    );
+
+    /// Largest `CL_DEVICE_MEM_BASE_ADDR_ALIGN` (in bytes) among the devices of the buffer's context.
+    /// `create_sub_buffer` origins must be a multiple of it.
+    #[cfg(feature = "CL_VERSION_1_1")]
+    pub fn get_base_addr_align(&self) -> Result<usize, ClError> {
+        let mut alignment = 1;
+        for device in self.get_context()?.get_devices()? {
+            // Reported in bits
+            alignment = alignment.max(device.get_mem_base_addr_align()? as usize / 8);
+        }
+        Ok(alignment)
+    }
 }
 
 #[cfg(feature = "CL_VERSION_1_1")]
@@ -52,7 +136,22 @@ impl Clone for ClBuffer {
         unsafe {
             self.increase_reference_count();
         }
-        Self { value: self.value }
+        Self { value: self.value, parent: self.parent.clone(), handles: self.handles.clone() }
+    }
+}
+
+#[cfg(feature = "CL_VERSION_1_1")]
+impl std::fmt::Display for ClBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "ClBuffer {{ size: {}, flags: {:?}, offset: {}, map count: {}, references: {} }}",
+            self.get_size().unwrap_or_default(),
+            self.get_flags().unwrap_or_default(),
+            self.get_offset().unwrap_or_default(),
+            self.get_map_count().unwrap_or_default(),
+            self.get_reference_count().unwrap_or_default()
+        )
     }
 }
 
@@ -74,5 +173,12 @@ impl Releaseable for ClBuffer {
     }
 }
 
+#[cfg(feature = "CL_VERSION_1_1")]
+impl MemObject for ClBuffer {
+    fn mem_ptr(&self) -> *mut c_void {
+        self.value
+    }
+}
+
 unsafe impl Sync for ClBuffer {}
 unsafe impl Send for ClBuffer {}
\ No newline at end of file
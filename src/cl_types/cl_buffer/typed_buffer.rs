@@ -0,0 +1,188 @@
+use std::marker::PhantomData;
+use std::ops::{Deref, RangeBounds};
+use std::os::raw::c_void;
+
+use crate::{
+    cl_types::{
+        cl_buffer::{ClBuffer, buffer_map::{BufferMapGuard, BufferMapGuardMut, MapMode}}, cl_command_queue::ClCommandQueue, cl_context::ClContext, cl_event::ClEvent,
+        mem_object::MemObject, memory_flags::MemoryFlags,
+    },
+    error::{ClError, wrapper_error::WrapperError},
+};
+
+/// Bytes taken by `len` elements of `T`, or `BufferSizeOverflow` if they don't fit in a `usize`.
+pub(crate) fn elements_size<T>(len: usize) -> Result<usize, ClError> {
+    len.checked_mul(std::mem::size_of::<T>())
+        .ok_or(ClError::Wrapper(WrapperError::BufferSizeOverflow { len, element_size: std::mem::size_of::<T>() }))
+}
+
+/// # TypedBuffer
+///
+/// A `ClBuffer` that knows its element type and element count.
+///
+/// Sizes are given in elements instead of bytes, and every transfer checks the
+/// host slice against the buffer length. It dereferences to `ClBuffer`, so it can be
+/// passed anywhere an untyped buffer is expected (e.g. `arg_buffer(0, &typed)`).
+///
+/// The buffer keeps a handle to a command queue, which `to_vec`, `read` and `write` use.
+///
+/// # Example
+/// ```ignore
+/// let input = executor.typed_buffer_from_slice(&[MemoryFlags::ReadWrite], &[1.0f32, 2.0, 3.0])?;
+/// executor.create_task(&kernel).arg_buffer(0, &input).global_work_dims(input.len(), 1, 1).run().await?;
+/// let result: Vec<f32> = input.to_vec().await?;
+/// ```
+#[cfg(feature = "CL_VERSION_1_1")]
+pub struct TypedBuffer<T> {
+    buffer: ClBuffer,
+    len: usize,
+    queue: ClCommandQueue,
+    _marker: PhantomData<T>,
+}
+
+#[cfg(feature = "CL_VERSION_1_1")]
+impl<T: Copy> TypedBuffer<T> {
+    /// Creates an uninitialized buffer of `len` elements.
+    pub fn new(context: &ClContext, queue: &ClCommandQueue, flags: &[MemoryFlags], len: usize) -> Result<Self, ClError> {
+        let buffer = ClBuffer::new(context, &flags.to_vec(), elements_size::<T>(len)?, std::ptr::null_mut())?;
+        Ok(Self::wrap(buffer, len, queue))
+    }
+
+    /// Creates a buffer holding a copy of `data`.
+    pub fn from_slice(context: &ClContext, queue: &ClCommandQueue, flags: &[MemoryFlags], data: &[T]) -> Result<Self, ClError> {
+        let mut flags = flags.to_vec();
+        if !flags.iter().any(|f| matches!(f, MemoryFlags::CopyHostPtr | MemoryFlags::UseHostPtr)) {
+            flags.push(MemoryFlags::CopyHostPtr);
+        }
+        let buffer = ClBuffer::new(context, &flags, std::mem::size_of_val(data), data.as_ptr() as *mut c_void)?;
+        Ok(Self::wrap(buffer, data.len(), queue))
+    }
+
+    /// Wraps an existing buffer that holds `len` elements of `T`.
+    ///
+    /// Fails with `BufferRangeOutOfBounds` if the buffer is smaller than `len` elements.
+    pub fn from_buffer(buffer: ClBuffer, len: usize, queue: &ClCommandQueue) -> Result<Self, ClError> {
+        let buffer_len = buffer.get_size()?.checked_div(std::mem::size_of::<T>()).unwrap_or(usize::MAX);
+        if len > buffer_len {
+            return Err(ClError::Wrapper(WrapperError::BufferRangeOutOfBounds { offset: 0, len, buffer_len }));
+        }
+        Ok(Self::wrap(buffer, len, queue))
+    }
+
+    fn wrap(buffer: ClBuffer, len: usize, queue: &ClCommandQueue) -> Self {
+        Self { buffer, len, queue: queue.clone(), _marker: PhantomData }
+    }
+
+    /// Number of elements.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Size in bytes.
+    pub fn byte_size(&self) -> usize {
+        self.len * std::mem::size_of::<T>()
+    }
+
+    /// The untyped buffer.
+    pub fn as_buffer(&self) -> &ClBuffer {
+        &self.buffer
+    }
+
+    /// Drops the type information and returns the untyped buffer.
+    pub fn into_buffer(self) -> ClBuffer {
+        self.buffer
+    }
+
+    fn check_range(&self, offset: usize, len: usize) -> Result<(), ClError> {
+        if offset.checked_add(len).is_none_or(|end| end > self.len) {
+            return Err(ClError::Wrapper(WrapperError::BufferRangeOutOfBounds { offset, len, buffer_len: self.len }));
+        }
+        Ok(())
+    }
+
+    /// Reads the whole buffer into `host_memory`, which must have exactly `len()` elements.
+    pub async fn read(&self, host_memory: &mut [T]) -> Result<ClEvent, ClError> {
+        if host_memory.len() != self.len {
+            return Err(ClError::Wrapper(WrapperError::BufferLengthMismatch { expected: self.len, actual: host_memory.len() }));
+        }
+        self.read_at(0, host_memory).await
+    }
+
+    /// Reads `host_memory.len()` elements starting at element `offset`.
+    pub async fn read_at(&self, offset: usize, host_memory: &mut [T]) -> Result<ClEvent, ClError> {
+        self.check_range(offset, host_memory.len())?;
+        let elem = std::mem::size_of::<T>();
+        self.queue
+            .enqueue_read_buffer_raw(&self.buffer, Some(offset * elem), host_memory.as_mut_ptr() as *mut c_void, std::mem::size_of_val(host_memory), None)
+            .await
+    }
+
+    /// Overwrites the whole buffer with `data`, which must have exactly `len()` elements.
+    pub async fn write(&self, data: &[T]) -> Result<ClEvent, ClError> {
+        if data.len() != self.len {
+            return Err(ClError::Wrapper(WrapperError::BufferLengthMismatch { expected: self.len, actual: data.len() }));
+        }
+        self.write_at(0, data).await
+    }
+
+    /// Writes `data` starting at element `offset`.
+    pub async fn write_at(&self, offset: usize, data: &[T]) -> Result<ClEvent, ClError> {
+        self.check_range(offset, data.len())?;
+        let elem = std::mem::size_of::<T>();
+        self.queue
+            .write_buffer(&self.buffer, data.as_ptr() as *mut c_void, offset * elem, std::mem::size_of_val(data), None)
+            .await
+    }
+
+    /// Maps the elements selected by `range` into host memory for reading.
+    pub async fn map(&self, range: impl RangeBounds<usize>) -> Result<BufferMapGuard<'_, T>, ClError> {
+        BufferMapGuard::new(&self.queue, &self.buffer, range).await
+    }
+
+    /// Maps the elements selected by `range` into host memory for writing.
+    pub async fn map_mut(&mut self, range: impl RangeBounds<usize>, mode: MapMode) -> Result<BufferMapGuardMut<'_, T>, ClError> {
+        BufferMapGuardMut::new(&self.queue, &mut self.buffer, range, mode).await
+    }
+
+    /// Copies the whole buffer back to a new `Vec`.
+    pub async fn to_vec(&self) -> Result<Vec<T>, ClError> {
+        let mut host: Vec<T> = Vec::with_capacity(self.len);
+        self.queue
+            .enqueue_read_buffer_raw(&self.buffer, None, host.as_mut_ptr() as *mut c_void, self.byte_size(), None)
+            .await?;
+        unsafe {
+            host.set_len(self.len);
+        }
+        Ok(host)
+    }
+}
+
+#[cfg(feature = "CL_VERSION_1_1")]
+impl<T> Deref for TypedBuffer<T> {
+    type Target = ClBuffer;
+
+    fn deref(&self) -> &ClBuffer {
+        &self.buffer
+    }
+}
+
+#[cfg(feature = "CL_VERSION_1_1")]
+impl<T> MemObject for TypedBuffer<T> {
+    fn mem_ptr(&self) -> *mut c_void {
+        self.buffer.as_ptr()
+    }
+}
+
+#[cfg(feature = "CL_VERSION_1_1")]
+impl<T> Clone for TypedBuffer<T> {
+    fn clone(&self) -> Self {
+        Self { buffer: self.buffer.clone(), len: self.len, queue: self.queue.clone(), _marker: PhantomData }
+    }
+}
+
+unsafe impl<T: Send> Send for TypedBuffer<T> {}
+unsafe impl<T: Sync> Sync for TypedBuffer<T> {}
@@ -0,0 +1,48 @@
+use crate::cl_types::cl_image::image_channel_data_type::ClImageChannelType;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// # FillColor
+///
+/// A channel type `fill_image` accepts for its RGBA color: OpenCL reads exactly four
+/// `f32`s, `i32`s or `u32`s, depending on the image's channel type.
+pub trait FillColor: sealed::Sealed + Copy + Send + 'static {
+    /// Name shown in `FillColorMismatch`.
+    const NAME: &'static str;
+
+    /// Whether images with channels of `channel_type` are filled with this type.
+    fn fills(channel_type: ClImageChannelType) -> bool;
+}
+
+impl sealed::Sealed for f32 {}
+impl sealed::Sealed for i32 {}
+impl sealed::Sealed for u32 {}
+
+/// Normalized and float channel types.
+impl FillColor for f32 {
+    const NAME: &'static str = "f32";
+
+    fn fills(channel_type: ClImageChannelType) -> bool {
+        channel_type.reads_as_float()
+    }
+}
+
+/// Signed integer channel types.
+impl FillColor for i32 {
+    const NAME: &'static str = "i32";
+
+    fn fills(channel_type: ClImageChannelType) -> bool {
+        matches!(channel_type, ClImageChannelType::SignedInt8 | ClImageChannelType::SignedInt16 | ClImageChannelType::SignedInt32)
+    }
+}
+
+/// Unsigned integer channel types.
+impl FillColor for u32 {
+    const NAME: &'static str = "u32";
+
+    fn fills(channel_type: ClImageChannelType) -> bool {
+        matches!(channel_type, ClImageChannelType::UnsignedInt8 | ClImageChannelType::UnsignedInt16 | ClImageChannelType::UnsignedInt32)
+    }
+}
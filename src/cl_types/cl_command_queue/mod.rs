@@ -1,4 +1,5 @@
 pub mod command_queue_parameters;
+pub mod fill_color;
 use std::os::raw::c_void;
 use std::ptr::null;
 
@@ -6,6 +7,9 @@ use crate::cl_types::cl_buffer::ClBuffer;
 use crate::cl_types::cl_image::ClImage;
 use crate::error::ClError;
 use crate::error::api_error::ApiError;
+use crate::error::wrapper_error::WrapperError;
+#[cfg(feature = "CL_VERSION_1_2")]
+use crate::cl_types::cl_command_queue::fill_color::FillColor;
 use crate::{
     cl_command_queue_generate_getters,
     cl_types::{
@@ -20,6 +24,19 @@ struct SendPtr(*mut c_void);
 unsafe impl Send for SendPtr {}
 unsafe impl Sync for SendPtr {}
 
+/// Image origin without a mip level.
+fn pad_origin(origin: [usize; 3]) -> [usize; 4] {
+    [origin[0], origin[1], origin[2], 0]
+}
+
+/// Origin and region given as `Vec`s, which must have 3 coordinates each.
+fn image_coords(origin: &[usize], region: &[usize]) -> Result<([usize; 3], [usize; 3]), ClError> {
+    match (origin.try_into(), region.try_into()) {
+        (Ok(origin), Ok(region)) => Ok((origin, region)),
+        _ => Err(ClError::Wrapper(WrapperError::InvalidImageCoordinates { origin_len: origin.len(), region_len: region.len() })),
+    }
+}
+
 pub struct ClCommandQueue {
     value: *mut c_void,
 }
@@ -223,8 +240,8 @@ impl ClCommandQueue {
         let offset = offset.unwrap_or(0);
         let q_ptr = SendPtr(self.as_ptr());
         let b_ptr = SendPtr(buffer.as_ptr());
+        let len = std::mem::size_of_val(host_memory);
         let h_ptr = SendPtr(host_memory.as_mut_ptr() as *mut c_void);
-        let len = host_memory.len();
 
         // Convert wait list to Send-safe SendPtrs
         let wait_ptrs: Option<Vec<SendPtr>> =
@@ -302,10 +319,11 @@ impl ClCommandQueue {
     }
 
     #[cfg(feature = "CL_VERSION_1_1")]
+    /// `origin` has room for the mip level of `cl_khr_mipmap_image`, see `ClImage::mip_origin`.
     fn read_image_raw_inner(
         q_ptr: SendPtr,
         i_ptr: SendPtr,
-        origin: [usize; 3],
+        origin: [usize; 4],
         region: [usize; 3],
         row_pitch: usize,
         slice_pitch: usize,
@@ -366,7 +384,7 @@ impl ClCommandQueue {
             let event = Self::read_image_raw_inner(
                 q_ptr,
                 i_ptr,
-                origin,
+                pad_origin(origin),
                 region,
                 row_pitch,
                 slice_pitch,
@@ -378,30 +396,40 @@ impl ClCommandQueue {
         }
     }
 
+    /// Reads `region` of `image` into `host_memory`, laid out with `row_pitch` and `slice_pitch`
+    /// (0 meaning tightly packed).
+    ///
+    /// Fails with `InvalidImageCoordinates` unless `origin` and `region` have 3 coordinates,
+    /// and with `HostBufferTooSmall` if `host_memory` can't hold the region.
     #[cfg(feature = "CL_VERSION_1_1")]
-    pub fn read_image(
+    pub fn read_image<T>(
         &self,
         image: ClImage,
         origin: Vec<usize>,
         region: Vec<usize>,
         row_pitch: usize,
         slice_pitch: usize,
-        buffer: *mut c_void,
+        host_memory: &mut [T],
         event_wait_list: Option<Vec<ClEvent>>,
     ) -> impl Future<Output = Result<ClEvent, ClError>> + Send + '_ {
         let q_ptr = SendPtr(self.as_ptr());
         let i_ptr = SendPtr(image.as_ptr());
-        let b_ptr = SendPtr(buffer);
+        let b_ptr = SendPtr(host_memory.as_mut_ptr() as *mut c_void);
+        let coords = image_coords(&origin, &region).and_then(|(origin, region)| {
+            image.check_host_region_pitched(region, row_pitch, slice_pitch, std::mem::size_of_val(host_memory))?;
+            Ok((origin, region))
+        });
 
         let wait_ptrs: Option<Vec<SendPtr>> =
             event_wait_list.map(|v| v.iter().map(|e| SendPtr(e.as_ptr())).collect());
 
         async move {
+            let (origin, region) = coords?;
             let event = Self::read_image_raw_inner(
                 q_ptr,
                 i_ptr,
-                origin.as_slice().try_into().unwrap_or([0, 0, 0]),
-                region.as_slice().try_into().unwrap_or([0, 0, 0]),
+                pad_origin(origin),
+                region,
                 row_pitch,
                 slice_pitch,
                 b_ptr,
@@ -416,7 +444,7 @@ impl ClCommandQueue {
     fn write_image_raw_inner(
         q_ptr: SendPtr,
         i_ptr: SendPtr,
-        origin: [usize; 3],
+        origin: [usize; 4],
         region: [usize; 3],
         row_pitch: usize,
         slice_pitch: usize,
@@ -477,7 +505,7 @@ impl ClCommandQueue {
             let event = Self::write_image_raw_inner(
                 q_ptr,
                 i_ptr,
-                origin,
+                pad_origin(origin),
                 region,
                 row_pitch,
                 slice_pitch,
@@ -489,30 +517,40 @@ impl ClCommandQueue {
         }
     }
 
+    /// Writes `host_memory`, laid out with `row_pitch` and `slice_pitch` (0 meaning tightly
+    /// packed), to `region` of `image`.
+    ///
+    /// Fails with `InvalidImageCoordinates` unless `origin` and `region` have 3 coordinates,
+    /// and with `HostBufferTooSmall` if `host_memory` can't hold the region.
     #[cfg(feature = "CL_VERSION_1_1")]
-    pub fn write_image(
+    pub fn write_image<T>(
         &self,
         image: ClImage,
         origin: Vec<usize>,
         region: Vec<usize>,
         row_pitch: usize,
         slice_pitch: usize,
-        buffer: *mut c_void,
+        host_memory: &[T],
         event_wait_list: Option<Vec<ClEvent>>,
     ) -> impl Future<Output = Result<ClEvent, ClError>> + Send + '_ {
         let q_ptr = SendPtr(self.as_ptr());
         let i_ptr = SendPtr(image.as_ptr());
-        let b_ptr = SendPtr(buffer);
+        let b_ptr = SendPtr(host_memory.as_ptr() as *mut c_void);
+        let coords = image_coords(&origin, &region).and_then(|(origin, region)| {
+            image.check_host_region_pitched(region, row_pitch, slice_pitch, std::mem::size_of_val(host_memory))?;
+            Ok((origin, region))
+        });
 
         let wait_ptrs: Option<Vec<SendPtr>> =
             event_wait_list.map(|v| v.iter().map(|e| SendPtr(e.as_ptr())).collect());
 
         async move {
+            let (origin, region) = coords?;
             let event = Self::write_image_raw_inner(
                 q_ptr,
                 i_ptr,
-                origin.as_slice().try_into().unwrap_or([0, 0, 0]),
-                region.as_slice().try_into().unwrap_or([0, 0, 0]),
+                pad_origin(origin),
+                region,
                 row_pitch,
                 slice_pitch,
                 b_ptr,
@@ -523,6 +561,62 @@ impl ClCommandQueue {
         }
     }
 
+    /// Reads `region` of mip level `level` (`cl_khr_mipmap_image`); `origin` is within the level.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub fn read_image_level_raw(
+        &self,
+        image: &ClImage,
+        level: u32,
+        origin: [usize; 3],
+        region: [usize; 3],
+        row_pitch: usize,
+        slice_pitch: usize,
+        buffer: *mut c_void,
+        event_wait_list: Option<Vec<ClEvent>>,
+    ) -> impl Future<Output = Result<ClEvent, ClError>> + Send + '_ {
+        let q_ptr = SendPtr(self.as_ptr());
+        let i_ptr = SendPtr(image.as_ptr());
+        let b_ptr = SendPtr(buffer);
+        let origin = image.mip_origin(origin, level);
+
+        let wait_ptrs: Option<Vec<SendPtr>> =
+            event_wait_list.map(|v| v.iter().map(|e| SendPtr(e.as_ptr())).collect());
+
+        async move {
+            let event = Self::read_image_raw_inner(q_ptr, i_ptr, origin?, region, row_pitch, slice_pitch, b_ptr, wait_ptrs)?;
+            event.event_future().await;
+            Ok(event)
+        }
+    }
+
+    /// Writes `region` of mip level `level` (`cl_khr_mipmap_image`); `origin` is within the level.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub fn write_image_level_raw(
+        &self,
+        image: &ClImage,
+        level: u32,
+        origin: [usize; 3],
+        region: [usize; 3],
+        row_pitch: usize,
+        slice_pitch: usize,
+        buffer: *const c_void,
+        event_wait_list: Option<Vec<ClEvent>>,
+    ) -> impl Future<Output = Result<ClEvent, ClError>> + Send + '_ {
+        let q_ptr = SendPtr(self.as_ptr());
+        let i_ptr = SendPtr(image.as_ptr());
+        let b_ptr = SendPtr(buffer as *mut c_void);
+        let origin = image.mip_origin(origin, level);
+
+        let wait_ptrs: Option<Vec<SendPtr>> =
+            event_wait_list.map(|v| v.iter().map(|e| SendPtr(e.as_ptr())).collect());
+
+        async move {
+            let event = Self::write_image_raw_inner(q_ptr, i_ptr, origin?, region, row_pitch, slice_pitch, b_ptr, wait_ptrs)?;
+            event.event_future().await;
+            Ok(event)
+        }
+    }
+
     fn write_buffer_inner(
         q_ptr: SendPtr,
         b_ptr: SendPtr,
@@ -583,6 +677,1186 @@ impl ClCommandQueue {
         }
     }
 
+    /// Reads a 2D or 3D region of a buffer into host memory.
+    ///
+    /// Origins and region are `[x in bytes, y in rows, z in slices]`. Pitches of `0`
+    /// are computed from the region as if the data were tightly packed.
+    #[cfg(feature = "CL_VERSION_1_1")]
+    fn read_buffer_rect_inner(
+        q_ptr: SendPtr,
+        b_ptr: SendPtr,
+        buffer_origin: [usize; 3],
+        host_origin: [usize; 3],
+        region: [usize; 3],
+        buffer_pitch: (usize, usize),
+        host_pitch: (usize, usize),
+        h_ptr: SendPtr,
+        wait_ptrs: Option<Vec<SendPtr>>,
+    ) -> Result<ClEvent, ClError> {
+        let res = {
+            let wait_raw: Vec<*mut c_void> = wait_ptrs
+                .map(|v| v.iter().map(|p| p.0).collect())
+                .unwrap_or_default();
+
+            let (num_wait, wait_ptr) = if wait_raw.is_empty() {
+                (0, null())
+            } else {
+                (wait_raw.len() as u32, wait_raw.as_ptr())
+            };
+
+            unsafe {
+                cl3::command_queue::enqueue_read_buffer_rect(
+                    q_ptr.0,
+                    b_ptr.0,
+                    0, // CL_FALSE (Non-blocking)
+                    buffer_origin.as_ptr(),
+                    host_origin.as_ptr(),
+                    region.as_ptr(),
+                    buffer_pitch.0,
+                    buffer_pitch.1,
+                    host_pitch.0,
+                    host_pitch.1,
+                    h_ptr.0,
+                    num_wait,
+                    wait_ptr,
+                )
+            }
+        };
+
+        Ok(ClEvent::from_ptr(
+            res.map_err(|code| ClError::Api(ApiError::get_error(code)))?,
+        ))
+    }
+
+    #[cfg(feature = "CL_VERSION_1_1")]
+    pub fn read_buffer_rect(
+        &self,
+        buffer: &ClBuffer,
+        buffer_origin: [usize; 3],
+        host_origin: [usize; 3],
+        region: [usize; 3],
+        buffer_row_pitch: usize,
+        buffer_slice_pitch: usize,
+        host_row_pitch: usize,
+        host_slice_pitch: usize,
+        host_ptr: *mut c_void,
+        event_wait_list: Option<Vec<ClEvent>>,
+    ) -> impl Future<Output = Result<ClEvent, ClError>> + Send + '_ {
+        let q_ptr = SendPtr(self.as_ptr());
+        let b_ptr = SendPtr(buffer.as_ptr());
+        let h_ptr = SendPtr(host_ptr);
+
+        let wait_ptrs: Option<Vec<SendPtr>> =
+            event_wait_list.map(|v| v.iter().map(|e| SendPtr(e.as_ptr())).collect());
+
+        async move {
+            let event = Self::read_buffer_rect_inner(
+                q_ptr,
+                b_ptr,
+                buffer_origin,
+                host_origin,
+                region,
+                (buffer_row_pitch, buffer_slice_pitch),
+                (host_row_pitch, host_slice_pitch),
+                h_ptr,
+                wait_ptrs,
+            )?;
+            event.event_future().await;
+            Ok(event)
+        }
+    }
+
+    /// Writes a 2D or 3D region of host memory into a buffer.
+    ///
+    /// Same conventions as [`ClCommandQueue::read_buffer_rect`].
+    #[cfg(feature = "CL_VERSION_1_1")]
+    fn write_buffer_rect_inner(
+        q_ptr: SendPtr,
+        b_ptr: SendPtr,
+        buffer_origin: [usize; 3],
+        host_origin: [usize; 3],
+        region: [usize; 3],
+        buffer_pitch: (usize, usize),
+        host_pitch: (usize, usize),
+        h_ptr: SendPtr,
+        wait_ptrs: Option<Vec<SendPtr>>,
+    ) -> Result<ClEvent, ClError> {
+        let res = {
+            let wait_raw: Vec<*mut c_void> = wait_ptrs
+                .map(|v| v.iter().map(|p| p.0).collect())
+                .unwrap_or_default();
+
+            let (num_wait, wait_ptr) = if wait_raw.is_empty() {
+                (0, null())
+            } else {
+                (wait_raw.len() as u32, wait_raw.as_ptr())
+            };
+
+            unsafe {
+                cl3::command_queue::enqueue_write_buffer_rect(
+                    q_ptr.0,
+                    b_ptr.0,
+                    0, // CL_FALSE (Non-blocking)
+                    buffer_origin.as_ptr(),
+                    host_origin.as_ptr(),
+                    region.as_ptr(),
+                    buffer_pitch.0,
+                    buffer_pitch.1,
+                    host_pitch.0,
+                    host_pitch.1,
+                    h_ptr.0,
+                    num_wait,
+                    wait_ptr,
+                )
+            }
+        };
+
+        Ok(ClEvent::from_ptr(
+            res.map_err(|code| ClError::Api(ApiError::get_error(code)))?,
+        ))
+    }
+
+    #[cfg(feature = "CL_VERSION_1_1")]
+    pub fn write_buffer_rect(
+        &self,
+        buffer: &ClBuffer,
+        buffer_origin: [usize; 3],
+        host_origin: [usize; 3],
+        region: [usize; 3],
+        buffer_row_pitch: usize,
+        buffer_slice_pitch: usize,
+        host_row_pitch: usize,
+        host_slice_pitch: usize,
+        host_ptr: *const c_void,
+        event_wait_list: Option<Vec<ClEvent>>,
+    ) -> impl Future<Output = Result<ClEvent, ClError>> + Send + '_ {
+        let q_ptr = SendPtr(self.as_ptr());
+        let b_ptr = SendPtr(buffer.as_ptr());
+        let h_ptr = SendPtr(host_ptr as *mut c_void);
+
+        let wait_ptrs: Option<Vec<SendPtr>> =
+            event_wait_list.map(|v| v.iter().map(|e| SendPtr(e.as_ptr())).collect());
+
+        async move {
+            let event = Self::write_buffer_rect_inner(
+                q_ptr,
+                b_ptr,
+                buffer_origin,
+                host_origin,
+                region,
+                (buffer_row_pitch, buffer_slice_pitch),
+                (host_row_pitch, host_slice_pitch),
+                h_ptr,
+                wait_ptrs,
+            )?;
+            event.event_future().await;
+            Ok(event)
+        }
+    }
+
+    /// Copies `size` bytes from `src` to `dst` on the device.
+    #[cfg(feature = "CL_VERSION_1_1")]
+    fn copy_buffer_inner(
+        q_ptr: SendPtr,
+        src_ptr: SendPtr,
+        dst_ptr: SendPtr,
+        src_offset: usize,
+        dst_offset: usize,
+        size: usize,
+        wait_ptrs: Option<Vec<SendPtr>>,
+    ) -> Result<ClEvent, ClError> {
+        let res = {
+            let wait_raw: Vec<*mut c_void> = wait_ptrs
+                .map(|v| v.iter().map(|p| p.0).collect())
+                .unwrap_or_default();
+
+            let (num_wait, wait_ptr) = if wait_raw.is_empty() {
+                (0, null())
+            } else {
+                (wait_raw.len() as u32, wait_raw.as_ptr())
+            };
+
+            unsafe {
+                cl3::command_queue::enqueue_copy_buffer(
+                    q_ptr.0,
+                    src_ptr.0,
+                    dst_ptr.0,
+                    src_offset,
+                    dst_offset,
+                    size,
+                    num_wait,
+                    wait_ptr,
+                )
+            }
+        };
+
+        Ok(ClEvent::from_ptr(
+            res.map_err(|code| ClError::Api(ApiError::get_error(code)))?,
+        ))
+    }
+
+    #[cfg(feature = "CL_VERSION_1_1")]
+    pub fn copy_buffer(
+        &self,
+        src: &ClBuffer,
+        dst: &ClBuffer,
+        src_offset: usize,
+        dst_offset: usize,
+        size: usize,
+        event_wait_list: Option<Vec<ClEvent>>,
+    ) -> impl Future<Output = Result<ClEvent, ClError>> + Send + '_ {
+        let q_ptr = SendPtr(self.as_ptr());
+        let src_ptr = SendPtr(src.as_ptr());
+        let dst_ptr = SendPtr(dst.as_ptr());
+
+        let wait_ptrs: Option<Vec<SendPtr>> =
+            event_wait_list.map(|v| v.iter().map(|e| SendPtr(e.as_ptr())).collect());
+
+        async move {
+            let event = Self::copy_buffer_inner(q_ptr, src_ptr, dst_ptr, src_offset, dst_offset, size, wait_ptrs)?;
+            event.event_future().await;
+            Ok(event)
+        }
+    }
+
+    /// Copies a 2D or 3D region between two buffers on the device.
+    ///
+    /// Origins and region are `[x in bytes, y in rows, z in slices]`. Pitches of `0`
+    /// are computed from the region as if the data were tightly packed.
+    #[cfg(feature = "CL_VERSION_1_1")]
+    fn copy_buffer_rect_inner(
+        q_ptr: SendPtr,
+        src_ptr: SendPtr,
+        dst_ptr: SendPtr,
+        src_origin: [usize; 3],
+        dst_origin: [usize; 3],
+        region: [usize; 3],
+        src_pitch: (usize, usize),
+        dst_pitch: (usize, usize),
+        wait_ptrs: Option<Vec<SendPtr>>,
+    ) -> Result<ClEvent, ClError> {
+        let res = {
+            let wait_raw: Vec<*mut c_void> = wait_ptrs
+                .map(|v| v.iter().map(|p| p.0).collect())
+                .unwrap_or_default();
+
+            let (num_wait, wait_ptr) = if wait_raw.is_empty() {
+                (0, null())
+            } else {
+                (wait_raw.len() as u32, wait_raw.as_ptr())
+            };
+
+            unsafe {
+                cl3::command_queue::enqueue_copy_buffer_rect(
+                    q_ptr.0,
+                    src_ptr.0,
+                    dst_ptr.0,
+                    src_origin.as_ptr(),
+                    dst_origin.as_ptr(),
+                    region.as_ptr(),
+                    src_pitch.0,
+                    src_pitch.1,
+                    dst_pitch.0,
+                    dst_pitch.1,
+                    num_wait,
+                    wait_ptr,
+                )
+            }
+        };
+
+        Ok(ClEvent::from_ptr(
+            res.map_err(|code| ClError::Api(ApiError::get_error(code)))?,
+        ))
+    }
+
+    #[cfg(feature = "CL_VERSION_1_1")]
+    pub fn copy_buffer_rect(
+        &self,
+        src: &ClBuffer,
+        dst: &ClBuffer,
+        src_origin: [usize; 3],
+        dst_origin: [usize; 3],
+        region: [usize; 3],
+        src_row_pitch: usize,
+        src_slice_pitch: usize,
+        dst_row_pitch: usize,
+        dst_slice_pitch: usize,
+        event_wait_list: Option<Vec<ClEvent>>,
+    ) -> impl Future<Output = Result<ClEvent, ClError>> + Send + '_ {
+        let q_ptr = SendPtr(self.as_ptr());
+        let src_ptr = SendPtr(src.as_ptr());
+        let dst_ptr = SendPtr(dst.as_ptr());
+
+        let wait_ptrs: Option<Vec<SendPtr>> =
+            event_wait_list.map(|v| v.iter().map(|e| SendPtr(e.as_ptr())).collect());
+
+        async move {
+            let event = Self::copy_buffer_rect_inner(
+                q_ptr,
+                src_ptr,
+                dst_ptr,
+                src_origin,
+                dst_origin,
+                region,
+                (src_row_pitch, src_slice_pitch),
+                (dst_row_pitch, dst_slice_pitch),
+                wait_ptrs,
+            )?;
+            event.event_future().await;
+            Ok(event)
+        }
+    }
+
+    /// Fills `size` bytes of a buffer, starting at byte `offset`, with a repeated pattern.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    fn fill_buffer_inner(
+        q_ptr: SendPtr,
+        b_ptr: SendPtr,
+        pattern: Vec<u8>,
+        offset: usize,
+        size: usize,
+        wait_ptrs: Option<Vec<SendPtr>>,
+    ) -> Result<ClEvent, ClError> {
+        let res = {
+            let wait_raw: Vec<*mut c_void> = wait_ptrs
+                .map(|v| v.iter().map(|p| p.0).collect())
+                .unwrap_or_default();
+
+            let (num_wait, wait_ptr) = if wait_raw.is_empty() {
+                (0, null())
+            } else {
+                (wait_raw.len() as u32, wait_raw.as_ptr())
+            };
+
+            unsafe {
+                cl3::command_queue::enqueue_fill_buffer(
+                    q_ptr.0,
+                    b_ptr.0,
+                    pattern.as_ptr() as *const c_void,
+                    pattern.len(),
+                    offset,
+                    size,
+                    num_wait,
+                    wait_ptr,
+                )
+            }
+        };
+
+        Ok(ClEvent::from_ptr(
+            res.map_err(|code| ClError::Api(ApiError::get_error(code)))?,
+        ))
+    }
+
+    /// `offset` and `size` are in bytes and must be multiples of `size_of::<T>()`.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub fn fill_buffer<T: Copy>(
+        &self,
+        buffer: &ClBuffer,
+        pattern: T,
+        offset: usize,
+        size: usize,
+        event_wait_list: Option<Vec<ClEvent>>,
+    ) -> impl Future<Output = Result<ClEvent, ClError>> + Send + '_ {
+        let q_ptr = SendPtr(self.as_ptr());
+        let b_ptr = SendPtr(buffer.as_ptr());
+        let pattern = unsafe {
+            std::slice::from_raw_parts(&pattern as *const T as *const u8, std::mem::size_of::<T>()).to_vec()
+        };
+
+        let wait_ptrs: Option<Vec<SendPtr>> =
+            event_wait_list.map(|v| v.iter().map(|e| SendPtr(e.as_ptr())).collect());
+
+        async move {
+            let event = Self::fill_buffer_inner(q_ptr, b_ptr, pattern, offset, size, wait_ptrs)?;
+            event.event_future().await;
+            Ok(event)
+        }
+    }
+
+    /// Copies a region between two images with the same format.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    fn copy_image_inner(
+        q_ptr: SendPtr,
+        src_ptr: SendPtr,
+        dst_ptr: SendPtr,
+        src_origin: [usize; 4],
+        dst_origin: [usize; 4],
+        region: [usize; 3],
+        wait_ptrs: Option<Vec<SendPtr>>,
+    ) -> Result<ClEvent, ClError> {
+        let res = {
+            let wait_raw: Vec<*mut c_void> = wait_ptrs
+                .map(|v| v.iter().map(|p| p.0).collect())
+                .unwrap_or_default();
+
+            let (num_wait, wait_ptr) = if wait_raw.is_empty() {
+                (0, null())
+            } else {
+                (wait_raw.len() as u32, wait_raw.as_ptr())
+            };
+
+            unsafe {
+                cl3::command_queue::enqueue_copy_image(
+                    q_ptr.0,
+                    src_ptr.0,
+                    dst_ptr.0,
+                    src_origin.as_ptr(),
+                    dst_origin.as_ptr(),
+                    region.as_ptr(),
+                    num_wait,
+                    wait_ptr,
+                )
+            }
+        };
+
+        Ok(ClEvent::from_ptr(
+            res.map_err(|code| ClError::Api(ApiError::get_error(code)))?,
+        ))
+    }
+
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub fn copy_image(
+        &self,
+        src: &ClImage,
+        dst: &ClImage,
+        src_origin: [usize; 3],
+        dst_origin: [usize; 3],
+        region: [usize; 3],
+        event_wait_list: Option<Vec<ClEvent>>,
+    ) -> impl Future<Output = Result<ClEvent, ClError>> + Send + '_ {
+        let q_ptr = SendPtr(self.as_ptr());
+        let src_ptr = SendPtr(src.as_ptr());
+        let dst_ptr = SendPtr(dst.as_ptr());
+
+        let wait_ptrs: Option<Vec<SendPtr>> =
+            event_wait_list.map(|v| v.iter().map(|e| SendPtr(e.as_ptr())).collect());
+
+        async move {
+            let event = Self::copy_image_inner(q_ptr, src_ptr, dst_ptr, pad_origin(src_origin), pad_origin(dst_origin), region, wait_ptrs)?;
+            event.event_future().await;
+            Ok(event)
+        }
+    }
+
+    /// Copies a region from mip level `src_level` of `src` to mip level `dst_level` of `dst`
+    /// (`cl_khr_mipmap_image`). Either image may have a single level.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub fn copy_image_levels(
+        &self,
+        src: &ClImage,
+        src_level: u32,
+        dst: &ClImage,
+        dst_level: u32,
+        src_origin: [usize; 3],
+        dst_origin: [usize; 3],
+        region: [usize; 3],
+        event_wait_list: Option<Vec<ClEvent>>,
+    ) -> impl Future<Output = Result<ClEvent, ClError>> + Send + '_ {
+        let q_ptr = SendPtr(self.as_ptr());
+        let src_ptr = SendPtr(src.as_ptr());
+        let dst_ptr = SendPtr(dst.as_ptr());
+        let origins = src.mip_origin(src_origin, src_level).and_then(|src| Ok((src, dst.mip_origin(dst_origin, dst_level)?)));
+
+        let wait_ptrs: Option<Vec<SendPtr>> =
+            event_wait_list.map(|v| v.iter().map(|e| SendPtr(e.as_ptr())).collect());
+
+        async move {
+            let (src_origin, dst_origin) = origins?;
+            let event = Self::copy_image_inner(q_ptr, src_ptr, dst_ptr, src_origin, dst_origin, region, wait_ptrs)?;
+            event.event_future().await;
+            Ok(event)
+        }
+    }
+
+    #[cfg(feature = "cl_img_generate_mipmap")]
+    fn generate_mipmap_img_inner(
+        q_ptr: SendPtr,
+        i_ptr: SendPtr,
+        array_region: [usize; 2],
+        mip_region: [usize; 2],
+        wait_ptrs: Option<Vec<SendPtr>>,
+    ) -> Result<ClEvent, ClError> {
+        let res = {
+            let wait_raw: Vec<*mut c_void> = wait_ptrs
+                .map(|v| v.iter().map(|p| p.0).collect())
+                .unwrap_or_default();
+
+            let (num_wait, wait_ptr) = if wait_raw.is_empty() {
+                (0, null())
+            } else {
+                (wait_raw.len() as u32, wait_raw.as_ptr())
+            };
+
+            unsafe {
+                cl3::ext::enqueue_generate_mipmap_img(
+                    q_ptr.0,
+                    i_ptr.0,
+                    i_ptr.0,
+                    cl3::ext::CL_MIPMAP_FILTER_BOX_IMG,
+                    array_region.as_ptr(),
+                    mip_region.as_ptr(),
+                    num_wait,
+                    wait_ptr,
+                )
+            }
+        };
+
+        Ok(ClEvent::from_ptr(
+            res.map_err(|code| ClError::Api(ApiError::get_error(code)))?,
+        ))
+    }
+
+    /// Fills levels 1.. of a mipmapped image from level 0 with a box filter
+    /// (`cl_img_generate_mipmap`; the device must report the extension).
+    #[cfg(feature = "cl_img_generate_mipmap")]
+    pub fn generate_mipmap_img(
+        &self,
+        image: &ClImage,
+        event_wait_list: Option<Vec<ClEvent>>,
+    ) -> impl Future<Output = Result<ClEvent, ClError>> + Send + '_ {
+        let q_ptr = SendPtr(self.as_ptr());
+        let i_ptr = SendPtr(image.as_ptr());
+        let regions = image.get_num_mip_levels().and_then(|levels| Ok(([0, image.get_array_size()?.max(1)], [0, levels as usize])));
+
+        let wait_ptrs: Option<Vec<SendPtr>> =
+            event_wait_list.map(|v| v.iter().map(|e| SendPtr(e.as_ptr())).collect());
+
+        async move {
+            let (array_region, mip_region) = regions?;
+            let event = Self::generate_mipmap_img_inner(q_ptr, i_ptr, array_region, mip_region, wait_ptrs)?;
+            event.event_future().await;
+            Ok(event)
+        }
+    }
+
+    /// Fills a region of an image with one color.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    fn fill_image_inner(
+        q_ptr: SendPtr,
+        i_ptr: SendPtr,
+        fill_color: Vec<u8>,
+        origin: [usize; 3],
+        region: [usize; 3],
+        wait_ptrs: Option<Vec<SendPtr>>,
+    ) -> Result<ClEvent, ClError> {
+        let res = {
+            let wait_raw: Vec<*mut c_void> = wait_ptrs
+                .map(|v| v.iter().map(|p| p.0).collect())
+                .unwrap_or_default();
+
+            let (num_wait, wait_ptr) = if wait_raw.is_empty() {
+                (0, null())
+            } else {
+                (wait_raw.len() as u32, wait_raw.as_ptr())
+            };
+
+            unsafe {
+                cl3::command_queue::enqueue_fill_image(
+                    q_ptr.0,
+                    i_ptr.0,
+                    fill_color.as_ptr() as *const c_void,
+                    origin.as_ptr(),
+                    region.as_ptr(),
+                    num_wait,
+                    wait_ptr,
+                )
+            }
+        };
+
+        Ok(ClEvent::from_ptr(
+            res.map_err(|code| ClError::Api(ApiError::get_error(code)))?,
+        ))
+    }
+
+    /// `fill_color` is RGBA as `f32` for normalized and float formats,
+    /// `i32` for signed integer formats and `u32` for unsigned integer formats.
+    /// Other combinations fail with `FillColorMismatch` before anything is enqueued.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub fn fill_image<T: FillColor>(
+        &self,
+        image: &ClImage,
+        fill_color: [T; 4],
+        origin: [usize; 3],
+        region: [usize; 3],
+        event_wait_list: Option<Vec<ClEvent>>,
+    ) -> impl Future<Output = Result<ClEvent, ClError>> + Send + '_ {
+        let q_ptr = SendPtr(self.as_ptr());
+        let i_ptr = SendPtr(image.as_ptr());
+        let format = image.get_image_format();
+        let fill_color = unsafe {
+            std::slice::from_raw_parts(fill_color.as_ptr() as *const u8, std::mem::size_of::<[T; 4]>()).to_vec()
+        };
+
+        let wait_ptrs: Option<Vec<SendPtr>> =
+            event_wait_list.map(|v| v.iter().map(|e| SendPtr(e.as_ptr())).collect());
+
+        async move {
+            let format = format?;
+            if !T::fills(format.image_channel_data_type) {
+                return Err(ClError::Wrapper(WrapperError::FillColorMismatch { format, color_type: T::NAME }));
+            }
+            let event = Self::fill_image_inner(q_ptr, i_ptr, fill_color, origin, region, wait_ptrs)?;
+            event.event_future().await;
+            Ok(event)
+        }
+    }
+
+    /// Copies a region of an image into a buffer, tightly packed, starting at byte `dst_offset`.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    fn copy_image_to_buffer_inner(
+        q_ptr: SendPtr,
+        i_ptr: SendPtr,
+        b_ptr: SendPtr,
+        src_origin: [usize; 3],
+        region: [usize; 3],
+        dst_offset: usize,
+        wait_ptrs: Option<Vec<SendPtr>>,
+    ) -> Result<ClEvent, ClError> {
+        let res = {
+            let wait_raw: Vec<*mut c_void> = wait_ptrs
+                .map(|v| v.iter().map(|p| p.0).collect())
+                .unwrap_or_default();
+
+            let (num_wait, wait_ptr) = if wait_raw.is_empty() {
+                (0, null())
+            } else {
+                (wait_raw.len() as u32, wait_raw.as_ptr())
+            };
+
+            unsafe {
+                cl3::command_queue::enqueue_copy_image_to_buffer(
+                    q_ptr.0,
+                    i_ptr.0,
+                    b_ptr.0,
+                    src_origin.as_ptr(),
+                    region.as_ptr(),
+                    dst_offset,
+                    num_wait,
+                    wait_ptr,
+                )
+            }
+        };
+
+        Ok(ClEvent::from_ptr(
+            res.map_err(|code| ClError::Api(ApiError::get_error(code)))?,
+        ))
+    }
+
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub fn copy_image_to_buffer(
+        &self,
+        src: &ClImage,
+        dst: &ClBuffer,
+        src_origin: [usize; 3],
+        region: [usize; 3],
+        dst_offset: usize,
+        event_wait_list: Option<Vec<ClEvent>>,
+    ) -> impl Future<Output = Result<ClEvent, ClError>> + Send + '_ {
+        let q_ptr = SendPtr(self.as_ptr());
+        let i_ptr = SendPtr(src.as_ptr());
+        let b_ptr = SendPtr(dst.as_ptr());
+
+        let wait_ptrs: Option<Vec<SendPtr>> =
+            event_wait_list.map(|v| v.iter().map(|e| SendPtr(e.as_ptr())).collect());
+
+        async move {
+            let event = Self::copy_image_to_buffer_inner(q_ptr, i_ptr, b_ptr, src_origin, region, dst_offset, wait_ptrs)?;
+            event.event_future().await;
+            Ok(event)
+        }
+    }
+
+    /// Copies tightly packed pixels from a buffer, starting at byte `src_offset`, into a region of an image.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    fn copy_buffer_to_image_inner(
+        q_ptr: SendPtr,
+        b_ptr: SendPtr,
+        i_ptr: SendPtr,
+        src_offset: usize,
+        dst_origin: [usize; 3],
+        region: [usize; 3],
+        wait_ptrs: Option<Vec<SendPtr>>,
+    ) -> Result<ClEvent, ClError> {
+        let res = {
+            let wait_raw: Vec<*mut c_void> = wait_ptrs
+                .map(|v| v.iter().map(|p| p.0).collect())
+                .unwrap_or_default();
+
+            let (num_wait, wait_ptr) = if wait_raw.is_empty() {
+                (0, null())
+            } else {
+                (wait_raw.len() as u32, wait_raw.as_ptr())
+            };
+
+            unsafe {
+                cl3::command_queue::enqueue_copy_buffer_to_image(
+                    q_ptr.0,
+                    b_ptr.0,
+                    i_ptr.0,
+                    src_offset,
+                    dst_origin.as_ptr(),
+                    region.as_ptr(),
+                    num_wait,
+                    wait_ptr,
+                )
+            }
+        };
+
+        Ok(ClEvent::from_ptr(
+            res.map_err(|code| ClError::Api(ApiError::get_error(code)))?,
+        ))
+    }
+
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub fn copy_buffer_to_image(
+        &self,
+        src: &ClBuffer,
+        dst: &ClImage,
+        src_offset: usize,
+        dst_origin: [usize; 3],
+        region: [usize; 3],
+        event_wait_list: Option<Vec<ClEvent>>,
+    ) -> impl Future<Output = Result<ClEvent, ClError>> + Send + '_ {
+        let q_ptr = SendPtr(self.as_ptr());
+        let b_ptr = SendPtr(src.as_ptr());
+        let i_ptr = SendPtr(dst.as_ptr());
+
+        let wait_ptrs: Option<Vec<SendPtr>> =
+            event_wait_list.map(|v| v.iter().map(|e| SendPtr(e.as_ptr())).collect());
+
+        async move {
+            let event = Self::copy_buffer_to_image_inner(q_ptr, b_ptr, i_ptr, src_offset, dst_origin, region, wait_ptrs)?;
+            event.event_future().await;
+            Ok(event)
+        }
+    }
+
+    #[cfg(feature = "CL_VERSION_1_1")]
+    fn enqueue_map_buffer_inner(
+        q_ptr: SendPtr,
+        b_ptr: SendPtr,
+        map_flags: u64,
+        offset: usize,
+        size: usize,
+        wait_ptrs: Option<Vec<SendPtr>>,
+    ) -> Result<(ClEvent, SendPtr), ClError> {
+        let mut mapped: *mut c_void = std::ptr::null_mut();
+        let res = {
+            let wait_raw: Vec<*mut c_void> = wait_ptrs
+                .map(|v| v.iter().map(|p| p.0).collect())
+                .unwrap_or_default();
+
+            let (num_wait, wait_ptr) = if wait_raw.is_empty() {
+                (0, null())
+            } else {
+                (wait_raw.len() as u32, wait_raw.as_ptr())
+            };
+
+            unsafe {
+                cl3::command_queue::enqueue_map_buffer(
+                    q_ptr.0,
+                    b_ptr.0,
+                    0, // CL_FALSE (Non-blocking)
+                    map_flags,
+                    offset,
+                    size,
+                    &mut mapped,
+                    num_wait,
+                    wait_ptr,
+                )
+            }
+        };
+
+        let event = ClEvent::from_ptr(res.map_err(|code| ClError::Api(ApiError::get_error(code)))?);
+        Ok((event, SendPtr(mapped)))
+    }
+
+    /// Maps `size` bytes of a buffer, starting at byte `offset`, into host memory.
+    ///
+    /// `map_flags` is a combination of `CL_MAP_*` values. Resolves to the completion
+    /// event and the host pointer, which is valid until the region is unmapped.
+    #[cfg(feature = "CL_VERSION_1_1")]
+    pub fn enqueue_map_buffer(
+        &self,
+        buffer: &ClBuffer,
+        map_flags: u64,
+        offset: usize,
+        size: usize,
+        event_wait_list: Option<Vec<ClEvent>>,
+    ) -> impl Future<Output = Result<(ClEvent, *mut c_void), ClError>> + Send + '_ {
+        let q_ptr = SendPtr(self.as_ptr());
+        let b_ptr = SendPtr(buffer.as_ptr());
+
+        let wait_ptrs: Option<Vec<SendPtr>> =
+            event_wait_list.map(|v| v.iter().map(|e| SendPtr(e.as_ptr())).collect());
+
+        async move {
+            let (event, mapped) = Self::enqueue_map_buffer_inner(q_ptr, b_ptr, map_flags, offset, size, wait_ptrs)?;
+            event.event_future().await;
+            Ok((event, mapped.0))
+        }
+    }
+
+    #[cfg(feature = "CL_VERSION_1_1")]
+    fn enqueue_unmap_mem_object_inner(
+        q_ptr: SendPtr,
+        m_ptr: SendPtr,
+        mapped_ptr: SendPtr,
+        wait_ptrs: Option<Vec<SendPtr>>,
+    ) -> Result<ClEvent, ClError> {
+        let res = {
+            let wait_raw: Vec<*mut c_void> = wait_ptrs
+                .map(|v| v.iter().map(|p| p.0).collect())
+                .unwrap_or_default();
+
+            let (num_wait, wait_ptr) = if wait_raw.is_empty() {
+                (0, null())
+            } else {
+                (wait_raw.len() as u32, wait_raw.as_ptr())
+            };
+
+            unsafe {
+                cl3::command_queue::enqueue_unmap_mem_object(q_ptr.0, m_ptr.0, mapped_ptr.0, num_wait, wait_ptr)
+            }
+        };
+
+        Ok(ClEvent::from_ptr(
+            res.map_err(|code| ClError::Api(ApiError::get_error(code)))?,
+        ))
+    }
+
+    /// Unmaps a region previously returned by `enqueue_map_buffer`.
+    #[cfg(feature = "CL_VERSION_1_1")]
+    pub fn enqueue_unmap_mem_object(
+        &self,
+        mem_object: *mut c_void,
+        mapped_ptr: *mut c_void,
+        event_wait_list: Option<Vec<ClEvent>>,
+    ) -> impl Future<Output = Result<ClEvent, ClError>> + Send + '_ {
+        let q_ptr = SendPtr(self.as_ptr());
+        let m_ptr = SendPtr(mem_object);
+        let mapped_ptr = SendPtr(mapped_ptr);
+
+        let wait_ptrs: Option<Vec<SendPtr>> =
+            event_wait_list.map(|v| v.iter().map(|e| SendPtr(e.as_ptr())).collect());
+
+        async move {
+            let event = Self::enqueue_unmap_mem_object_inner(q_ptr, m_ptr, mapped_ptr, wait_ptrs)?;
+            event.event_future().await;
+            Ok(event)
+        }
+    }
+
+    #[cfg(feature = "CL_VERSION_1_2")]
+    fn enqueue_migrate_mem_objects_inner(
+        q_ptr: SendPtr,
+        mem_ptrs: Vec<SendPtr>,
+        flags: u64,
+        wait_ptrs: Option<Vec<SendPtr>>,
+    ) -> Result<ClEvent, ClError> {
+        let res = {
+            let mem_raw: Vec<*mut c_void> = mem_ptrs.iter().map(|p| p.0).collect();
+            let wait_raw: Vec<*mut c_void> = wait_ptrs
+                .map(|v| v.iter().map(|p| p.0).collect())
+                .unwrap_or_default();
+
+            let (num_wait, wait_ptr) = if wait_raw.is_empty() {
+                (0, null())
+            } else {
+                (wait_raw.len() as u32, wait_raw.as_ptr())
+            };
+
+            unsafe {
+                cl3::command_queue::enqueue_migrate_mem_object(
+                    q_ptr.0,
+                    mem_raw.len() as u32,
+                    mem_raw.as_ptr(),
+                    flags,
+                    num_wait,
+                    wait_ptr,
+                )
+            }
+        };
+
+        Ok(ClEvent::from_ptr(
+            res.map_err(|code| ClError::Api(ApiError::get_error(code)))?,
+        ))
+    }
+
+    /// Moves memory objects (buffers, images, pipes) to the device of this queue.
+    ///
+    /// `flags` is a combination of `CL_MIGRATE_MEM_OBJECT_*` values (`0` migrates the contents).
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub fn enqueue_migrate_mem_objects(
+        &self,
+        mem_objects: &[*mut c_void],
+        flags: u64,
+        event_wait_list: Option<Vec<ClEvent>>,
+    ) -> impl Future<Output = Result<ClEvent, ClError>> + Send + '_ {
+        let q_ptr = SendPtr(self.as_ptr());
+        let mem_ptrs: Vec<SendPtr> = mem_objects.iter().map(|p| SendPtr(*p)).collect();
+
+        let wait_ptrs: Option<Vec<SendPtr>> =
+            event_wait_list.map(|v| v.iter().map(|e| SendPtr(e.as_ptr())).collect());
+
+        async move {
+            let event = Self::enqueue_migrate_mem_objects_inner(q_ptr, mem_ptrs, flags, wait_ptrs)?;
+            event.event_future().await;
+            Ok(event)
+        }
+    }
+
+    #[cfg(feature = "CL_VERSION_2_0")]
+    fn svm_memcpy_inner(
+        q_ptr: SendPtr,
+        dst_ptr: SendPtr,
+        src_ptr: SendPtr,
+        size: usize,
+        wait_ptrs: Option<Vec<SendPtr>>,
+    ) -> Result<ClEvent, ClError> {
+        let res = {
+            let wait_raw: Vec<*mut c_void> = wait_ptrs
+                .map(|v| v.iter().map(|p| p.0).collect())
+                .unwrap_or_default();
+
+            let (num_wait, wait_ptr) = if wait_raw.is_empty() {
+                (0, null())
+            } else {
+                (wait_raw.len() as u32, wait_raw.as_ptr())
+            };
+
+            unsafe {
+                cl3::command_queue::enqueue_svm_mem_cpy(
+                    q_ptr.0,
+                    0, // CL_FALSE
+                    dst_ptr.0,
+                    src_ptr.0 as *const c_void,
+                    size,
+                    num_wait,
+                    wait_ptr,
+                )
+            }
+        };
+
+        Ok(ClEvent::from_ptr(
+            res.map_err(|code| ClError::Api(ApiError::get_error(code)))?,
+        ))
+    }
+
+    /// Copies `size` bytes from `src` to `dst` without blocking the calling thread.
+    ///
+    /// Either pointer can be SVM memory or ordinary host memory, so this also stages host
+    /// data into coarse-grain SVM buffers without mapping them. Both must stay valid until
+    /// the returned future completes.
+    #[cfg(feature = "CL_VERSION_2_0")]
+    pub fn svm_memcpy(
+        &self,
+        dst: *mut c_void,
+        src: *const c_void,
+        size: usize,
+        event_wait_list: Option<Vec<ClEvent>>,
+    ) -> impl Future<Output = Result<ClEvent, ClError>> + Send + '_ {
+        let q_ptr = SendPtr(self.as_ptr());
+        let dst_ptr = SendPtr(dst);
+        let src_ptr = SendPtr(src as *mut c_void);
+
+        let wait_ptrs: Option<Vec<SendPtr>> =
+            event_wait_list.map(|v| v.iter().map(|e| SendPtr(e.as_ptr())).collect());
+
+        async move {
+            let event = Self::svm_memcpy_inner(q_ptr, dst_ptr, src_ptr, size, wait_ptrs)?;
+            event.event_future().await;
+            Ok(event)
+        }
+    }
+
+    #[cfg(feature = "CL_VERSION_2_0")]
+    fn svm_mem_fill_inner(
+        q_ptr: SendPtr,
+        svm_ptr: SendPtr,
+        pattern: Vec<u8>,
+        size: usize,
+        wait_ptrs: Option<Vec<SendPtr>>,
+    ) -> Result<ClEvent, ClError> {
+        let res = {
+            let wait_raw: Vec<*mut c_void> = wait_ptrs
+                .map(|v| v.iter().map(|p| p.0).collect())
+                .unwrap_or_default();
+
+            let (num_wait, wait_ptr) = if wait_raw.is_empty() {
+                (0, null())
+            } else {
+                (wait_raw.len() as u32, wait_raw.as_ptr())
+            };
+
+            unsafe {
+                cl3::command_queue::enqueue_svm_mem_fill(
+                    q_ptr.0,
+                    svm_ptr.0,
+                    pattern.as_ptr() as *const c_void,
+                    pattern.len(),
+                    size,
+                    num_wait,
+                    wait_ptr,
+                )
+            }
+        };
+
+        Ok(ClEvent::from_ptr(
+            res.map_err(|code| ClError::Api(ApiError::get_error(code)))?,
+        ))
+    }
+
+    /// Fills `size` bytes of SVM memory at `svm_ptr` with a repeated pattern.
+    ///
+    /// `size_of::<T>()` must be a power of two up to 128, and `svm_ptr` and `size` multiples of it.
+    #[cfg(feature = "CL_VERSION_2_0")]
+    pub fn svm_mem_fill<T: Copy>(
+        &self,
+        svm_ptr: *mut c_void,
+        pattern: T,
+        size: usize,
+        event_wait_list: Option<Vec<ClEvent>>,
+    ) -> impl Future<Output = Result<ClEvent, ClError>> + Send + '_ {
+        let q_ptr = SendPtr(self.as_ptr());
+        let svm_ptr = SendPtr(svm_ptr);
+        let pattern = unsafe {
+            std::slice::from_raw_parts(&pattern as *const T as *const u8, std::mem::size_of::<T>()).to_vec()
+        };
+
+        let wait_ptrs: Option<Vec<SendPtr>> =
+            event_wait_list.map(|v| v.iter().map(|e| SendPtr(e.as_ptr())).collect());
+
+        async move {
+            let event = Self::svm_mem_fill_inner(q_ptr, svm_ptr, pattern, size, wait_ptrs)?;
+            event.event_future().await;
+            Ok(event)
+        }
+    }
+
+    #[cfg(feature = "CL_VERSION_2_1")]
+    fn svm_migrate_mem_inner(
+        q_ptr: SendPtr,
+        svm_ptrs: Vec<SendPtr>,
+        sizes: Vec<usize>,
+        flags: u64,
+        wait_ptrs: Option<Vec<SendPtr>>,
+    ) -> Result<ClEvent, ClError> {
+        let res = {
+            let svm_raw: Vec<*const c_void> = svm_ptrs.iter().map(|p| p.0 as *const c_void).collect();
+            let wait_raw: Vec<*mut c_void> = wait_ptrs
+                .map(|v| v.iter().map(|p| p.0).collect())
+                .unwrap_or_default();
+
+            let (num_wait, wait_ptr) = if wait_raw.is_empty() {
+                (0, null())
+            } else {
+                (wait_raw.len() as u32, wait_raw.as_ptr())
+            };
+
+            unsafe {
+                cl3::command_queue::enqueue_svm_migrate_mem(
+                    q_ptr.0,
+                    svm_raw.len() as u32,
+                    svm_raw.as_ptr(),
+                    sizes.as_ptr(),
+                    flags,
+                    num_wait,
+                    wait_ptr,
+                )
+            }
+        };
+
+        Ok(ClEvent::from_ptr(
+            res.map_err(|code| ClError::Api(ApiError::get_error(code)))?,
+        ))
+    }
+
+    /// Moves SVM ranges to the device of this queue. `sizes[i]` is the byte size of the
+    /// range starting at `svm_pointers[i]` (`0` for the whole allocation).
+    ///
+    /// `flags` is a combination of `CL_MIGRATE_MEM_OBJECT_*` values (`0` migrates the contents).
+    #[cfg(feature = "CL_VERSION_2_1")]
+    pub fn svm_migrate_mem(
+        &self,
+        svm_pointers: &[*const c_void],
+        sizes: &[usize],
+        flags: u64,
+        event_wait_list: Option<Vec<ClEvent>>,
+    ) -> impl Future<Output = Result<ClEvent, ClError>> + Send + '_ {
+        let q_ptr = SendPtr(self.as_ptr());
+        let svm_ptrs: Vec<SendPtr> = svm_pointers.iter().map(|p| SendPtr(*p as *mut c_void)).collect();
+        let sizes = sizes.to_vec();
+
+        let wait_ptrs: Option<Vec<SendPtr>> =
+            event_wait_list.map(|v| v.iter().map(|e| SendPtr(e.as_ptr())).collect());
+
+        async move {
+            let event = Self::svm_migrate_mem_inner(q_ptr, svm_ptrs, sizes, flags, wait_ptrs)?;
+            event.event_future().await;
+            Ok(event)
+        }
+    }
+
+    #[cfg(feature = "CL_VERSION_2_0")]
+    fn svm_free_inner(
+        q_ptr: SendPtr,
+        svm_ptrs: Vec<SendPtr>,
+        wait_ptrs: Option<Vec<SendPtr>>,
+    ) -> Result<ClEvent, ClError> {
+        let res = {
+            let svm_raw: Vec<*const c_void> = svm_ptrs.iter().map(|p| p.0 as *const c_void).collect();
+            let wait_raw: Vec<*mut c_void> = wait_ptrs
+                .map(|v| v.iter().map(|p| p.0).collect())
+                .unwrap_or_default();
+
+            let (num_wait, wait_ptr) = if wait_raw.is_empty() {
+                (0, null())
+            } else {
+                (wait_raw.len() as u32, wait_raw.as_ptr())
+            };
+
+            unsafe {
+                cl3::command_queue::enqueue_svm_free(
+                    q_ptr.0,
+                    svm_raw.len() as u32,
+                    svm_raw.as_ptr(),
+                    None,
+                    std::ptr::null_mut(),
+                    num_wait,
+                    wait_ptr,
+                )
+            }
+        };
+
+        Ok(ClEvent::from_ptr(
+            res.map_err(|code| ClError::Api(ApiError::get_error(code)))?,
+        ))
+    }
+
+    /// Frees SVM allocations once the commands before it in this queue (and in
+    /// `event_wait_list`) have completed, so they can still be in use when it is called.
+    ///
+    /// The pointers must come from `ClSvmBuffer::into_raw` and not be used afterwards.
+    #[cfg(feature = "CL_VERSION_2_0")]
+    pub fn svm_free(
+        &self,
+        svm_pointers: &[*mut c_void],
+        event_wait_list: Option<Vec<ClEvent>>,
+    ) -> impl Future<Output = Result<ClEvent, ClError>> + Send + '_ {
+        let q_ptr = SendPtr(self.as_ptr());
+        let svm_ptrs: Vec<SendPtr> = svm_pointers.iter().map(|p| SendPtr(*p)).collect();
+
+        let wait_ptrs: Option<Vec<SendPtr>> =
+            event_wait_list.map(|v| v.iter().map(|e| SendPtr(e.as_ptr())).collect());
+
+        async move {
+            let event = Self::svm_free_inner(q_ptr, svm_ptrs, wait_ptrs)?;
+            event.event_future().await;
+            Ok(event)
+        }
+    }
+
     #[cfg(feature = "CL_VERSION_1_1")]
     cl_command_queue_generate_getters!(
         (get_context, ClContext, cl3::command_queue::CL_QUEUE_CONTEXT),
@@ -0,0 +1,112 @@
+use crate::{
+    cl_types::{
+        cl_buffer::ClBuffer,
+        cl_context::ClContext,
+        cl_image::{ClImage, image_desc::ClImageDesc, image_formats::ClImageFormats, image_type::ClImageType},
+        memory_flags::MemoryFlags,
+    },
+    error::{ClError, wrapper_error::WrapperError},
+};
+
+fn element_size(format: &ClImageFormats) -> Result<usize, ClError> {
+    format.bytes_per_pixel().ok_or(ClError::Wrapper(WrapperError::UnsupportedPixelLayout {
+        channel_order: format.image_channel_order,
+        channel_type: format.image_channel_data_type,
+    }))
+}
+
+/// `required` is `None` when the size overflowed, which no buffer can hold.
+fn check_fits(required: Option<usize>, buffer: &ClBuffer) -> Result<(), ClError> {
+    let buffer_size = buffer.get_size()?;
+    match required {
+        Some(required) if required <= buffer_size => Ok(()),
+        _ => Err(ClError::Wrapper(WrapperError::ImageExceedsBuffer { required: required.unwrap_or(usize::MAX), buffer_size })),
+    }
+}
+
+impl ClImage {
+    /// Creates an `Image1DBuffer` of `width` pixels that uses the memory of `buffer`, without copying.
+    ///
+    /// Fails with `ImageExceedsBuffer` if the pixels don't fit in the buffer. The image keeps
+    /// the buffer alive, and writes through either one are visible through the other.
+    pub fn from_buffer_1d(
+        context: &ClContext,
+        flags: &[MemoryFlags],
+        format: &ClImageFormats,
+        buffer: &ClBuffer,
+        width: usize,
+    ) -> Result<Self, ClError> {
+        check_fits(width.checked_mul(element_size(format)?), buffer)?;
+        let desc = ClImageDesc {
+            image_type: ClImageType::Image1DBuffer,
+            image_width: Some(width),
+            buffer: Some(buffer.as_ptr()),
+            ..Default::default()
+        };
+        Self::view(context, flags, format, &desc, buffer)
+    }
+
+    /// Creates a 2D image that uses the memory of `buffer`, without copying.
+    ///
+    /// Rows are `row_pitch` bytes apart; by default, the row size rounded up to the pitch
+    /// alignment. The pitch and the buffer's start must be aligned for every device of the
+    /// context (`CL_DEVICE_IMAGE_PITCH_ALIGNMENT` and `CL_DEVICE_IMAGE_BASE_ADDRESS_ALIGNMENT`),
+    /// and every row must fit in the buffer. The image keeps the buffer alive.
+    #[cfg(feature = "CL_VERSION_2_0")]
+    pub fn from_buffer_2d(
+        context: &ClContext,
+        flags: &[MemoryFlags],
+        format: &ClImageFormats,
+        buffer: &ClBuffer,
+        width: usize,
+        height: usize,
+        row_pitch: Option<usize>,
+    ) -> Result<Self, ClError> {
+        let element_size = element_size(format)?;
+        let devices = context.get_devices()?;
+        // Alignments are powers of two in pixels, so the largest one suits every device
+        let mut pitch_alignment = 1;
+        let mut base_alignment = 1;
+        for device in &devices {
+            pitch_alignment = pitch_alignment.max(device.get_image_pitch_alignment()? as usize);
+            base_alignment = base_alignment.max(device.get_image_base_address_alignment()? as usize);
+        }
+        let pitch_alignment = pitch_alignment.checked_mul(element_size);
+        let base_alignment = base_alignment.checked_mul(element_size);
+        let min_pitch = width.checked_mul(element_size);
+        let row_pitch = row_pitch.or_else(|| min_pitch?.checked_next_multiple_of(pitch_alignment?));
+        // Overflowing here means not even one row fits in a `usize`
+        let (Some(pitch_alignment), Some(base_alignment), Some(min_pitch), Some(row_pitch)) =
+            (pitch_alignment, base_alignment, min_pitch, row_pitch)
+        else {
+            return Err(ClError::Wrapper(WrapperError::ImageExceedsBuffer { required: usize::MAX, buffer_size: buffer.get_size()? }));
+        };
+        if row_pitch < min_pitch || !row_pitch.is_multiple_of(pitch_alignment) {
+            return Err(ClError::Wrapper(WrapperError::InvalidImagePitch { row_pitch, min_pitch, alignment: pitch_alignment }));
+        }
+        check_fits(row_pitch.checked_mul(height.saturating_sub(1)).and_then(|rows| rows.checked_add(min_pitch)), buffer)?;
+
+        // Sub-buffers start at an offset, and `UseHostPtr` buffers at their host pointer
+        for address in [buffer.get_offset()?, buffer.get_host_ptr()? as usize] {
+            if !address.is_multiple_of(base_alignment) {
+                return Err(ClError::Wrapper(WrapperError::MisalignedImageBaseAddress { address, alignment: base_alignment }));
+            }
+        }
+
+        let desc = ClImageDesc {
+            image_type: ClImageType::Image2D,
+            image_width: Some(width),
+            image_height: Some(height),
+            image_row_pitch: Some(row_pitch),
+            buffer: Some(buffer.as_ptr()),
+            ..Default::default()
+        };
+        Self::view(context, flags, format, &desc, buffer)
+    }
+
+    fn view(context: &ClContext, flags: &[MemoryFlags], format: &ClImageFormats, desc: &ClImageDesc, buffer: &ClBuffer) -> Result<Self, ClError> {
+        let mut image = ClImage::new(context, &flags.to_vec(), format, desc, std::ptr::null_mut())?;
+        image.parent = Some(buffer.clone());
+        Ok(image)
+    }
+}
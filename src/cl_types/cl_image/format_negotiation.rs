@@ -0,0 +1,267 @@
+use crate::{
+    cl_types::{
+        cl_context::ClContext,
+        cl_image::{
+            ClImage, image_channel_data_type::ClImageChannelType, image_channel_order::ClImageChannelOrder,
+            image_formats::ClImageFormats, image_type::ClImageType,
+        },
+        memory_flags::MemoryFlags,
+    },
+    error::{ClError, wrapper_error::WrapperError},
+};
+
+/// # PixelLayout
+///
+/// How pixels are laid out in host memory: the order of their channels and the type of each channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PixelLayout {
+    pub channel_order: ClImageChannelOrder,
+    pub channel_type: ClImageChannelType,
+}
+
+impl PixelLayout {
+    pub fn new(channel_order: ClImageChannelOrder, channel_type: ClImageChannelType) -> Self {
+        Self { channel_order, channel_type }
+    }
+
+    /// Size in bytes of one pixel, or `None` for packed channel types.
+    pub fn pixel_size(&self) -> Option<usize> {
+        Some(self.channel_order.channel_count()? * self.channel_type.channel_size()?)
+    }
+
+    fn format(&self) -> ClImageFormats {
+        ClImageFormats { image_channel_order: self.channel_order, image_channel_data_type: self.channel_type }
+    }
+
+    fn unsupported(&self) -> ClError {
+        ClError::Wrapper(WrapperError::UnsupportedPixelLayout { channel_order: self.channel_order, channel_type: self.channel_type })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Channel {
+    R,
+    G,
+    B,
+    A,
+}
+
+/// Channels of an order, in memory order. `None` for orders that can't be swizzled
+/// (luminance, intensity, depth, sRGB and padded orders).
+fn channels(order: ClImageChannelOrder) -> Option<&'static [Channel]> {
+    use Channel::*;
+    match order {
+        ClImageChannelOrder::R => Some(&[R]),
+        ClImageChannelOrder::A => Some(&[A]),
+        ClImageChannelOrder::RG => Some(&[R, G]),
+        ClImageChannelOrder::RA => Some(&[R, A]),
+        ClImageChannelOrder::RGB => Some(&[R, G, B]),
+        ClImageChannelOrder::RGBA => Some(&[R, G, B, A]),
+        ClImageChannelOrder::BGRA => Some(&[B, G, R, A]),
+        ClImageChannelOrder::ARGB => Some(&[A, R, G, B]),
+        ClImageChannelOrder::ABGR => Some(&[A, B, G, R]),
+        _ => None,
+    }
+}
+
+/// Orders a layout can be stored in when its own isn't supported, in order of preference.
+const FALLBACK_ORDERS: [ClImageChannelOrder; 9] = [
+    ClImageChannelOrder::R,
+    ClImageChannelOrder::A,
+    ClImageChannelOrder::RG,
+    ClImageChannelOrder::RA,
+    ClImageChannelOrder::RGB,
+    ClImageChannelOrder::RGBA,
+    ClImageChannelOrder::BGRA,
+    ClImageChannelOrder::ARGB,
+    ClImageChannelOrder::ABGR,
+];
+
+/// Types that hold every value of `channel_type` exactly, narrowest first.
+fn wider_types(channel_type: ClImageChannelType) -> &'static [ClImageChannelType] {
+    use ClImageChannelType::*;
+    match channel_type {
+        UnormInt8 => &[UnormInt16, Float],
+        SnormInt8 => &[SnormInt16, Float],
+        UnormInt16 | SnormInt16 => &[Float],
+        UnsignedInt8 => &[UnsignedInt16, UnsignedInt32],
+        UnsignedInt16 => &[UnsignedInt32],
+        SignedInt8 => &[SignedInt16, SignedInt32],
+        SignedInt16 => &[SignedInt32],
+        _ => &[],
+    }
+}
+
+/// # NegotiatedFormat
+///
+/// The image format chosen for a [`PixelLayout`], and how host data has to be
+/// converted to and from it.
+///
+/// The format always holds every channel of the layout without losing precision:
+/// missing channels are added (colour as 0, alpha as 1) on the way to the device and
+/// dropped on the way back, and narrow types are widened (e.g. `UnormInt8` to `UnormInt16`).
+///
+/// # Example
+/// ```ignore
+/// let layout = PixelLayout::new(ClImageChannelOrder::RGB, ClImageChannelType::UnormInt8);
+/// let negotiated = executor.negotiate_image_format(&[MemoryFlags::ReadOnly], ClImageType::Image2D, layout)?;
+/// let pixels = negotiated.to_device(&rgb_bytes)?;
+/// let image = executor.create_image(&[MemoryFlags::ReadOnly, MemoryFlags::CopyHostPtr], &negotiated.format(), &desc, pixels.as_ptr() as *mut c_void)?;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NegotiatedFormat {
+    format: ClImageFormats,
+    host: PixelLayout,
+}
+
+impl NegotiatedFormat {
+    /// Picks the best format in `supported` for `layout`: the layout itself, then the same
+    /// channel type with the fewest extra channels, then the same with wider types.
+    pub fn negotiate(supported: &[ClImageFormats], layout: PixelLayout) -> Option<Self> {
+        if supported.contains(&layout.format()) {
+            return Some(Self { format: layout.format(), host: layout });
+        }
+
+        let wanted = channels(layout.channel_order)?;
+        layout.channel_type.channel_size()?;
+        let mut orders: Vec<ClImageChannelOrder> = FALLBACK_ORDERS
+            .into_iter()
+            .filter(|order| {
+                let available = channels(*order).unwrap();
+                wanted.iter().all(|channel| available.contains(channel))
+            })
+            .collect();
+        orders.sort_by_key(|order| channels(*order).unwrap().len());
+
+        std::iter::once(layout.channel_type)
+            .chain(wider_types(layout.channel_type).iter().copied())
+            .flat_map(|channel_type| orders.iter().map(move |order| PixelLayout::new(*order, channel_type)))
+            .find(|candidate| supported.contains(&candidate.format()))
+            .map(|device| Self { format: device.format(), host: layout })
+    }
+
+    /// Format to create the image with.
+    pub fn format(&self) -> ClImageFormats {
+        self.format
+    }
+
+    /// Layout of the host data.
+    pub fn host_layout(&self) -> PixelLayout {
+        self.host
+    }
+
+    /// Whether the image stores the layout as-is, so host data can be used directly.
+    pub fn is_exact(&self) -> bool {
+        self.format == self.host.format()
+    }
+
+    /// Whether channels have to be reordered, added or dropped.
+    pub fn needs_swizzle(&self) -> bool {
+        self.format.image_channel_order != self.host.channel_order
+    }
+
+    /// Whether channel values have to be converted to another type.
+    pub fn needs_conversion(&self) -> bool {
+        self.format.image_channel_data_type != self.host.channel_type
+    }
+
+    /// Converts pixels in the host layout to the image format.
+    pub fn to_device(&self, host: &[u8]) -> Result<Vec<u8>, ClError> {
+        convert(self.host, self.device_layout(), host)
+    }
+
+    /// Converts pixels read from the image back to the host layout.
+    pub fn to_host(&self, device: &[u8]) -> Result<Vec<u8>, ClError> {
+        convert(self.device_layout(), self.host, device)
+    }
+
+    fn device_layout(&self) -> PixelLayout {
+        PixelLayout::new(self.format.image_channel_order, self.format.image_channel_data_type)
+    }
+}
+
+fn convert(from: PixelLayout, to: PixelLayout, data: &[u8]) -> Result<Vec<u8>, ClError> {
+    let pixel_size = from.pixel_size().ok_or_else(|| from.unsupported())?;
+    if !data.len().is_multiple_of(pixel_size) {
+        return Err(ClError::Wrapper(WrapperError::BufferLengthMismatch {
+            expected: data.len() - data.len() % pixel_size,
+            actual: data.len(),
+        }));
+    }
+    if from == to {
+        return Ok(data.to_vec());
+    }
+
+    // Only `negotiate` creates conversions, and it only picks swizzlable layouts
+    let (from_channels, to_channels) = (channels(from.channel_order).unwrap(), channels(to.channel_order).unwrap());
+    let channel_size = from.channel_type.channel_size().unwrap();
+    let mut out = Vec::with_capacity(data.len() / pixel_size * to.pixel_size().unwrap());
+    for pixel in data.chunks_exact(pixel_size) {
+        for channel in to_channels {
+            match from_channels.iter().position(|c| c == channel) {
+                Some(i) if from.channel_type == to.channel_type => {
+                    out.extend_from_slice(&pixel[i * channel_size..(i + 1) * channel_size]);
+                }
+                Some(i) => {
+                    let value = decode(from.channel_type, &pixel[i * channel_size..(i + 1) * channel_size]);
+                    encode(to.channel_type, value, &mut out);
+                }
+                None => encode(to.channel_type, if *channel == Channel::A { 1.0 } else { 0.0 }, &mut out),
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Reads a channel as its normalized value (`[0, 1]` or `[-1, 1]`) or its integer value.
+fn decode(channel_type: ClImageChannelType, bytes: &[u8]) -> f64 {
+    use ClImageChannelType::*;
+    match channel_type {
+        UnormInt8 => bytes[0] as f64 / u8::MAX as f64,
+        UnormInt16 => u16::from_ne_bytes([bytes[0], bytes[1]]) as f64 / u16::MAX as f64,
+        SnormInt8 => (i8::from_ne_bytes([bytes[0]]) as f64 / i8::MAX as f64).max(-1.0),
+        SnormInt16 => (i16::from_ne_bytes([bytes[0], bytes[1]]) as f64 / i16::MAX as f64).max(-1.0),
+        UnsignedInt8 => bytes[0] as f64,
+        UnsignedInt16 => u16::from_ne_bytes([bytes[0], bytes[1]]) as f64,
+        UnsignedInt32 => u32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64,
+        SignedInt8 => i8::from_ne_bytes([bytes[0]]) as f64,
+        SignedInt16 => i16::from_ne_bytes([bytes[0], bytes[1]]) as f64,
+        SignedInt32 => i32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64,
+        Float => f32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64,
+        // Never converted from, see `wider_types`
+        HalfFloat | UnormShort565 | UnormShort555 | UnormInt101010 | UnormInt101010_2 | Unknown(_) => 0.0,
+    }
+}
+
+fn encode(channel_type: ClImageChannelType, value: f64, out: &mut Vec<u8>) {
+    use ClImageChannelType::*;
+    match channel_type {
+        UnormInt8 => out.push((value.clamp(0.0, 1.0) * u8::MAX as f64).round() as u8),
+        UnormInt16 => out.extend_from_slice(&((value.clamp(0.0, 1.0) * u16::MAX as f64).round() as u16).to_ne_bytes()),
+        SnormInt8 => out.extend_from_slice(&((value.clamp(-1.0, 1.0) * i8::MAX as f64).round() as i8).to_ne_bytes()),
+        SnormInt16 => out.extend_from_slice(&((value.clamp(-1.0, 1.0) * i16::MAX as f64).round() as i16).to_ne_bytes()),
+        UnsignedInt8 => out.push(value as u8),
+        UnsignedInt16 => out.extend_from_slice(&(value as u16).to_ne_bytes()),
+        UnsignedInt32 => out.extend_from_slice(&(value as u32).to_ne_bytes()),
+        SignedInt8 => out.extend_from_slice(&(value as i8).to_ne_bytes()),
+        SignedInt16 => out.extend_from_slice(&(value as i16).to_ne_bytes()),
+        SignedInt32 => out.extend_from_slice(&(value as i32).to_ne_bytes()),
+        Float => out.extend_from_slice(&(value as f32).to_ne_bytes()),
+        // Only ever converted to for the fill values 0 and 1
+        HalfFloat => out.extend_from_slice(&(if value == 0.0 { 0u16 } else { 0x3c00 }).to_ne_bytes()),
+        UnormShort565 | UnormShort555 | UnormInt101010 | UnormInt101010_2 | Unknown(_) => {}
+    }
+}
+
+impl ClImage {
+    /// Picks the format the context supports for `layout`, see [`NegotiatedFormat::negotiate`].
+    pub fn negotiate_format(
+        context: &ClContext,
+        flags: &[MemoryFlags],
+        image_type: ClImageType,
+        layout: PixelLayout,
+    ) -> Result<NegotiatedFormat, ClError> {
+        let supported = ClImage::get_supported_image_formats(context, &flags.to_vec(), image_type)?;
+        NegotiatedFormat::negotiate(&supported, layout).ok_or_else(|| layout.unsupported())
+    }
+}
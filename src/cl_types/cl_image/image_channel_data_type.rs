@@ -9,6 +9,7 @@ pub enum ClImageChannelType {
     UnormShort565,
     UnormShort555,
     UnormInt101010,
+    UnormInt101010_2,
 
     SignedInt8,
     SignedInt16,
@@ -34,6 +35,7 @@ impl From<u32> for ClImageChannelType {
             cl3::memory::CL_UNORM_SHORT_565 => Self::UnormShort565,
             cl3::memory::CL_UNORM_SHORT_555 => Self::UnormShort555,
             cl3::memory::CL_UNORM_INT_101010 => Self::UnormInt101010,
+            cl3::memory::CL_UNORM_INT_101010_2 => Self::UnormInt101010_2,
             cl3::memory::CL_SIGNED_INT8 => Self::SignedInt8,
             cl3::memory::CL_SIGNED_INT16 => Self::SignedInt16,
             cl3::memory::CL_SIGNED_INT32 => Self::SignedInt32,
@@ -57,6 +59,7 @@ impl Into<u32> for ClImageChannelType {
             Self::UnormShort565 => cl3::memory::CL_UNORM_SHORT_565,
             Self::UnormShort555 => cl3::memory::CL_UNORM_SHORT_555,
             Self::UnormInt101010 => cl3::memory::CL_UNORM_INT_101010,
+            Self::UnormInt101010_2 => cl3::memory::CL_UNORM_INT_101010_2,
             Self::SignedInt8 => cl3::memory::CL_SIGNED_INT8,
             Self::SignedInt16 => cl3::memory::CL_SIGNED_INT16,
             Self::SignedInt32 => cl3::memory::CL_SIGNED_INT32,
@@ -70,3 +73,38 @@ impl Into<u32> for ClImageChannelType {
     }
 }
 
+
+impl ClImageChannelType {
+    /// Every channel type of the OpenCL core.
+    pub const ALL: [Self; 16] = [
+        Self::SnormInt8, Self::SnormInt16, Self::UnormInt8, Self::UnormInt16, Self::UnormShort565, Self::UnormShort555,
+        Self::UnormInt101010, Self::UnormInt101010_2, Self::SignedInt8, Self::SignedInt16, Self::SignedInt32, Self::UnsignedInt8,
+        Self::UnsignedInt16, Self::UnsignedInt32, Self::HalfFloat, Self::Float,
+    ];
+
+    /// Whether the channels of a pixel share one packed word (`UnormShort565`, `UnormShort555`,
+    /// `UnormInt101010`, `UnormInt101010_2`).
+    pub fn is_packed(&self) -> bool {
+        matches!(self, Self::UnormShort565 | Self::UnormShort555 | Self::UnormInt101010 | Self::UnormInt101010_2)
+    }
+
+    /// Size in bytes of one channel, or `None` for packed types whose channels share a word.
+    pub fn channel_size(&self) -> Option<usize> {
+        match self {
+            Self::SnormInt8 | Self::UnormInt8 | Self::SignedInt8 | Self::UnsignedInt8 => Some(1),
+            Self::SnormInt16 | Self::UnormInt16 | Self::SignedInt16 | Self::UnsignedInt16 | Self::HalfFloat => Some(2),
+            Self::SignedInt32 | Self::UnsignedInt32 | Self::Float => Some(4),
+            Self::UnormShort565 | Self::UnormShort555 | Self::UnormInt101010 | Self::UnormInt101010_2 | Self::Unknown(_) => None,
+        }
+    }
+
+    /// Whether kernels read channels of this type as floats (`read_imagef`): normalized and
+    /// float types, as opposed to the integer types read with `read_imagei`/`read_imageui`.
+    pub fn reads_as_float(&self) -> bool {
+        !matches!(
+            self,
+            Self::SignedInt8 | Self::SignedInt16 | Self::SignedInt32
+                | Self::UnsignedInt8 | Self::UnsignedInt16 | Self::UnsignedInt32 | Self::Unknown(_)
+        )
+    }
+}
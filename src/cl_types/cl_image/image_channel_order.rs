@@ -18,6 +18,7 @@ pub enum ClImageChannelOrder {
     SRgb,
     SRgbx,
     SRgba,
+    SBgra,
     Unknown(u32),
 }
 
@@ -42,6 +43,7 @@ impl From<u32> for ClImageChannelOrder {
             cl3::memory::CL_sRGB => Self::SRgb,
             cl3::memory::CL_sRGBx => Self::SRgbx,
             cl3::memory::CL_sRGBA => Self::SRgba,
+            cl3::ext::CL_sBGRA => Self::SBgra,
             other => Self::Unknown(other),
         }
     }
@@ -69,7 +71,28 @@ fn into(self) -> u32 {
             ClImageChannelOrder::SRgb => cl3::memory::CL_sRGB,
             ClImageChannelOrder::SRgbx => cl3::memory::CL_sRGBx,
             ClImageChannelOrder::SRgba => cl3::memory::CL_sRGBA,
+            ClImageChannelOrder::SBgra => cl3::ext::CL_sBGRA,
             ClImageChannelOrder::Unknown(v) => v,
         }
     }
 }
+
+impl ClImageChannelOrder {
+    /// Every channel order of the OpenCL core.
+    pub const ALL: [Self; 19] = [
+        Self::R, Self::A, Self::RG, Self::RA, Self::RGB, Self::RGBA, Self::BGRA, Self::ARGB, Self::ABGR,
+        Self::Intensity, Self::Luminance, Self::Rx, Self::RGx, Self::RGBx, Self::Depth, Self::SRgb, Self::SRgbx, Self::SRgba,
+        Self::SBgra,
+    ];
+
+    /// Number of channels stored per pixel, padding (`x`) included.
+    pub fn channel_count(&self) -> Option<usize> {
+        match self {
+            Self::R | Self::A | Self::Intensity | Self::Luminance | Self::Depth => Some(1),
+            Self::RG | Self::RA | Self::Rx => Some(2),
+            Self::RGB | Self::RGx | Self::SRgb => Some(3),
+            Self::RGBA | Self::BGRA | Self::ARGB | Self::ABGR | Self::RGBx | Self::SRgbx | Self::SRgba | Self::SBgra => Some(4),
+            Self::Unknown(_) => None,
+        }
+    }
+}
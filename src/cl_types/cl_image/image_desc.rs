@@ -36,11 +36,38 @@ impl Default for ClImageDesc {
     }
 }
 
+impl ClImageDesc {
+    /// Bytes of device memory an image of this description holds with pixels of
+    /// `bytes_per_pixel` bytes, over all its mip levels and layers, without driver padding.
+    /// Images over a buffer (`buffer` set) hold none of their own. Saturates instead of overflowing.
+    pub fn byte_size(&self, bytes_per_pixel: usize) -> usize {
+        if self.buffer.is_some() {
+            return 0;
+        }
+        let extent = |dim: Option<usize>| dim.unwrap_or(0).max(1);
+        let (width, height, depth, layers) = match self.image_type {
+            ClImageType::Image1D | ClImageType::Image1DBuffer => (extent(self.image_width), 1, 1, 1),
+            ClImageType::Image1DArray => (extent(self.image_width), 1, 1, extent(self.image_array_size)),
+            ClImageType::Image2D => (extent(self.image_width), extent(self.image_height), 1, 1),
+            ClImageType::Image2DArray => (extent(self.image_width), extent(self.image_height), 1, extent(self.image_array_size)),
+            ClImageType::Image3D => (extent(self.image_width), extent(self.image_height), extent(self.image_depth), 1),
+        };
+        let levels = self.num_mip_levels.unwrap_or(0).clamp(1, usize::BITS);
+        let pixels = (0..levels)
+            .map(|level| {
+                let scaled = |dim: usize| (dim >> level).max(1);
+                scaled(width).saturating_mul(scaled(height)).saturating_mul(scaled(depth))
+            })
+            .fold(0usize, usize::saturating_add);
+        pixels.saturating_mul(layers).saturating_mul(bytes_per_pixel)
+    }
+}
+
 impl Into<cl_image_desc> for ClImageDesc {
     fn into(self) -> cl_image_desc {
         cl_image_desc {
             image_type: self.image_type.into(),
-            image_width: self.image_height.unwrap_or(0),
+            image_width: self.image_width.unwrap_or(0),
             image_height: self.image_height.unwrap_or(0),
             image_depth:  self.image_depth.unwrap_or(0),
             image_array_size: self.image_array_size.unwrap_or(0),
@@ -57,7 +84,7 @@ impl Into<cl_image_desc> for &ClImageDesc {
     fn into(self) -> cl_image_desc {
         cl_image_desc {
             image_type: self.image_type.into(),
-            image_width: self.image_height.unwrap_or(0),
+            image_width: self.image_width.unwrap_or(0),
             image_height: self.image_height.unwrap_or(0),
             image_depth:  self.image_depth.unwrap_or(0),
             image_array_size: self.image_array_size.unwrap_or(0),
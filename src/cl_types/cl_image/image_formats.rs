@@ -1,11 +1,28 @@
-use crate::cl_types::cl_image::{image_channel_data_type::{ClImageChannelType}, image_channel_order::ClImageChannelOrder};
-#[derive(Debug, Clone, Copy )]
+use cl3::ext::cl_image_format;
+
+use crate::{
+    cl_types::cl_image::{image_channel_data_type::ClImageChannelType, image_channel_order::ClImageChannelOrder},
+    error::{ClError, wrapper_error::WrapperError},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ClImageFormats {
     pub image_channel_order: ClImageChannelOrder,
     pub image_channel_data_type: ClImageChannelType,
 }
 
 impl ClImageFormats {
+    /// Format of `channel_order` channels of `channel_type`.
+    ///
+    /// Fails with `InvalidImageFormat` for combinations OpenCL rejects (see [`Self::is_valid`]).
+    pub fn new(channel_order: ClImageChannelOrder, channel_type: ClImageChannelType) -> Result<Self, ClError> {
+        let format = Self { image_channel_order: channel_order, image_channel_data_type: channel_type };
+        if !format.is_valid() {
+            return Err(ClError::Wrapper(WrapperError::InvalidImageFormat { channel_order, channel_type }));
+        }
+        Ok(format)
+    }
+
     pub fn rgba_unorm_int8() -> Self {
         Self {
             image_channel_order: ClImageChannelOrder::RGBA,
@@ -19,4 +36,90 @@ impl ClImageFormats {
             image_channel_data_type: ClImageChannelType::Float,
         }
     }
+
+    /// Every valid combination of core channel orders and types. Which of them a device
+    /// supports is given by `ClImage::get_supported_image_formats`.
+    pub fn all() -> Vec<Self> {
+        ClImageChannelOrder::ALL
+            .iter()
+            .flat_map(|&order| {
+                ClImageChannelType::ALL
+                    .iter()
+                    .map(move |&channel_type| Self { image_channel_order: order, image_channel_data_type: channel_type })
+            })
+            .filter(Self::is_valid)
+            .collect()
+    }
+
+    /// Whether OpenCL allows this combination of channel order and type:
+    /// - `UnormInt101010_2` only with `RGBA`
+    /// - other packed types (`UnormShort565`, `UnormShort555`, `UnormInt101010`) only with `RGB` or `RGBx`,
+    ///   and those orders only with packed types
+    /// - `Intensity` and `Luminance` only with normalized 8 and 16-bit, half and float types
+    /// - `Depth` only with `UnormInt16` or `Float`
+    /// - `BGRA`, `ARGB` and `ABGR` only with 8-bit types
+    /// - `sRGB` orders and `sBGRA` only with `UnormInt8`
+    ///
+    /// Unknown orders or types (e.g. from vendor extensions) are accepted.
+    pub fn is_valid(&self) -> bool {
+        use ClImageChannelOrder as Order;
+        use ClImageChannelType as Type;
+
+        let channel_type = self.image_channel_data_type;
+        match self.image_channel_order {
+            Order::Unknown(_) => true,
+            _ if matches!(channel_type, Type::Unknown(_)) => true,
+            // 10 bits per color and 2 of alpha
+            _ if channel_type == Type::UnormInt101010_2 => self.image_channel_order == Order::RGBA,
+            Order::RGB | Order::RGBx => channel_type.is_packed(),
+            _ if channel_type.is_packed() => false,
+            Order::Intensity | Order::Luminance => {
+                matches!(channel_type, Type::UnormInt8 | Type::UnormInt16 | Type::SnormInt8 | Type::SnormInt16 | Type::HalfFloat | Type::Float)
+            }
+            Order::Depth => matches!(channel_type, Type::UnormInt16 | Type::Float),
+            Order::BGRA | Order::ARGB | Order::ABGR => {
+                matches!(channel_type, Type::UnormInt8 | Type::SnormInt8 | Type::SignedInt8 | Type::UnsignedInt8)
+            }
+            Order::SRgb | Order::SRgbx | Order::SRgba | Order::SBgra => channel_type == Type::UnormInt8,
+            _ => true,
+        }
+    }
+
+    /// Size in bytes of one pixel, or `None` if the order or type is unknown or the
+    /// combination is invalid.
+    pub fn bytes_per_pixel(&self) -> Option<usize> {
+        if !self.is_valid() {
+            return None;
+        }
+        match self.image_channel_data_type {
+            ClImageChannelType::UnormShort565 | ClImageChannelType::UnormShort555 => Some(2),
+            ClImageChannelType::UnormInt101010 | ClImageChannelType::UnormInt101010_2 => Some(4),
+            channel_type => Some(self.image_channel_order.channel_count()? * channel_type.channel_size()?),
+        }
+    }
+}
+
+impl TryFrom<cl_image_format> for ClImageFormats {
+    type Error = ClError;
+
+    fn try_from(format: cl_image_format) -> Result<Self, ClError> {
+        Self::new(format.image_channel_order.into(), format.image_channel_data_type.into())
+    }
+}
+
+impl TryFrom<ClImageFormats> for cl_image_format {
+    type Error = ClError;
+
+    fn try_from(format: ClImageFormats) -> Result<Self, ClError> {
+        if !format.is_valid() {
+            return Err(ClError::Wrapper(WrapperError::InvalidImageFormat {
+                channel_order: format.image_channel_order,
+                channel_type: format.image_channel_data_type,
+            }));
+        }
+        Ok(cl_image_format {
+            image_channel_order: format.image_channel_order.into(),
+            image_channel_data_type: format.image_channel_data_type.into(),
+        })
+    }
 }
@@ -0,0 +1,233 @@
+use std::os::raw::c_void;
+
+use image::{DynamicImage, ImageBuffer, Luma, LumaA, Pixel, Primitive, Rgb, Rgba};
+
+use crate::{
+    cl_types::{
+        cl_command_queue::ClCommandQueue,
+        cl_context::ClContext,
+        cl_image::{
+            ClImage, image_channel_data_type::ClImageChannelType, image_channel_order::ClImageChannelOrder,
+            image_desc::ClImageDesc, image_formats::ClImageFormats, image_type::ClImageType,
+        },
+        memory_flags::MemoryFlags,
+    },
+    error::{ClError, wrapper_error::WrapperError},
+};
+
+/// A subpixel type of the `image` crate with an OpenCL channel type.
+///
+/// # Safety
+///
+/// `CHANNEL_TYPE` must store one channel in exactly `size_of::<Self>()` bytes, valid as
+/// `Self` for any bit pattern, since images are copied to and from `Vec<Self>` by size.
+pub unsafe trait ClSubpixel: Primitive + Send + Sync + 'static {
+    const CHANNEL_TYPE: ClImageChannelType;
+}
+
+unsafe impl ClSubpixel for u8 {
+    const CHANNEL_TYPE: ClImageChannelType = ClImageChannelType::UnormInt8;
+}
+
+unsafe impl ClSubpixel for u16 {
+    const CHANNEL_TYPE: ClImageChannelType = ClImageChannelType::UnormInt16;
+}
+
+unsafe impl ClSubpixel for f32 {
+    const CHANNEL_TYPE: ClImageChannelType = ClImageChannelType::Float;
+}
+
+/// # ClPixel
+///
+/// A pixel type of the `image` crate that can be stored in a `ClImage`.
+///
+/// Pixels are stored in their own format when OpenCL has one and the device supports
+/// it (e.g. `Luma<u8>` as `R`/`UnormInt8`), and expanded to RGBA with the same channel
+/// type otherwise. RGBA is in every device's list of required formats.
+///
+/// # Safety
+///
+/// `native_format`, if any, must store a pixel as its `CHANNEL_COUNT` subpixels in order,
+/// with the subpixel's `CHANNEL_TYPE`: transfers size the host memory from the subpixels,
+/// while the device reads and writes the format's bytes per pixel.
+pub unsafe trait ClPixel: Pixel<Subpixel: ClSubpixel> + Send + Sync + 'static {
+    /// Format that stores the pixel as-is, if there is one.
+    fn native_format() -> Option<ClImageFormats>;
+
+    /// Inverse of `Pixel::to_rgba` for pixels read back from an RGBA image.
+    fn from_rgba(rgba: Rgba<Self::Subpixel>) -> Self;
+}
+
+/// RGBA format with the channel type of `S`.
+pub fn rgba_format<S: ClSubpixel>() -> ClImageFormats {
+    ClImageFormats { image_channel_order: ClImageChannelOrder::RGBA, image_channel_data_type: S::CHANNEL_TYPE }
+}
+
+macro_rules! impl_cl_pixel {
+    ($($subpixel:ty),*) => {
+        $(
+            unsafe impl ClPixel for Luma<$subpixel> {
+                fn native_format() -> Option<ClImageFormats> {
+                    Some(ClImageFormats { image_channel_order: ClImageChannelOrder::R, image_channel_data_type: <$subpixel>::CHANNEL_TYPE })
+                }
+
+                fn from_rgba(rgba: Rgba<$subpixel>) -> Self {
+                    Luma([rgba[0]])
+                }
+            }
+
+            unsafe impl ClPixel for LumaA<$subpixel> {
+                fn native_format() -> Option<ClImageFormats> {
+                    Some(ClImageFormats { image_channel_order: ClImageChannelOrder::RA, image_channel_data_type: <$subpixel>::CHANNEL_TYPE })
+                }
+
+                fn from_rgba(rgba: Rgba<$subpixel>) -> Self {
+                    LumaA([rgba[0], rgba[3]])
+                }
+            }
+
+            unsafe impl ClPixel for Rgb<$subpixel> {
+                // OpenCL only has RGB for packed channel types
+                fn native_format() -> Option<ClImageFormats> {
+                    None
+                }
+
+                fn from_rgba(rgba: Rgba<$subpixel>) -> Self {
+                    Rgb([rgba[0], rgba[1], rgba[2]])
+                }
+            }
+
+            unsafe impl ClPixel for Rgba<$subpixel> {
+                fn native_format() -> Option<ClImageFormats> {
+                    Some(rgba_format::<$subpixel>())
+                }
+
+                fn from_rgba(rgba: Rgba<$subpixel>) -> Self {
+                    rgba
+                }
+            }
+        )*
+    };
+}
+
+impl_cl_pixel!(u8, u16, f32);
+
+fn desc_2d(width: u32, height: u32) -> ClImageDesc {
+    ClImageDesc {
+        image_type: ClImageType::Image2D,
+        image_width: Some(width as usize),
+        image_height: Some(height as usize),
+        ..Default::default()
+    }
+}
+
+/// `ImageBuffer` of pixels read from a `width` x `height` image, whose dimensions must fit in a `u32`.
+fn image_buffer<P: Pixel>(width: usize, height: usize, data: Vec<P::Subpixel>) -> Result<ImageBuffer<P, Vec<P::Subpixel>>, ClError> {
+    u32::try_from(width)
+        .ok()
+        .zip(u32::try_from(height).ok())
+        .and_then(|(w, h)| ImageBuffer::from_raw(w, h, data))
+        .ok_or(ClError::Wrapper(WrapperError::ImageTooLargeForHost { width, height }))
+}
+
+impl ClImage {
+    /// Creates a 2D image holding a copy of `image`.
+    ///
+    /// Uses the pixel's own format if the device supports it, otherwise converts to RGBA.
+    pub fn from_image_buffer<P: ClPixel>(
+        context: &ClContext,
+        flags: &[MemoryFlags],
+        image: &ImageBuffer<P, Vec<P::Subpixel>>,
+    ) -> Result<Self, ClError> {
+        let format = Self::image_buffer_format::<P>(context, flags)?;
+        let mut flags = flags.to_vec();
+        if !flags.contains(&MemoryFlags::CopyHostPtr) {
+            flags.push(MemoryFlags::CopyHostPtr);
+        }
+
+        let desc = desc_2d(image.width(), image.height());
+        if Some(format) == P::native_format() {
+            ClImage::new(context, &flags, &format, &desc, image.as_raw().as_ptr() as *mut c_void)
+        } else {
+            let rgba: Vec<P::Subpixel> = image.pixels().flat_map(|p| p.to_rgba().0).collect();
+            ClImage::new(context, &flags, &format, &desc, rgba.as_ptr() as *mut c_void)
+        }
+    }
+
+    /// Format `from_image_buffer` creates images of `P` in: the pixel's own format if the
+    /// device supports it, RGBA otherwise.
+    pub fn image_buffer_format<P: ClPixel>(context: &ClContext, flags: &[MemoryFlags]) -> Result<ClImageFormats, ClError> {
+        let supported = ClImage::get_supported_image_formats(context, &flags.to_vec(), ClImageType::Image2D)?;
+        Ok(P::native_format().filter(|format| supported.contains(format)).unwrap_or_else(rgba_format::<P::Subpixel>))
+    }
+
+    /// Creates a 2D image holding a copy of `image`, in the format matching its variant.
+    pub fn from_dynamic_image(context: &ClContext, flags: &[MemoryFlags], image: &DynamicImage) -> Result<Self, ClError> {
+        match image {
+            DynamicImage::ImageLuma8(b) => Self::from_image_buffer(context, flags, b),
+            DynamicImage::ImageLumaA8(b) => Self::from_image_buffer(context, flags, b),
+            DynamicImage::ImageRgb8(b) => Self::from_image_buffer(context, flags, b),
+            DynamicImage::ImageRgba8(b) => Self::from_image_buffer(context, flags, b),
+            DynamicImage::ImageLuma16(b) => Self::from_image_buffer(context, flags, b),
+            DynamicImage::ImageLumaA16(b) => Self::from_image_buffer(context, flags, b),
+            DynamicImage::ImageRgb16(b) => Self::from_image_buffer(context, flags, b),
+            DynamicImage::ImageRgba16(b) => Self::from_image_buffer(context, flags, b),
+            DynamicImage::ImageRgb32F(b) => Self::from_image_buffer(context, flags, b),
+            DynamicImage::ImageRgba32F(b) => Self::from_image_buffer(context, flags, b),
+            other => Self::from_image_buffer(context, flags, &other.to_rgba32f()),
+        }
+    }
+
+    /// Reads the whole image back into an `ImageBuffer` of `P`.
+    ///
+    /// The image must be in `P`'s own format or in RGBA with `P`'s channel type
+    /// (which is what `from_image_buffer` creates).
+    pub async fn to_image_buffer<P: ClPixel>(&self, queue: &ClCommandQueue) -> Result<ImageBuffer<P, Vec<P::Subpixel>>, ClError> {
+        let format = self.get_image_format()?;
+        let (width, height) = (self.get_width()?, self.get_height()?.max(1));
+        let region = [width, height, 1];
+
+        if Some(format) == P::native_format() {
+            let mut data = vec![P::Subpixel::DEFAULT_MIN_VALUE; width * height * P::CHANNEL_COUNT as usize];
+            queue.read_image_raw(self, [0, 0, 0], region, 0, 0, data.as_mut_ptr() as *mut c_void, None).await?;
+            return image_buffer(width, height, data);
+        }
+
+        let rgba = rgba_format::<P::Subpixel>();
+        if format != rgba {
+            return Err(ClError::Wrapper(WrapperError::IncompatibleImageFormat {
+                image: format,
+                requested: P::native_format().unwrap_or(rgba),
+            }));
+        }
+
+        let mut data = vec![P::Subpixel::DEFAULT_MIN_VALUE; width * height * 4];
+        queue.read_image_raw(self, [0, 0, 0], region, 0, 0, data.as_mut_ptr() as *mut c_void, None).await?;
+        let pixels: Vec<P::Subpixel> = data
+            .chunks_exact(4)
+            .flat_map(|c| P::from_rgba(Rgba([c[0], c[1], c[2], c[3]])).channels().to_vec())
+            .collect();
+        image_buffer(width, height, pixels)
+    }
+
+    /// Reads the whole image back into the `DynamicImage` variant matching its format.
+    pub async fn to_dynamic_image(&self, queue: &ClCommandQueue) -> Result<DynamicImage, ClError> {
+        let format = self.get_image_format()?;
+        let image = match (format.image_channel_order, format.image_channel_data_type) {
+            (ClImageChannelOrder::R, ClImageChannelType::UnormInt8) => DynamicImage::ImageLuma8(self.to_image_buffer(queue).await?),
+            (ClImageChannelOrder::RA, ClImageChannelType::UnormInt8) => DynamicImage::ImageLumaA8(self.to_image_buffer(queue).await?),
+            (ClImageChannelOrder::RGBA, ClImageChannelType::UnormInt8) => DynamicImage::ImageRgba8(self.to_image_buffer(queue).await?),
+            (ClImageChannelOrder::R, ClImageChannelType::UnormInt16) => DynamicImage::ImageLuma16(self.to_image_buffer(queue).await?),
+            (ClImageChannelOrder::RA, ClImageChannelType::UnormInt16) => DynamicImage::ImageLumaA16(self.to_image_buffer(queue).await?),
+            (ClImageChannelOrder::RGBA, ClImageChannelType::UnormInt16) => DynamicImage::ImageRgba16(self.to_image_buffer(queue).await?),
+            (ClImageChannelOrder::RGBA, ClImageChannelType::Float) => DynamicImage::ImageRgba32F(self.to_image_buffer(queue).await?),
+            _ => {
+                return Err(ClError::Wrapper(WrapperError::IncompatibleImageFormat {
+                    image: format,
+                    requested: rgba_format::<f32>(),
+                }));
+            }
+        };
+        Ok(image)
+    }
+}
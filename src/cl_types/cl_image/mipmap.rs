@@ -0,0 +1,91 @@
+use crate::{
+    cl_types::{
+        cl_image::{ClImage, image_desc::ClImageDesc, image_type::ClImageType},
+        mem_object_type::MemObjectType,
+    },
+    error::{ClError, wrapper_error::WrapperError},
+};
+
+/// Averages 2x2 blocks of `src` into `dst`, which is half its size (rounded down, at least 1).
+/// Edges of odd-sized images are clamped.
+pub(crate) const DOWNSAMPLE_SOURCE: &str = r#"
+__constant sampler_t edge = CLK_NORMALIZED_COORDS_FALSE | CLK_ADDRESS_CLAMP_TO_EDGE | CLK_FILTER_NEAREST;
+
+kernel void downsample_2x2(read_only image2d_t src, write_only image2d_t dst) {
+    int2 p = (int2)(get_global_id(0), get_global_id(1));
+    int2 s = p * 2;
+    float4 sum = read_imagef(src, edge, s) + read_imagef(src, edge, s + (int2)(1, 0))
+               + read_imagef(src, edge, s + (int2)(0, 1)) + read_imagef(src, edge, s + (int2)(1, 1));
+    write_imagef(dst, p, sum * 0.25f);
+}
+"#;
+
+impl ClImage {
+    /// Whether the image has more than one mip level.
+    pub fn is_mipmapped(&self) -> Result<bool, ClError> {
+        Ok(self.get_num_mip_levels()? > 1)
+    }
+
+    /// Whether the image has more than one sample per pixel. Multi-sample images only come
+    /// from sharing with graphics APIs and can't be read or written from the host.
+    pub fn is_multisampled(&self) -> Result<bool, ClError> {
+        Ok(self.get_num_samples()? > 1)
+    }
+
+    /// Size of mip level `level`: each dimension is halved per level (rounded down, at least 1),
+    /// except the array size.
+    pub fn mip_level_region(&self, level: u32) -> Result<[usize; 3], ClError> {
+        let levels = self.get_num_mip_levels()?.max(1);
+        if level >= levels {
+            return Err(ClError::Wrapper(WrapperError::MipLevelOutOfRange { level, levels }));
+        }
+
+        let shrink = |size: usize| (size >> level).max(1);
+        let (width, height, depth) = (self.get_width()?, self.get_height()?, self.get_depth()?);
+        Ok(match self.get_mem_type()? {
+            MemObjectType::Image1DArray => [shrink(width), self.get_array_size()?, 1],
+            MemObjectType::Image2DArray => [shrink(width), shrink(height), self.get_array_size()?],
+            MemObjectType::Image3D => [shrink(width), shrink(height), shrink(depth)],
+            _ => [shrink(width), shrink(height.max(1)), 1],
+        })
+    }
+
+    /// Description of a single-level image the size of mip level `level`.
+    pub fn mip_level_desc(&self, level: u32) -> Result<ClImageDesc, ClError> {
+        let region = self.mip_level_region(level)?;
+        let desc = ClImageDesc { image_width: Some(region[0]), ..Default::default() };
+        Ok(match self.get_mem_type()? {
+            MemObjectType::Image1DArray => ClImageDesc { image_type: ClImageType::Image1DArray, image_array_size: Some(region[1]), ..desc },
+            MemObjectType::Image2D => ClImageDesc { image_type: ClImageType::Image2D, image_height: Some(region[1]), ..desc },
+            MemObjectType::Image2DArray => ClImageDesc {
+                image_type: ClImageType::Image2DArray,
+                image_height: Some(region[1]),
+                image_array_size: Some(region[2]),
+                ..desc
+            },
+            MemObjectType::Image3D => ClImageDesc {
+                image_type: ClImageType::Image3D,
+                image_height: Some(region[1]),
+                image_depth: Some(region[2]),
+                ..desc
+            },
+            _ => ClImageDesc { image_type: ClImageType::Image1D, ..desc },
+        })
+    }
+
+    /// `origin` within mip level `level`, as `cl_khr_mipmap_image` expects it: the level
+    /// goes in the coordinate after the last one the image type uses.
+    pub fn mip_origin(&self, origin: [usize; 3], level: u32) -> Result<[usize; 4], ClError> {
+        let mut mip_origin = [origin[0], origin[1], origin[2], 0];
+        if level == 0 {
+            return Ok(mip_origin);
+        }
+        let index = match self.get_mem_type()? {
+            MemObjectType::Image1D | MemObjectType::Image1DBuffer => 1,
+            MemObjectType::Image1DArray | MemObjectType::Image2D => 2,
+            _ => 3,
+        };
+        mip_origin[index] = level as usize;
+        Ok(mip_origin)
+    }
+}
@@ -11,9 +11,13 @@ use crate::{
         },
         cl_platform::ClPlatform,
         releaseable::Releaseable,
+        cl_buffer::ClBuffer,
+        mem_object::MemObject,
+        mem_object_type::MemObjectType,
     },
-    error::{ClError, api_error::ApiError},
+    error::{ClError, api_error::ApiError, wrapper_error::WrapperError},
     cl_image_generate_getters,
+    cl_mem_generate_getters,
 };
 
 pub mod image_channel_data_type;
@@ -21,6 +25,17 @@ pub mod image_channel_order;
 pub mod image_formats;
 pub mod image_type;
 pub mod image_desc;
+#[cfg(feature = "CL_VERSION_1_1")]
+pub mod format_negotiation;
+#[cfg(feature = "CL_VERSION_1_2")]
+pub mod mipmap;
+#[cfg(feature = "CL_VERSION_1_2")]
+pub mod buffer_image;
+pub mod pixel;
+#[cfg(feature = "CL_VERSION_1_2")]
+pub mod typed_image;
+#[cfg(all(feature = "image", feature = "CL_VERSION_1_2"))]
+pub mod image_interop;
 
 /// # ClImage
 /// 
@@ -28,7 +43,9 @@ pub mod image_desc;
 /// Unlike Buffers, images are optimized for spatial access (2D/3D)
 /// and can use hardware filtering.
 pub struct ClImage {
-    value: *mut c_void
+    value: *mut c_void,
+    /// The buffer this image is a view of, kept alive as long as the image exists.
+    parent: Option<ClBuffer>,
 }
 
 impl ClImage {
@@ -63,10 +80,7 @@ impl ClImage {
     #[cfg(feature = "CL_VERSION_1_2")]
     pub fn new(context: &ClContext, flags: &Vec<MemoryFlags>, image_format: &ClImageFormats, image_desc: &ClImageDesc, host_ptr: *mut c_void ) -> Result<Self, ClError> {
 
-        let image_format = cl_image_format {
-            image_channel_data_type: image_format.image_channel_data_type.into(),
-            image_channel_order: image_format.image_channel_order.into()
-        };
+        let image_format = cl_image_format::try_from(*image_format)?;
 
         let desc: cl_image_desc = image_desc.into();
 
@@ -75,16 +89,14 @@ impl ClImage {
         };
         
         Ok(Self {
-            value: raw
+            value: raw,
+            parent: None,
         })
     }
 
     #[cfg(feature = "CL_VERSION_3_0")]
     pub fn new_with_properties(context: &ClContext, properties: Vec<u64> ,flags: &Vec<MemoryFlags>, image_format: &ClImageFormats, image_desc: &ClImageDesc, host_ptr: *mut c_void ) -> Result<Self, ClError> {
-        let image_format = cl_image_format {
-            image_channel_data_type: image_format.image_channel_data_type.into(),
-            image_channel_order: image_format.image_channel_order.into()
-        };
+        let image_format = cl_image_format::try_from(*image_format)?;
 
         let desc: cl_image_desc = image_desc.into();
 
@@ -93,7 +105,8 @@ impl ClImage {
         };
         
         Ok(Self {
-            value: raw
+            value: raw,
+            parent: None,
         })
     }
 
@@ -109,8 +122,84 @@ impl ClImage {
         (get_array_size, usize, cl3::memory::CL_IMAGE_ARRAY_SIZE),
         (get_num_mip_levels, u32, cl3::memory::CL_IMAGE_NUM_MIP_LEVELS),
         (get_num_samples, u32, cl3::memory::CL_IMAGE_NUM_SAMPLES),
+    );
+
+    #[cfg(feature = "CL_VERSION_1_1")]
+    cl_mem_generate_getters!(
+        (get_mem_type, MemObjectType, cl3::memory::CL_MEM_TYPE),
+        (get_flags, Vec<MemoryFlags>, cl3::memory::CL_MEM_FLAGS),
+        (get_size, usize, cl3::memory::CL_MEM_SIZE),
+        (get_host_ptr, *mut c_void, cl3::memory::CL_MEM_HOST_PTR),
+        (get_map_count, u32, cl3::memory::CL_MEM_MAP_COUNT),
         (get_reference_count, u32, cl3::memory::CL_MEM_REFERENCE_COUNT),
+        (get_context, ClContext, cl3::memory::CL_MEM_CONTEXT),
+        (get_associated_memobject, Option<ClBuffer>, cl3::memory::CL_MEM_ASSOCIATED_MEMOBJECT),
     );
+
+    /// Bytes of host memory a read or write of `region` touches, for the row and slice
+    /// pitches given to `clEnqueueReadImage`/`clEnqueueWriteImage` (0 meaning tightly packed).
+    /// Fails with `HostRegionOverflow` if that doesn't fit in a `usize`.
+    #[cfg(feature = "CL_VERSION_1_1")]
+    pub fn host_region_size(&self, region: [usize; 3], row_pitch: usize, slice_pitch: usize) -> Result<usize, ClError> {
+        if region.contains(&0) {
+            return Ok(0);
+        }
+        let overflow = || ClError::Wrapper(WrapperError::HostRegionOverflow { region, row_pitch, slice_pitch });
+        let row = region[0].checked_mul(self.get_element_size()?).ok_or_else(overflow)?;
+        let row_pitch = if row_pitch == 0 { row } else { row_pitch };
+        // A 1D array has one row per image, `slice_pitch` apart
+        let (rows, slices) = if self.get_mem_type()? == MemObjectType::Image1DArray {
+            (1, region[1])
+        } else {
+            (region[1], region[2])
+        };
+        let slice_pitch = if slice_pitch == 0 { row_pitch.checked_mul(rows).ok_or_else(overflow)? } else { slice_pitch };
+        slice_pitch
+            .checked_mul(slices - 1)
+            .and_then(|slices| row_pitch.checked_mul(rows - 1).and_then(|rows| slices.checked_add(rows)))
+            .and_then(|size| size.checked_add(row))
+            .ok_or_else(overflow)
+    }
+
+    /// Fails with `HostBufferTooSmall` if `host_len` bytes can't hold `region` tightly packed.
+    #[cfg(feature = "CL_VERSION_1_1")]
+    pub(crate) fn check_host_region(&self, region: [usize; 3], host_len: usize) -> Result<(), ClError> {
+        self.check_host_region_pitched(region, 0, 0, host_len)
+    }
+
+    /// Like `check_host_region`, for host memory laid out with `row_pitch` and `slice_pitch`.
+    #[cfg(feature = "CL_VERSION_1_1")]
+    pub(crate) fn check_host_region_pitched(&self, region: [usize; 3], row_pitch: usize, slice_pitch: usize, host_len: usize) -> Result<(), ClError> {
+        let required = self.host_region_size(region, row_pitch, slice_pitch)?;
+        if host_len < required {
+            return Err(ClError::Wrapper(WrapperError::HostBufferTooSmall { required, provided: host_len }));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "CL_VERSION_1_1")]
+impl std::fmt::Display for ClImage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "ClImage {{ type: {}, width: {}, height: {}, depth: {}, format: {:?}, size: {}, references: {} }}",
+            self.get_mem_type().map(|t| t.to_string()).unwrap_or_default(),
+            self.get_width().unwrap_or_default(),
+            self.get_height().unwrap_or_default(),
+            self.get_depth().unwrap_or_default(),
+            self.get_image_format().ok(),
+            self.get_size().unwrap_or_default(),
+            self.get_reference_count().unwrap_or_default()
+        )
+    }
+}
+
+#[cfg(feature = "CL_VERSION_1_1")]
+impl MemObject for ClImage {
+    fn mem_ptr(&self) -> *mut c_void {
+        self.value
+    }
 }
 
 #[cfg(feature = "CL_VERSION_1_1")]
@@ -131,6 +220,7 @@ impl Clone for ClImage {
 
         Self {
             value: self.value,
+            parent: self.parent.clone(),
         }
     }
 }
@@ -0,0 +1,117 @@
+use crate::cl_types::cl_image::{
+    image_channel_data_type::ClImageChannelType, image_channel_order::ClImageChannelOrder, image_formats::ClImageFormats,
+};
+
+/// # ChannelType
+///
+/// A Rust type stored in one image channel.
+///
+/// Unsigned and signed 8 and 16-bit integers are normalized (`UnormInt*`/`SnormInt*`,
+/// read in kernels with `read_imagef`); 32-bit integers are not (`read_imagei`/`read_imageui`).
+///
+/// # Safety
+///
+/// Every bit pattern of `CHANNEL_TYPE`'s size must be a valid value of the type, since typed
+/// images read channels straight from device memory.
+pub unsafe trait ChannelType: Copy + Send + Sync + 'static {
+    const CHANNEL_TYPE: ClImageChannelType;
+}
+
+macro_rules! impl_channel_type {
+    ($($ty:ty => $channel_type:ident),*) => {
+        $(
+            unsafe impl ChannelType for $ty {
+                const CHANNEL_TYPE: ClImageChannelType = ClImageChannelType::$channel_type;
+            }
+        )*
+    };
+}
+
+impl_channel_type!(
+    u8 => UnormInt8,
+    u16 => UnormInt16,
+    i8 => SnormInt8,
+    i16 => SnormInt16,
+    u32 => UnsignedInt32,
+    i32 => SignedInt32,
+    f32 => Float
+);
+
+/// # ImagePixel
+///
+/// A pixel type with a fixed image format, used by the typed images (`ClImage2D<P>`, ...).
+///
+/// `size_of::<P>()` must be `FORMAT.bytes_per_pixel()`; typed images check it when
+/// they are created.
+///
+/// # Safety
+///
+/// Typed images copy device bytes into `P`, so `P` must be plain data laid out as `FORMAT`
+/// stores a pixel: its channels in order, without padding, each valid for any bit pattern.
+pub unsafe trait ImagePixel: Copy + Send + Sync + 'static {
+    const FORMAT: ClImageFormats;
+}
+
+/// One red channel.
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct R<T>(pub T);
+
+/// Red and green channels.
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Rg<T>(pub [T; 2]);
+
+/// Red and alpha channels.
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Ra<T>(pub [T; 2]);
+
+/// Red, green, blue and alpha channels.
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Rgba<T>(pub [T; 4]);
+
+/// Blue, green, red and alpha channels, as in many window system surfaces.
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Bgra<T>(pub [T; 4]);
+
+const fn format(order: ClImageChannelOrder, channel_type: ClImageChannelType) -> ClImageFormats {
+    ClImageFormats { image_channel_order: order, image_channel_data_type: channel_type }
+}
+
+unsafe impl<T: ChannelType> ImagePixel for R<T> {
+    const FORMAT: ClImageFormats = format(ClImageChannelOrder::R, T::CHANNEL_TYPE);
+}
+
+unsafe impl<T: ChannelType> ImagePixel for Rg<T> {
+    const FORMAT: ClImageFormats = format(ClImageChannelOrder::RG, T::CHANNEL_TYPE);
+}
+
+unsafe impl<T: ChannelType> ImagePixel for Ra<T> {
+    const FORMAT: ClImageFormats = format(ClImageChannelOrder::RA, T::CHANNEL_TYPE);
+}
+
+unsafe impl<T: ChannelType> ImagePixel for Rgba<T> {
+    const FORMAT: ClImageFormats = format(ClImageChannelOrder::RGBA, T::CHANNEL_TYPE);
+}
+
+unsafe impl<T: ChannelType> ImagePixel for Bgra<T> {
+    const FORMAT: ClImageFormats = format(ClImageChannelOrder::BGRA, T::CHANNEL_TYPE);
+}
+
+#[cfg(feature = "image")]
+unsafe impl<T: ChannelType> ImagePixel for image::Luma<T> {
+    const FORMAT: ClImageFormats = format(ClImageChannelOrder::R, T::CHANNEL_TYPE);
+}
+
+#[cfg(feature = "image")]
+unsafe impl<T: ChannelType> ImagePixel for image::LumaA<T> {
+    const FORMAT: ClImageFormats = format(ClImageChannelOrder::RA, T::CHANNEL_TYPE);
+}
+
+#[cfg(feature = "image")]
+unsafe impl<T: ChannelType> ImagePixel for image::Rgba<T> {
+    const FORMAT: ClImageFormats = format(ClImageChannelOrder::RGBA, T::CHANNEL_TYPE);
+}
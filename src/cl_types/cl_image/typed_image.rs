@@ -0,0 +1,372 @@
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::os::raw::c_void;
+
+use crate::{
+    cl_types::{
+        cl_command_queue::ClCommandQueue,
+        cl_context::ClContext,
+        cl_event::ClEvent,
+        cl_image::{ClImage, image_desc::ClImageDesc, image_type::ClImageType, pixel::ImagePixel},
+        mem_object::MemObject,
+        mem_object_type::MemObjectType,
+        memory_flags::MemoryFlags,
+    },
+    error::{ClError, wrapper_error::WrapperError},
+};
+
+/// State shared by the typed images: the image, its size in pixels (`[width, height, depth or layers]`)
+/// and the queue transfers go through.
+struct TypedImage<P> {
+    image: ClImage,
+    size: [usize; 3],
+    queue: ClCommandQueue,
+    _marker: PhantomData<P>,
+}
+
+impl<P: ImagePixel> TypedImage<P> {
+    fn check_pixel() -> Result<(), ClError> {
+        if P::FORMAT.bytes_per_pixel() != Some(std::mem::size_of::<P>()) {
+            return Err(ClError::Wrapper(WrapperError::UnsupportedPixelLayout {
+                channel_order: P::FORMAT.image_channel_order,
+                channel_type: P::FORMAT.image_channel_data_type,
+            }));
+        }
+        Ok(())
+    }
+
+    fn create(
+        context: &ClContext,
+        queue: &ClCommandQueue,
+        flags: &[MemoryFlags],
+        desc: ClImageDesc,
+        size: [usize; 3],
+        pixels: Option<&[P]>,
+    ) -> Result<Self, ClError> {
+        Self::check_pixel()?;
+        let mut flags = flags.to_vec();
+        let host_ptr = match pixels {
+            Some(pixels) => {
+                let expected = size.iter().product();
+                if pixels.len() != expected {
+                    return Err(ClError::Wrapper(WrapperError::BufferLengthMismatch { expected, actual: pixels.len() }));
+                }
+                if !flags.contains(&MemoryFlags::CopyHostPtr) {
+                    flags.push(MemoryFlags::CopyHostPtr);
+                }
+                pixels.as_ptr() as *mut c_void
+            }
+            None => std::ptr::null_mut(),
+        };
+        let image = ClImage::new(context, &flags, &P::FORMAT, &desc, host_ptr)?;
+        Ok(Self { image, size, queue: queue.clone(), _marker: PhantomData })
+    }
+
+    fn wrap(image: ClImage, queue: &ClCommandQueue, expected: MemObjectType) -> Result<Self, ClError> {
+        Self::check_pixel()?;
+        let actual = image.get_mem_type()?;
+        if actual != expected {
+            return Err(ClError::Wrapper(WrapperError::ImageTypeMismatch { expected, actual }));
+        }
+        let format = image.get_image_format()?;
+        if format != P::FORMAT {
+            return Err(ClError::Wrapper(WrapperError::IncompatibleImageFormat { image: format, requested: P::FORMAT }));
+        }
+        let size = match expected {
+            MemObjectType::Image2D => [image.get_width()?, image.get_height()?, 1],
+            MemObjectType::Image2DArray => [image.get_width()?, image.get_height()?, image.get_array_size()?],
+            _ => [image.get_width()?, image.get_height()?, image.get_depth()?],
+        };
+        Ok(Self { image, size, queue: queue.clone(), _marker: PhantomData })
+    }
+
+    fn check_region(&self, origin: [usize; 3], region: [usize; 3], host_len: usize) -> Result<(), ClError> {
+        let in_bounds = (0..3).all(|i| origin[i].checked_add(region[i]).is_some_and(|end| end <= self.size[i]));
+        if !in_bounds {
+            return Err(ClError::Wrapper(WrapperError::ImageRegionOutOfBounds { origin, region, size: self.size }));
+        }
+        let expected = region.iter().product();
+        if host_len != expected {
+            return Err(ClError::Wrapper(WrapperError::BufferLengthMismatch { expected, actual: host_len }));
+        }
+        Ok(())
+    }
+
+    async fn read(&self, origin: [usize; 3], region: [usize; 3], host_memory: &mut [P]) -> Result<ClEvent, ClError> {
+        self.check_region(origin, region, host_memory.len())?;
+        self.queue
+            .read_image_raw(&self.image, origin, region, 0, 0, host_memory.as_mut_ptr() as *mut c_void, None)
+            .await
+    }
+
+    async fn write(&self, origin: [usize; 3], region: [usize; 3], pixels: &[P]) -> Result<ClEvent, ClError> {
+        self.check_region(origin, region, pixels.len())?;
+        self.queue
+            .write_image_raw(&self.image, origin, region, 0, 0, pixels.as_ptr() as *mut c_void, None)
+            .await
+    }
+
+    async fn to_vec(&self) -> Result<Vec<P>, ClError> {
+        let len = self.size.iter().product();
+        let mut host: Vec<P> = Vec::with_capacity(len);
+        self.queue
+            .read_image_raw(&self.image, [0, 0, 0], self.size, 0, 0, host.as_mut_ptr() as *mut c_void, None)
+            .await?;
+        unsafe {
+            host.set_len(len);
+        }
+        Ok(host)
+    }
+}
+
+impl<P> Clone for TypedImage<P> {
+    fn clone(&self) -> Self {
+        Self { image: self.image.clone(), size: self.size, queue: self.queue.clone(), _marker: PhantomData }
+    }
+}
+
+/// # ClImage2D
+///
+/// A 2D image whose pixels are `P` (e.g. `ClImage2D<Rgba<u8>>`).
+///
+/// The format comes from the pixel type, and transfers take `[x, y]` coordinates and
+/// slices of `P` whose length must match the region. It dereferences to `ClImage`, so it
+/// can be bound with `arg_image`. Like `TypedBuffer`, it keeps a handle to a command queue.
+///
+/// # Example
+/// ```ignore
+/// let image = executor.create_image_2d::<Rgba<u8>>(&[MemoryFlags::ReadWrite], 640, 480)?;
+/// image.write([0, 0], [640, 480], &pixels).await?;
+/// let mut tile = vec![Rgba([0u8; 4]); 16 * 16];
+/// image.read([32, 32], [16, 16], &mut tile).await?;
+/// ```
+pub struct ClImage2D<P> {
+    inner: TypedImage<P>,
+}
+
+impl<P: ImagePixel> ClImage2D<P> {
+    /// Creates an uninitialized `width` x `height` image.
+    pub fn new(context: &ClContext, queue: &ClCommandQueue, flags: &[MemoryFlags], width: usize, height: usize) -> Result<Self, ClError> {
+        let inner = TypedImage::create(context, queue, flags, Self::desc(width, height), [width, height, 1], None)?;
+        Ok(Self { inner })
+    }
+
+    /// Creates an image holding a copy of `pixels`, row after row.
+    pub fn from_pixels(
+        context: &ClContext,
+        queue: &ClCommandQueue,
+        flags: &[MemoryFlags],
+        width: usize,
+        height: usize,
+        pixels: &[P],
+    ) -> Result<Self, ClError> {
+        let inner = TypedImage::create(context, queue, flags, Self::desc(width, height), [width, height, 1], Some(pixels))?;
+        Ok(Self { inner })
+    }
+
+    /// Wraps an existing image, which must be 2D and have `P`'s format.
+    pub fn from_image(image: ClImage, queue: &ClCommandQueue) -> Result<Self, ClError> {
+        Ok(Self { inner: TypedImage::wrap(image, queue, MemObjectType::Image2D)? })
+    }
+
+    fn desc(width: usize, height: usize) -> ClImageDesc {
+        ClImageDesc { image_type: ClImageType::Image2D, image_width: Some(width), image_height: Some(height), ..Default::default() }
+    }
+
+    pub fn width(&self) -> usize {
+        self.inner.size[0]
+    }
+
+    pub fn height(&self) -> usize {
+        self.inner.size[1]
+    }
+
+    /// Reads the `size` pixels at `origin` into `host_memory`.
+    pub async fn read(&self, origin: [usize; 2], size: [usize; 2], host_memory: &mut [P]) -> Result<ClEvent, ClError> {
+        self.inner.read([origin[0], origin[1], 0], [size[0], size[1], 1], host_memory).await
+    }
+
+    /// Writes `pixels` to the `size` pixels at `origin`.
+    pub async fn write(&self, origin: [usize; 2], size: [usize; 2], pixels: &[P]) -> Result<ClEvent, ClError> {
+        self.inner.write([origin[0], origin[1], 0], [size[0], size[1], 1], pixels).await
+    }
+
+    /// Copies the whole image to a new `Vec`, row after row.
+    pub async fn to_vec(&self) -> Result<Vec<P>, ClError> {
+        self.inner.to_vec().await
+    }
+
+    /// Drops the pixel type and returns the untyped image.
+    pub fn into_image(self) -> ClImage {
+        self.inner.image
+    }
+}
+
+/// # ClImage3D
+///
+/// A 3D image whose pixels are `P`. Transfers take `[x, y, z]` coordinates.
+pub struct ClImage3D<P> {
+    inner: TypedImage<P>,
+}
+
+impl<P: ImagePixel> ClImage3D<P> {
+    /// Creates an uninitialized `width` x `height` x `depth` image.
+    pub fn new(context: &ClContext, queue: &ClCommandQueue, flags: &[MemoryFlags], size: [usize; 3]) -> Result<Self, ClError> {
+        Ok(Self { inner: TypedImage::create(context, queue, flags, Self::desc(size), size, None)? })
+    }
+
+    /// Creates an image holding a copy of `pixels`, slice after slice.
+    pub fn from_pixels(context: &ClContext, queue: &ClCommandQueue, flags: &[MemoryFlags], size: [usize; 3], pixels: &[P]) -> Result<Self, ClError> {
+        Ok(Self { inner: TypedImage::create(context, queue, flags, Self::desc(size), size, Some(pixels))? })
+    }
+
+    /// Wraps an existing image, which must be 3D and have `P`'s format.
+    pub fn from_image(image: ClImage, queue: &ClCommandQueue) -> Result<Self, ClError> {
+        Ok(Self { inner: TypedImage::wrap(image, queue, MemObjectType::Image3D)? })
+    }
+
+    fn desc(size: [usize; 3]) -> ClImageDesc {
+        ClImageDesc {
+            image_type: ClImageType::Image3D,
+            image_width: Some(size[0]),
+            image_height: Some(size[1]),
+            image_depth: Some(size[2]),
+            ..Default::default()
+        }
+    }
+
+    /// `[width, height, depth]`.
+    pub fn size(&self) -> [usize; 3] {
+        self.inner.size
+    }
+
+    pub async fn read(&self, origin: [usize; 3], size: [usize; 3], host_memory: &mut [P]) -> Result<ClEvent, ClError> {
+        self.inner.read(origin, size, host_memory).await
+    }
+
+    pub async fn write(&self, origin: [usize; 3], size: [usize; 3], pixels: &[P]) -> Result<ClEvent, ClError> {
+        self.inner.write(origin, size, pixels).await
+    }
+
+    pub async fn to_vec(&self) -> Result<Vec<P>, ClError> {
+        self.inner.to_vec().await
+    }
+
+    pub fn into_image(self) -> ClImage {
+        self.inner.image
+    }
+}
+
+/// # ClImage2DArray
+///
+/// An array of 2D images (layers) whose pixels are `P`. Transfers take `[x, y, layer]`
+/// coordinates; kernels index layers with the third coordinate.
+pub struct ClImage2DArray<P> {
+    inner: TypedImage<P>,
+}
+
+impl<P: ImagePixel> ClImage2DArray<P> {
+    /// Creates `layers` uninitialized `width` x `height` layers.
+    pub fn new(context: &ClContext, queue: &ClCommandQueue, flags: &[MemoryFlags], width: usize, height: usize, layers: usize) -> Result<Self, ClError> {
+        let size = [width, height, layers];
+        Ok(Self { inner: TypedImage::create(context, queue, flags, Self::desc(size), size, None)? })
+    }
+
+    /// Creates layers holding a copy of `pixels`, layer after layer.
+    pub fn from_pixels(
+        context: &ClContext,
+        queue: &ClCommandQueue,
+        flags: &[MemoryFlags],
+        width: usize,
+        height: usize,
+        layers: usize,
+        pixels: &[P],
+    ) -> Result<Self, ClError> {
+        let size = [width, height, layers];
+        Ok(Self { inner: TypedImage::create(context, queue, flags, Self::desc(size), size, Some(pixels))? })
+    }
+
+    /// Wraps an existing image, which must be a 2D array and have `P`'s format.
+    pub fn from_image(image: ClImage, queue: &ClCommandQueue) -> Result<Self, ClError> {
+        Ok(Self { inner: TypedImage::wrap(image, queue, MemObjectType::Image2DArray)? })
+    }
+
+    fn desc(size: [usize; 3]) -> ClImageDesc {
+        ClImageDesc {
+            image_type: ClImageType::Image2DArray,
+            image_width: Some(size[0]),
+            image_height: Some(size[1]),
+            image_array_size: Some(size[2]),
+            ..Default::default()
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.inner.size[0]
+    }
+
+    pub fn height(&self) -> usize {
+        self.inner.size[1]
+    }
+
+    pub fn layers(&self) -> usize {
+        self.inner.size[2]
+    }
+
+    pub async fn read(&self, origin: [usize; 3], size: [usize; 3], host_memory: &mut [P]) -> Result<ClEvent, ClError> {
+        self.inner.read(origin, size, host_memory).await
+    }
+
+    pub async fn write(&self, origin: [usize; 3], size: [usize; 3], pixels: &[P]) -> Result<ClEvent, ClError> {
+        self.inner.write(origin, size, pixels).await
+    }
+
+    /// Reads one whole layer.
+    pub async fn read_layer(&self, layer: usize, host_memory: &mut [P]) -> Result<ClEvent, ClError> {
+        self.inner.read([0, 0, layer], [self.width(), self.height(), 1], host_memory).await
+    }
+
+    /// Overwrites one whole layer.
+    pub async fn write_layer(&self, layer: usize, pixels: &[P]) -> Result<ClEvent, ClError> {
+        self.inner.write([0, 0, layer], [self.width(), self.height(), 1], pixels).await
+    }
+
+    pub async fn to_vec(&self) -> Result<Vec<P>, ClError> {
+        self.inner.to_vec().await
+    }
+
+    pub fn into_image(self) -> ClImage {
+        self.inner.image
+    }
+}
+
+macro_rules! impl_typed_image_traits {
+    ($($name:ident),*) => {
+        $(
+            impl<P> Deref for $name<P> {
+                type Target = ClImage;
+
+                fn deref(&self) -> &ClImage {
+                    &self.inner.image
+                }
+            }
+
+            impl<P> MemObject for $name<P> {
+                fn mem_ptr(&self) -> *mut c_void {
+                    self.inner.image.as_ptr()
+                }
+            }
+
+            impl<P> Clone for $name<P> {
+                fn clone(&self) -> Self {
+                    Self { inner: self.inner.clone() }
+                }
+            }
+
+            unsafe impl<P: Send> Send for $name<P> {}
+            unsafe impl<P: Sync> Sync for $name<P> {}
+        )*
+    };
+}
+
+impl_typed_image_traits!(ClImage2D, ClImage3D, ClImage2DArray);
@@ -69,8 +69,8 @@ impl ClKernel {
         unsafe {
             cl3::kernel::set_kernel_arg(
                 self.value, index, byte_size, value
-            );
-        };
+            )
+        }.map_err(|code| ClError::Api(ApiError::get_error(code)))?;
 
         Ok(())
     }
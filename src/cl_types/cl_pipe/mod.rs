@@ -1,7 +1,9 @@
 use std::os::raw::c_void;
 use crate::{
-    cl_types::{cl_context::ClContext, memory_flags::MemoryFlags, releaseable::Releaseable},
+    cl_types::{cl_context::ClContext, memory_flags::MemoryFlags, mem_object::MemObject, mem_object_type::MemObjectType, releaseable::Releaseable},
     error::{ClError, api_error::ApiError},
+    cl_mem_generate_getters,
+    cl_pipe_generate_getters,
 };
 
 /// # ClPipe
@@ -43,6 +45,33 @@ impl ClPipe {
     pub fn as_ptr(&self) -> *mut c_void {
         self.value
     }
+
+    cl_mem_generate_getters!(
+        (get_mem_type, MemObjectType, cl3::memory::CL_MEM_TYPE),
+        (get_flags, Vec<MemoryFlags>, cl3::memory::CL_MEM_FLAGS),
+        (get_size, usize, cl3::memory::CL_MEM_SIZE),
+        (get_reference_count, u32, cl3::memory::CL_MEM_REFERENCE_COUNT),
+        (get_context, ClContext, cl3::memory::CL_MEM_CONTEXT),
+    );
+
+    cl_pipe_generate_getters!(
+        (get_packet_size, u32, cl3::memory::CL_PIPE_PACKET_SIZE),
+        (get_max_packets, u32, cl3::memory::CL_PIPE_MAX_PACKETS),
+    );
+}
+
+#[cfg(feature = "CL_VERSION_2_0")]
+impl std::fmt::Display for ClPipe {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "ClPipe {{ packet size: {}, max packets: {}, size: {}, references: {} }}",
+            self.get_packet_size().unwrap_or_default(),
+            self.get_max_packets().unwrap_or_default(),
+            self.get_size().unwrap_or_default(),
+            self.get_reference_count().unwrap_or_default()
+        )
+    }
 }
 
 #[cfg(feature = "CL_VERSION_2_0")]
@@ -54,6 +83,13 @@ impl Releaseable for ClPipe {
     }
 }
 
+#[cfg(feature = "CL_VERSION_2_0")]
+impl MemObject for ClPipe {
+    fn mem_ptr(&self) -> *mut c_void {
+        self.value
+    }
+}
+
 #[cfg(feature = "CL_VERSION_2_0")]
 impl Drop for ClPipe {
     fn drop(&mut self) {
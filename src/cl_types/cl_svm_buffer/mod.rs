@@ -1,8 +1,9 @@
 use std::{marker::PhantomData, os::raw::c_void, ptr::null};
 
-use cl3::{command_queue::enqueue_svm_mem_cpy, context};
+use crate::{async_executor::memory_tracker::Reservation, cl_types::{cl_buffer::typed_buffer::elements_size, memory_flags::MemoryFlags, cl_command_queue::ClCommandQueue, cl_context::ClContext, cl_svm_buffer::{svm_element::SvmElement, svm_mode::SvmMode}}, error::{ClError, api_error::ApiError, wrapper_error::WrapperError}};
 
-use crate::{cl_types::{memory_flags::MemoryFlags, cl_command_queue::ClCommandQueue, cl_context::ClContext}, error::{ClError, api_error::ApiError}};
+pub mod svm_element;
+pub mod svm_mode;
 
 /// # ClSvmBuffer
 /// 
@@ -11,30 +12,121 @@ use crate::{cl_types::{memory_flags::MemoryFlags, cl_command_queue::ClCommandQue
 /// Unlike normal Buffers, SVM allows both processors 
 /// to access the same memory address, eliminating the need to 
 /// manually copy data in many cases.
+///
+/// Coarse-grain buffers (the default) are accessed from the host through `map_mut`.
+/// Fine-grain buffers (see `SvmMode`) can also be used directly as slices.
 #[cfg(feature = "CL_VERSION_2_0")]
 pub struct ClSvmBuffer<T> {
     memory: *mut c_void,
     pub len: usize,
     context: ClContext,
+    mode: SvmMode,
+    /// Bytes counted by the executor's `MemoryTracker` until the memory is freed.
+    reservation: Option<Reservation>,
     phantom: PhantomData<*mut T>,
 }
 
 #[cfg(feature = "CL_VERSION_2_0")]
 impl<T> ClSvmBuffer<T> {
-    /// Allocates SVM memory in the context.
+    /// Allocates coarse-grain SVM memory in the context.
     pub fn new(context: &ClContext, flags: &Vec<MemoryFlags>, item_amount: usize, alignment: u32) -> Result<Self, ClError> {
+        Self::alloc(context, flags, item_amount, alignment, SvmMode::Coarse)
+    }
+
+    /// Allocates SVM memory shared as `mode` describes.
+    ///
+    /// Fails with `SvmModeUnsupported` if some device of the context doesn't report the
+    /// needed `SvmCapabilities`, instead of letting the driver reject the allocation.
+    pub fn new_with_mode(
+        context: &ClContext,
+        flags: &Vec<MemoryFlags>,
+        item_amount: usize,
+        alignment: u32,
+        mode: SvmMode,
+    ) -> Result<Self, ClError> {
+        for device in context.get_devices()? {
+            if !mode.is_supported_by(&device.get_svm_capabilities()?) {
+                return Err(ClError::Wrapper(WrapperError::SvmModeUnsupported { mode, device: device.get_name()? }));
+            }
+        }
+        Self::alloc(context, flags, item_amount, alignment, mode)
+    }
+
+    fn alloc(context: &ClContext, flags: &Vec<MemoryFlags>, item_amount: usize, alignment: u32, mode: SvmMode) -> Result<Self, ClError> {
+        let size = elements_size::<T>(item_amount)?;
         let raw_ptr = unsafe {
-            cl3::memory::svm_alloc(context.as_ptr(), MemoryFlags::to_u64(&flags), item_amount * size_of::<T>(), alignment)
+            cl3::memory::svm_alloc(context.as_ptr(), MemoryFlags::to_u64(&flags) | mode.to_u64(), size, alignment)
         }.map_err(|code| ClError::Api(ApiError::get_error(code)))?;
 
         Ok(Self {
             memory: raw_ptr,
             len: item_amount,
             context: context.clone(),
+            mode,
+            reservation: None,
             phantom: PhantomData
         })
     }
 
+    pub fn mode(&self) -> SvmMode {
+        self.mode
+    }
+
+    /// Keeps `reservation` until the memory is freed.
+    pub(crate) fn with_reservation(mut self, reservation: Reservation) -> Self {
+        self.reservation = Some(reservation.hold());
+        self
+    }
+
+    /// Takes the reservation out, e.g. to give it back only once a queued free has run.
+    pub(crate) fn take_reservation(&mut self) -> Option<Reservation> {
+        self.reservation.take()
+    }
+
+    /// The context the memory was allocated in.
+    pub(crate) fn context(&self) -> &ClContext {
+        &self.context
+    }
+
+    /// The elements of a fine-grain buffer, read directly without mapping.
+    ///
+    /// Device writes are visible once the command that made them has completed
+    /// (e.g. after `run().await`).
+    /// Coarse-grain buffers fail with `SvmMapRequired`; use `map_mut` for them.
+    ///
+    /// # Safety
+    ///
+    /// Tasks only borrow the buffer immutably (`arg_svm`), so nothing stops a kernel from
+    /// writing it while the slice is alive. No command that writes the buffer may run until
+    /// the slice is dropped, unless the elements are atomics of a `FineGrainAtomics`
+    /// buffer (e.g. `AtomicU32`) and the kernel only accesses them atomically.
+    pub unsafe fn as_slice(&self) -> Result<&[T], ClError>
+    where
+        T: SvmElement,
+    {
+        self.check_fine_grain()?;
+        Ok(unsafe { std::slice::from_raw_parts(self.memory as *const T, self.len) })
+    }
+
+    /// The elements of a fine-grain buffer, written directly without mapping.
+    ///
+    /// Host writes are visible to commands enqueued after them. Requires `&mut self`,
+    /// so no task using the buffer can be running at the same time.
+    pub fn as_mut_slice(&mut self) -> Result<&mut [T], ClError>
+    where
+        T: SvmElement,
+    {
+        self.check_fine_grain()?;
+        Ok(unsafe { std::slice::from_raw_parts_mut(self.memory as *mut T, self.len) })
+    }
+
+    fn check_fine_grain(&self) -> Result<(), ClError> {
+        if !self.mode.is_fine_grain() {
+            return Err(ClError::Wrapper(WrapperError::SvmMapRequired));
+        }
+        Ok(())
+    }
+
     /// Provides direct access from the CPU to the GPU memory.
     /// 
     /// Returns an `SvmMapGuard`, which you can treat as a Rust slice (`&[T]` or `&mut [T]`).
@@ -68,6 +160,29 @@ impl<T> ClSvmBuffer<T> {
     pub fn as_ptr(&self) -> *mut c_void { // Do not use to get access to the array.
         self.memory.clone()
     }
+
+    /// Gives up ownership of the allocation without freeing it, e.g. to free it in order
+    /// with the commands still using it (`ClCommandQueue::svm_free`).
+    pub fn into_raw(self) -> *mut c_void {
+        let this = std::mem::ManuallyDrop::new(self);
+        // Only the allocation is kept; the context handle and reservation are still released
+        drop(unsafe { std::ptr::read(&this.context) });
+        drop(unsafe { std::ptr::read(&this.reservation) });
+        this.memory
+    }
+
+    /// Fails with `BufferRangeOutOfBounds` unless elements `offset..offset + len` exist.
+    pub(crate) fn check_range(&self, offset: usize, len: usize) -> Result<(), ClError> {
+        if offset.checked_add(len).is_none_or(|end| end > self.len) {
+            return Err(ClError::Wrapper(WrapperError::BufferRangeOutOfBounds { offset, len, buffer_len: self.len }));
+        }
+        Ok(())
+    }
+
+    /// Pointer to element `offset`, for transfers into part of the buffer.
+    pub(crate) fn element_ptr(&self, offset: usize) -> *mut c_void {
+        unsafe { (self.memory as *mut T).add(offset) as *mut c_void }
+    }
 }
 
 #[cfg(feature = "CL_VERSION_2_0")]
@@ -0,0 +1,23 @@
+use std::sync::atomic::{AtomicI32, AtomicI64, AtomicU32, AtomicU64};
+
+/// # SvmElement
+///
+/// A type whose values fine-grain SVM buffers hand out directly (`as_slice`/`as_mut_slice`).
+///
+/// # Safety
+///
+/// Every bit pattern of `size_of::<Self>()` bytes must be a valid value of the type, since
+/// the elements are whatever the devices wrote to the memory.
+pub unsafe trait SvmElement {}
+
+macro_rules! impl_svm_element {
+    ($($ty:ty),*) => {
+        $(
+            unsafe impl SvmElement for $ty {}
+        )*
+    };
+}
+
+impl_svm_element!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64, AtomicU32, AtomicI32, AtomicU64, AtomicI64);
+
+unsafe impl<T: SvmElement, const N: usize> SvmElement for [T; N] {}
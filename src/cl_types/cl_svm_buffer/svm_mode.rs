@@ -0,0 +1,47 @@
+use crate::cl_types::cl_device::svm_capabilities::SvmCapabilities;
+
+/// # SvmMode
+///
+/// How an SVM allocation is shared between the host and the devices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SvmMode {
+    /// The host must map the buffer (`map_mut`) to access it, and sees the devices'
+    /// writes only through a map. Supported by every OpenCL 2.0 device.
+    #[default]
+    Coarse,
+    /// The host reads and writes the buffer directly (`as_slice`/`as_mut_slice`).
+    /// Writes become visible to the other side at synchronization points: when a
+    /// command using the buffer is enqueued and when it completes.
+    FineGrain,
+    /// Like `FineGrain`, and atomic operations (with `memory_scope_all_svm_devices` in
+    /// kernels, `std::sync::atomic` types on the host) are visible to the other side
+    /// while a kernel is running.
+    FineGrainAtomics,
+}
+
+impl SvmMode {
+    /// The `cl_svm_mem_flags` bits of the mode.
+    pub fn to_u64(&self) -> u64 {
+        match self {
+            Self::Coarse => 0,
+            Self::FineGrain => cl3::memory::CL_MEM_SVM_FINE_GRAIN_BUFFER,
+            Self::FineGrainAtomics => cl3::memory::CL_MEM_SVM_FINE_GRAIN_BUFFER | cl3::memory::CL_MEM_SVM_ATOMICS,
+        }
+    }
+
+    /// Whether a device with `capabilities` can allocate buffers of this mode.
+    /// Fine-grain system SVM covers fine-grain buffers.
+    pub fn is_supported_by(&self, capabilities: &SvmCapabilities) -> bool {
+        let fine_grain = capabilities.fine_grain_buffer() || capabilities.fine_grain_system();
+        match self {
+            Self::Coarse => capabilities.coarse_grain_buffer(),
+            Self::FineGrain => fine_grain,
+            Self::FineGrainAtomics => fine_grain && capabilities.atomics(),
+        }
+    }
+
+    /// Whether the host can access the buffer without mapping it.
+    pub fn is_fine_grain(&self) -> bool {
+        !matches!(self, Self::Coarse)
+    }
+}
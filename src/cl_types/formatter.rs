@@ -265,3 +265,54 @@ impl Formatter for ClImageFormats {
         })
     }
 }
+
+/// Flattens the typed result of a `clGet*Info` query back into the bytes `Formatter` expects.
+pub fn info_type_to_bytes(info: cl3::info_type::InfoType) -> Option<Vec<u8>> {
+    use cl3::info_type::InfoType;
+    match info {
+        InfoType::Uint(v) => Some(v.to_le_bytes().to_vec()),
+        InfoType::Ulong(v) => Some(v.to_le_bytes().to_vec()),
+        InfoType::Size(v) => Some(v.to_le_bytes().to_vec()),
+        InfoType::Ptr(v) => Some(v.to_le_bytes().to_vec()),
+        InfoType::VecUchar(v) => Some(v),
+        InfoType::VecImageFormat(v) => v.first().map(|format| {
+            let mut bytes = format.image_channel_order.to_le_bytes().to_vec();
+            bytes.extend_from_slice(&format.image_channel_data_type.to_le_bytes());
+            bytes
+        }),
+        _ => None,
+    }
+}
+
+impl Formatter for *mut c_void {
+    fn from_buffer(buffer: &[u8]) -> Option<Self> {
+        usize::from_buffer(buffer).map(|address| address as *mut c_void)
+    }
+}
+
+use crate::cl_types::{cl_buffer::ClBuffer, mem_object_type::MemObjectType, memory_flags::MemoryFlags};
+
+impl Formatter for MemObjectType {
+    fn from_buffer(buffer: &[u8]) -> Option<Self> {
+        u32::from_buffer(buffer).map(MemObjectType::from)
+    }
+}
+
+impl Formatter for Vec<MemoryFlags> {
+    fn from_buffer(buffer: &[u8]) -> Option<Self> {
+        u64::from_buffer(buffer).map(MemoryFlags::from_u64)
+    }
+}
+
+/// `None` for a null handle (e.g. a buffer that isn't a sub-buffer).
+impl Formatter for Option<ClBuffer> {
+    fn from_buffer(buffer: &[u8]) -> Option<Self> {
+        let ptr = <*mut c_void>::from_buffer(buffer)?;
+        if ptr.is_null() {
+            return Some(None);
+        }
+        let buffer = ClBuffer::from_ptr(ptr);
+        unsafe { buffer.increase_reference_count() };
+        Some(Some(buffer))
+    }
+}
@@ -231,22 +231,61 @@ macro_rules! cl_image_generate_getters {
             where
                 $type_of: $crate::cl_types::formatter::Formatter,
             {
-                let buffer = cl3::memory::get_mem_object_info(self.value, $value_id)
-                    .map_err(|code| crate::error::ClError::Api(crate::error::api_error::ApiError::get_error(code)))?;
+                let info = cl3::memory::get_image_info(self.value, $value_id)
+                    .map_err(|code| $crate::error::ClError::Api($crate::error::api_error::ApiError::get_error(code)))?;
 
-                match buffer {
-                    cl3::info_type::InfoType::VecUchar(v) => {
-                        <$type_of as $crate::cl_types::formatter::Formatter>::from_buffer(&v)
-                            .ok_or_else(|| crate::error::ClError::Wrapper(crate::error::wrapper_error::WrapperError::FormatterFailed))
-                    },
-                    cl3::info_type::InfoType::Size(v) => {
-                        <$type_of as $crate::cl_types::formatter::Formatter>::from_buffer(&v.to_le_bytes())
-                            .ok_or_else(|| crate::error::ClError::Wrapper(crate::error::wrapper_error::WrapperError::FormatterFailed))
-                    },
-                    _ => {
-                        Err(crate::error::ClError::Wrapper(crate::error::wrapper_error::WrapperError::FormatterFailed))
-                    }
-                }
+                $crate::cl_types::formatter::info_type_to_bytes(info)
+                    .and_then(|buffer| <$type_of as $crate::cl_types::formatter::Formatter>::from_buffer(&buffer))
+                    .ok_or_else(|| $crate::error::ClError::Wrapper($crate::error::wrapper_error::WrapperError::FormatterFailed))
+            }
+        )*
+    };
+}
+
+/// Getters shared by every memory object (`ClBuffer`, `ClImage`, `ClPipe`).
+#[macro_export]
+macro_rules! cl_mem_generate_getters {
+    (
+        $(
+            ($name:ident, $type_of:ty, $value_id:expr)
+        ),* $(,)?
+    ) => {
+        $(
+            pub fn $name(&self)
+                -> Result<$type_of, $crate::error::ClError>
+            where
+                $type_of: $crate::cl_types::formatter::Formatter,
+            {
+                let info = cl3::memory::get_mem_object_info(self.value, $value_id)
+                    .map_err(|code| $crate::error::ClError::Api($crate::error::api_error::ApiError::get_error(code)))?;
+
+                $crate::cl_types::formatter::info_type_to_bytes(info)
+                    .and_then(|buffer| <$type_of as $crate::cl_types::formatter::Formatter>::from_buffer(&buffer))
+                    .ok_or_else(|| $crate::error::ClError::Wrapper($crate::error::wrapper_error::WrapperError::FormatterFailed))
+            }
+        )*
+    };
+}
+
+#[macro_export]
+macro_rules! cl_pipe_generate_getters {
+    (
+        $(
+            ($name:ident, $type_of:ty, $value_id:expr)
+        ),* $(,)?
+    ) => {
+        $(
+            pub fn $name(&self)
+                -> Result<$type_of, $crate::error::ClError>
+            where
+                $type_of: $crate::cl_types::formatter::Formatter,
+            {
+                let info = cl3::memory::get_pipe_info(self.value, $value_id)
+                    .map_err(|code| $crate::error::ClError::Api($crate::error::api_error::ApiError::get_error(code)))?;
+
+                $crate::cl_types::formatter::info_type_to_bytes(info)
+                    .and_then(|buffer| <$type_of as $crate::cl_types::formatter::Formatter>::from_buffer(&buffer))
+                    .ok_or_else(|| $crate::error::ClError::Wrapper($crate::error::wrapper_error::WrapperError::FormatterFailed))
             }
         )*
     };
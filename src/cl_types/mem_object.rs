@@ -0,0 +1,35 @@
+use std::os::raw::c_void;
+
+/// # MemObject
+///
+/// Anything backed by a `cl_mem` handle: buffers, images and pipes.
+///
+/// Lets operations that accept several kinds of memory objects at once, like
+/// `AsyncExecutor::migrate_mem_objects`, take a `&[&dyn MemObject]`.
+pub trait MemObject {
+    fn mem_ptr(&self) -> *mut c_void;
+}
+
+/// # MigrationFlags
+///
+/// Options of `clEnqueueMigrateMemObjects`. No flag migrates the contents to the target device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationFlags {
+    /// Migrate to the host instead of the queue's device.
+    ToHost,
+    /// Only move the allocation; the contents become undefined. Use it for
+    /// objects that are about to be overwritten entirely.
+    ContentUndefined,
+}
+
+impl MigrationFlags {
+    pub fn to_u64(flags: &[MigrationFlags]) -> u64 {
+        flags.iter().fold(0, |carry, flag| {
+            carry
+                | match flag {
+                    Self::ToHost => cl3::memory::CL_MIGRATE_MEM_OBJECT_HOST,
+                    Self::ContentUndefined => cl3::memory::CL_MIGRATE_MEM_OBJECT_CONTENT_UNDEFINED,
+                }
+        })
+    }
+}
@@ -0,0 +1,43 @@
+use cl3::memory::{
+    CL_MEM_OBJECT_BUFFER, CL_MEM_OBJECT_IMAGE1D, CL_MEM_OBJECT_IMAGE1D_ARRAY, CL_MEM_OBJECT_IMAGE1D_BUFFER,
+    CL_MEM_OBJECT_IMAGE2D, CL_MEM_OBJECT_IMAGE2D_ARRAY, CL_MEM_OBJECT_IMAGE3D, CL_MEM_OBJECT_PIPE,
+};
+
+/// Kind of a memory object, as reported by `CL_MEM_TYPE`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemObjectType {
+    Buffer,
+    Image1D,
+    Image1DArray,
+    Image1DBuffer,
+    Image2D,
+    Image2DArray,
+    Image3D,
+    Pipe,
+    Unknown(u32),
+}
+
+impl From<u32> for MemObjectType {
+    fn from(value: u32) -> Self {
+        match value {
+            CL_MEM_OBJECT_BUFFER => Self::Buffer,
+            CL_MEM_OBJECT_IMAGE1D => Self::Image1D,
+            CL_MEM_OBJECT_IMAGE1D_ARRAY => Self::Image1DArray,
+            CL_MEM_OBJECT_IMAGE1D_BUFFER => Self::Image1DBuffer,
+            CL_MEM_OBJECT_IMAGE2D => Self::Image2D,
+            CL_MEM_OBJECT_IMAGE2D_ARRAY => Self::Image2DArray,
+            CL_MEM_OBJECT_IMAGE3D => Self::Image3D,
+            CL_MEM_OBJECT_PIPE => Self::Pipe,
+            other => Self::Unknown(other),
+        }
+    }
+}
+
+impl std::fmt::Display for MemObjectType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unknown(value) => write!(f, "Unknown({:#x})", value),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use crate::error::{ClError, wrapper_error::WrapperError};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MemoryFlags {
     ReadWrite,
@@ -25,6 +27,27 @@ impl MemoryFlags {
         carry
     }
 
+    /// Splits a `cl_mem_flags` bit field into its flags.
+    pub fn from_u64(bits: u64) -> Vec<MemoryFlags> {
+        let all = [
+            Self::ReadWrite,
+            Self::WriteOnly,
+            Self::ReadOnly,
+            Self::UseHostPtr,
+            Self::AllocHostPtr,
+            Self::CopyHostPtr,
+            #[cfg(feature = "CL_VERSION_1_2")]
+            Self::HostWriteOnly,
+            #[cfg(feature = "CL_VERSION_1_2")]
+            Self::HostReadOnly,
+            #[cfg(feature = "CL_VERSION_1_2")]
+            Self::HostNoAccess,
+            #[cfg(feature = "CL_VERSION_2_0")]
+            Self::KernelReadAndWrite,
+        ];
+        all.into_iter().filter(|flag| bits & Self::get_u64(flag) != 0).collect()
+    }
+
     pub fn get_u64(flag: &MemoryFlags) -> u64 {
         match flag {
             Self::ReadWrite => cl3::memory::CL_MEM_READ_WRITE,
@@ -44,3 +67,31 @@ impl MemoryFlags {
         }
     }
 }
+
+
+impl std::str::FromStr for MemoryFlags {
+    type Err = ClError;
+
+    /// Parses the variant name (e.g. `"ReadWrite"` or `"read_write"`, case-insensitive).
+    /// Fails with `UnknownMemoryFlag` holding `s` otherwise.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized: String = s.chars().filter(|c| *c != '_' && *c != '-').collect::<String>().to_lowercase();
+        match normalized.as_str() {
+            "readwrite" => Ok(Self::ReadWrite),
+            "writeonly" => Ok(Self::WriteOnly),
+            "readonly" => Ok(Self::ReadOnly),
+            "usehostptr" => Ok(Self::UseHostPtr),
+            "allochostptr" => Ok(Self::AllocHostPtr),
+            "copyhostptr" => Ok(Self::CopyHostPtr),
+            #[cfg(feature = "CL_VERSION_1_2")]
+            "hostwriteonly" => Ok(Self::HostWriteOnly),
+            #[cfg(feature = "CL_VERSION_1_2")]
+            "hostreadonly" => Ok(Self::HostReadOnly),
+            #[cfg(feature = "CL_VERSION_1_2")]
+            "hostnoaccess" => Ok(Self::HostNoAccess),
+            #[cfg(feature = "CL_VERSION_2_0")]
+            "kernelreadandwrite" => Ok(Self::KernelReadAndWrite),
+            _ => Err(ClError::Wrapper(WrapperError::UnknownMemoryFlag { flag: s.to_string() })),
+        }
+    }
+}
@@ -10,6 +10,8 @@ pub mod cl_kernel;
 pub mod cl_event;
 pub mod cl_buffer;
 pub mod memory_flags;
+pub mod mem_object_type;
+pub mod mem_object;
 pub mod cl_svm_buffer;
 pub mod cl_image;
 pub mod cl_pipe;
\ No newline at end of file
@@ -1,3 +1,9 @@
+use crate::cl_types::{
+    cl_image::{image_channel_data_type::ClImageChannelType, image_channel_order::ClImageChannelOrder, image_formats::ClImageFormats},
+    cl_svm_buffer::svm_mode::SvmMode,
+    mem_object_type::MemObjectType,
+};
+
 #[derive(Debug)]
 pub enum WrapperError {
     FormatterFailed,
@@ -7,4 +13,69 @@ pub enum WrapperError {
     FailedToConvertStrToCString,
     PlatformsNotFound,
     FileIOError,
+    DeviceIndexOutOfRange,
+    /// A pipeline definition is malformed. `entry` locates the offending item (e.g. `stages[1].args[0]`).
+    InvalidPipelineDefinition { entry: String, reason: String },
+    /// A host slice doesn't have the element count of the typed buffer it is copied to or from.
+    BufferLengthMismatch { expected: usize, actual: usize },
+    /// `len` elements of `element_size` bytes take more bytes than a `usize` holds.
+    BufferSizeOverflow { len: usize, element_size: usize },
+    /// A range reaches past the end of a buffer.
+    BufferRangeOutOfBounds { offset: usize, len: usize, buffer_len: usize },
+    /// A sub-buffer origin is not a multiple of the devices' base address alignment (in bytes).
+    MisalignedSubBufferOffset { origin: usize, alignment: usize },
+    /// An allocation is larger than `CL_DEVICE_MAX_MEM_ALLOC_SIZE` of some device of the context
+    /// (all in bytes, `in_use` being the executor's memory usage at the time).
+    AllocationTooLarge { requested: usize, max_alloc: usize, in_use: usize },
+    /// An allocation would take the executor's memory usage over its budget (all in bytes).
+    MemoryBudgetExceeded { requested: usize, in_use: usize, budget: usize },
+    /// An image can't be read as the requested pixel type.
+    IncompatibleImageFormat { image: ClImageFormats, requested: ClImageFormats },
+    /// A host slice is smaller than the transfer that would read or write it (in bytes).
+    HostBufferTooSmall { required: usize, provided: usize },
+    /// The host memory an image region spans with the given pitches overflows a `usize`.
+    HostRegionOverflow { region: [usize; 3], row_pitch: usize, slice_pitch: usize },
+    /// A mip level past the last level of an image.
+    MipLevelOutOfRange { level: u32, levels: u32 },
+    /// Mip levels can't be generated for this image: without `cl_img_generate_mipmap`, only
+    /// 2D images with normalized or float channels are supported.
+    MipmapGenerationUnsupported,
+    /// An image view needs more bytes than its buffer has.
+    ImageExceedsBuffer { required: usize, buffer_size: usize },
+    /// The row pitch of an image view is smaller than a row or not a multiple of the
+    /// devices' `CL_DEVICE_IMAGE_PITCH_ALIGNMENT` (all in bytes).
+    InvalidImagePitch { row_pitch: usize, min_pitch: usize, alignment: usize },
+    /// The buffer of an image view doesn't start at a multiple of the devices'
+    /// `CL_DEVICE_IMAGE_BASE_ADDRESS_ALIGNMENT` (both in bytes).
+    MisalignedImageBaseAddress { address: usize, alignment: usize },
+    /// OpenCL doesn't allow this combination of channel order and type (e.g. `UnormShort565` with `RGBA`).
+    InvalidImageFormat { channel_order: ClImageChannelOrder, channel_type: ClImageChannelType },
+    /// No image format supported by the devices can hold pixels of this layout.
+    UnsupportedPixelLayout { channel_order: ClImageChannelOrder, channel_type: ClImageChannelType },
+    /// An image wrapped by a typed image is not of the typed image's kind (e.g. a 3D image as a `ClImage2D`).
+    ImageTypeMismatch { expected: MemObjectType, actual: MemObjectType },
+    /// A built-in image operation can't run on this format, e.g. integer channels where the
+    /// kernels need normalized or float ones.
+    UnsupportedImageOperation { operation: &'static str, format: ClImageFormats },
+    /// The standard deviation of a Gaussian blur is NaN or infinite.
+    InvalidBlurSigma { sigma: f32 },
+    /// A device of the context can't allocate SVM buffers of this mode (see `SvmCapabilities`).
+    SvmModeUnsupported { mode: SvmMode, device: String },
+    /// A coarse-grain SVM buffer was accessed directly; it has to be mapped with `map_mut`.
+    SvmMapRequired,
+    /// A region of a typed image reaches past its size (all in pixels).
+    ImageRegionOutOfBounds { origin: [usize; 3], region: [usize; 3], size: [usize; 3] },
+    /// A buffer was viewed as elements of a zero-sized type, which have no element count.
+    ZeroSizedElement,
+    /// An image origin or region given as a `Vec` doesn't have exactly 3 coordinates.
+    InvalidImageCoordinates { origin_len: usize, region_len: usize },
+    /// An image is too large for an `ImageBuffer`, whose dimensions are `u32`s.
+    ImageTooLargeForHost { width: usize, height: usize },
+    /// OpenCL destroyed a `HostBuffer` without handing its `Vec` back to `into_vec`.
+    HostDataLost,
+    /// `fill_image` was given a color type the image's channel type doesn't take
+    /// (`f32` for normalized and float channels, `i32`/`u32` for signed/unsigned integers).
+    FillColorMismatch { format: ClImageFormats, color_type: &'static str },
+    /// A memory flag name that `MemoryFlags::from_str` doesn't know.
+    UnknownMemoryFlag { flag: String },
 }
@@ -1,7 +1,7 @@
 use easy_async_opencl3::{
     async_executor::AsyncExecutor,
-    cl_types::memory_flags::MemoryFlags,
-    error::ClError,
+    cl_types::{cl_buffer::{buffer_map::MapMode, typed_buffer::TypedBuffer}, memory_flags::MemoryFlags},
+    error::{ClError, wrapper_error::WrapperError},
 };
 
 #[tokio::test]
@@ -39,3 +39,822 @@ async fn test_buffer_read_write() -> Result<(), ClError> {
     println!("Buffer read/write verification successful!");
     Ok(())
 }
+
+#[tokio::test]
+async fn test_typed_buffer_round_trip() -> Result<(), ClError> {
+    let executor = AsyncExecutor::new_best_platform()?;
+    let data: Vec<i32> = (0..256).collect();
+
+    let src = "kernel void negate(global int* a) { size_t i = get_global_id(0); a[i] = -a[i]; }";
+    let program = executor.build_program(src.to_string(), None)?;
+    let kernel = executor.create_kernel(&program, "negate")?;
+
+    let buffer = executor.typed_buffer_from_slice(&[MemoryFlags::ReadWrite], &data)?;
+    assert_eq!(buffer.len(), 256);
+    assert_eq!(buffer.byte_size(), 256 * 4);
+
+    executor.create_task(&kernel)
+        .arg_buffer(0, &buffer)
+        .global_work_dims(buffer.len(), 1, 1)
+        .run()
+        .await?;
+
+    let result = buffer.to_vec().await?;
+    assert!(result.iter().zip(&data).all(|(r, d)| *r == -d));
+
+    // Partial writes are bounds-checked in elements
+    buffer.write_at(250, &[7; 6]).await?;
+    let mut tail = [0i32; 6];
+    buffer.read_at(250, &mut tail).await?;
+    assert_eq!(tail, [7; 6]);
+
+    match buffer.write(&data[..10]).await {
+        Err(ClError::Wrapper(WrapperError::BufferLengthMismatch { expected: 256, actual: 10 })) => {}
+        other => panic!("expected a length mismatch, got {:?}", other.map(|_| ())),
+    }
+    assert!(buffer.read_at(251, &mut tail).await.is_err());
+
+    // Element counts whose byte size overflows, or that the buffer can't hold, are rejected
+    assert!(matches!(
+        executor.create_typed_buffer::<u64>(&[MemoryFlags::ReadWrite], usize::MAX / 4),
+        Err(ClError::Wrapper(WrapperError::BufferSizeOverflow { element_size: 8, .. }))
+    ));
+    let queue = &executor.get_queues()[0];
+    assert!(matches!(
+        TypedBuffer::<i32>::from_buffer(buffer.as_buffer().clone(), 257, queue),
+        Err(ClError::Wrapper(WrapperError::BufferRangeOutOfBounds { len: 257, buffer_len: 256, .. }))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sub_buffer_regions() -> Result<(), ClError> {
+    let executor = AsyncExecutor::new_best_platform()?;
+    let alignment = executor.create_buffer(&[MemoryFlags::ReadWrite], 4, std::ptr::null_mut())?.get_base_addr_align()?;
+
+    // Two aligned halves of one parent buffer
+    let half = alignment.max(256);
+    let elements = half * 2 / 4;
+    let data = vec![1.0f32; elements];
+    let parent = executor.typed_buffer_from_slice(&[MemoryFlags::ReadWrite], &data)?;
+    let second = parent.create_sub_buffer(&[MemoryFlags::ReadWrite], half, half)?;
+    assert!(second.parent().is_some());
+    assert_eq!(second.get_size()?, half);
+
+    let src = "kernel void fill(global float* a, float v) { a[get_global_id(0)] = v; }";
+    let program = executor.build_program(src.to_string(), None)?;
+    let kernel = executor.create_kernel(&program, "fill")?;
+    executor.create_task(&kernel)
+        .arg_buffer(0, &second)
+        .arg_scalar(1, 5.0f32)
+        .global_work_dims(half / 4, 1, 1)
+        .run()
+        .await?;
+
+    // The view stays usable after the parent handle is dropped
+    let mut view = vec![0.0f32; half / 4];
+    drop(parent);
+    executor.read_buffer(&second, &mut view).await?;
+    assert!(view.iter().all(|v| *v == 5.0));
+
+    if alignment > 1 {
+        let parent = executor.create_buffer(&[MemoryFlags::ReadWrite], half * 2, std::ptr::null_mut())?;
+        match parent.create_sub_buffer(&[MemoryFlags::ReadWrite], 1, 4) {
+            Err(ClError::Wrapper(WrapperError::MisalignedSubBufferOffset { origin: 1, .. })) => {}
+            other => panic!("expected a misaligned offset error, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_buffer_mapping() -> Result<(), ClError> {
+    let executor = AsyncExecutor::new_best_platform()?;
+    let size = 1024;
+    let mut buffer = executor.create_buffer(&[MemoryFlags::ReadWrite, MemoryFlags::AllocHostPtr], size * 4, std::ptr::null_mut())?;
+
+    // Fill through a zero-copy mapping
+    let mut view = executor.map_buffer_mut::<u32>(&mut buffer, .., MapMode::WriteInvalidate).await?;
+    assert_eq!(view.len(), size);
+    for (i, v) in view.iter_mut().enumerate() {
+        *v = i as u32;
+    }
+    view.unmap().await?;
+
+    let src = "kernel void double_it(global uint* a) { size_t i = get_global_id(0); a[i] *= 2; }";
+    let program = executor.build_program(src.to_string(), None)?;
+    let kernel = executor.create_kernel(&program, "double_it")?;
+    executor.create_task(&kernel)
+        .arg_buffer(0, &buffer)
+        .global_work_dims(size, 1, 1)
+        .run()
+        .await?;
+
+    // Map only part of the buffer; the guard unmaps on drop
+    {
+        let tail = executor.map_buffer::<u32>(&buffer, 1000..1024).await?;
+        assert_eq!(tail.len(), 24);
+        assert!(tail.iter().enumerate().all(|(i, v)| *v == (1000 + i as u32) * 2));
+    }
+
+    assert!(executor.map_buffer::<u32>(&buffer, 1000..1025).await.is_err());
+    assert!(matches!(
+        executor.map_buffer::<()>(&buffer, ..).await,
+        Err(ClError::Wrapper(WrapperError::ZeroSizedElement))
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_device_side_copy_and_fill() -> Result<(), ClError> {
+    use easy_async_opencl3::cl_types::cl_image::{image_desc::ClImageDesc, image_formats::ClImageFormats, image_type::ClImageType};
+
+    let executor = AsyncExecutor::new_best_platform()?;
+    let size = 1024;
+    let a = executor.create_buffer(&[MemoryFlags::ReadWrite], size * 4, std::ptr::null_mut())?;
+    let b = executor.create_buffer(&[MemoryFlags::ReadWrite], size * 4, std::ptr::null_mut())?;
+
+    // Clear `a`, then duplicate its first half into the second half of `b`
+    executor.fill_buffer(&a, 3u32).await?;
+    executor.fill_buffer(&b, 0u32).await?;
+    executor.copy_buffer(&a, &b, 0, size * 2, size * 2).await?;
+
+    let mut host = vec![9u32; size];
+    executor.read_buffer(&b, &mut host).await?;
+    assert!(host[..size / 2].iter().all(|v| *v == 0));
+    assert!(host[size / 2..].iter().all(|v| *v == 3));
+
+    // Fill an image and copy it into a buffer as packed RGBA floats
+    let side = 16;
+    let desc = ClImageDesc {
+        image_type: ClImageType::Image2D,
+        image_width: Some(side),
+        image_height: Some(side),
+        ..Default::default()
+    };
+    let image = executor.create_image(&[MemoryFlags::ReadWrite], &ClImageFormats::rgba_float(), &desc, std::ptr::null_mut())?;
+    let copy = executor.create_image(&[MemoryFlags::ReadWrite], &ClImageFormats::rgba_float(), &desc, std::ptr::null_mut())?;
+    executor.fill_image(&image, [0.25f32, 0.5, 0.75, 1.0], [0, 0, 0], [side, side, 1]).await?;
+    executor.copy_image(&image, &copy, [0, 0, 0], [0, 0, 0], [side, side, 1]).await?;
+
+    let pixels = executor.create_buffer(&[MemoryFlags::ReadWrite], side * side * 16, std::ptr::null_mut())?;
+    executor.copy_image_to_buffer(&copy, &pixels, [0, 0, 0], [side, side, 1], 0).await?;
+    let mut rgba = vec![0.0f32; side * side * 4];
+    executor.read_buffer(&pixels, &mut rgba).await?;
+    assert!(rgba.chunks(4).all(|p| p == [0.25, 0.5, 0.75, 1.0]));
+
+    // And back again
+    executor.fill_image(&copy, [0.0f32; 4], [0, 0, 0], [side, side, 1]).await?;
+    executor.copy_buffer_to_image(&pixels, &copy, 0, [0, 0, 0], [side, side, 1]).await?;
+    let mut round_trip = vec![0.0f32; side * side * 4];
+    executor.read_image(&copy, &mut round_trip, [0, 0, 0], [side, side, 1]).await?;
+    assert_eq!(round_trip, rgba);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rect_buffer_transfers() -> Result<(), ClError> {
+    let executor = AsyncExecutor::new_best_platform()?;
+    let (cols, rows) = (64usize, 32usize);
+    let mut matrix: Vec<u32> = (0..(cols * rows) as u32).collect();
+    let buffer = executor.create_buffer(&[MemoryFlags::ReadWrite], cols * rows * 4, std::ptr::null_mut())?;
+    executor.write_buffer(&buffer, &mut matrix).await?;
+
+    // 8x4 tile at column 10, row 5
+    let mut tile = vec![0u32; 8 * 4];
+    executor.read_buffer_rect(&buffer, cols, [10, 5], &mut tile, 8).await?;
+    for (r, row) in tile.chunks(8).enumerate() {
+        let start = (5 + r) * cols + 10;
+        assert_eq!(row, &matrix[start..start + 8]);
+    }
+
+    // Write it back negated one row lower and check nothing else changed
+    let negated: Vec<u32> = tile.iter().map(|v| u32::MAX - v).collect();
+    executor.write_buffer_rect(&buffer, cols, [10, 6], &negated, 8).await?;
+    let mut result = vec![0u32; cols * rows];
+    executor.read_buffer(&buffer, &mut result).await?;
+    for r in 0..rows {
+        for c in 0..cols {
+            let expected = if (6..10).contains(&r) && (10..18).contains(&c) {
+                u32::MAX - matrix[(r - 1) * cols + c]
+            } else {
+                matrix[r * cols + c]
+            };
+            assert_eq!(result[r * cols + c], expected, "row {r}, column {c}");
+        }
+    }
+
+    // Tiles that don't fit are rejected before reaching the driver
+    assert!(executor.read_buffer_rect(&buffer, cols, [60, 0], &mut tile, 8).await.is_err());
+    assert!(executor.read_buffer_rect(&buffer, cols, [0, 30], &mut tile, 8).await.is_err());
+    assert!(executor.read_buffer_rect(&buffer, cols, [0, 0], &mut tile, 7).await.is_err());
+    assert!(matches!(
+        executor.read_buffer_rect(&buffer, usize::MAX, [0, 0], &mut tile, 8).await,
+        Err(ClError::Wrapper(WrapperError::BufferRangeOutOfBounds { buffer_len: 0, .. }))
+    ));
+    assert!(matches!(
+        executor.read_buffer_rect(&buffer, cols, [0, 0], &mut [(); 8], 8).await,
+        Err(ClError::Wrapper(WrapperError::ZeroSizedElement))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_buffer_pool_recycles_by_class_and_flags() -> Result<(), ClError> {
+    use easy_async_opencl3::async_executor::buffer_pool::{BufferPool, BufferPoolConfig};
+
+    let config = BufferPoolConfig { max_bytes: 64 * 1024, max_buffers_per_class: 2 };
+    let executor = AsyncExecutor::new_best_platform()?.with_buffer_pool(config);
+    let pool = executor.buffer_pool().unwrap();
+
+    let first = executor.acquire_buffer(&[MemoryFlags::ReadWrite], 3000)?;
+    assert_eq!(first.size(), 3000);
+    assert_eq!(Some(first.capacity()), BufferPool::size_class(3000));
+    assert_eq!(first.get_size()?, 4096);
+    let raw = first.as_ptr();
+    drop(first);
+    assert_eq!(pool.stats().buffers_held, 1);
+
+    // Same class and flags: the buffer comes back
+    let second = executor.acquire_buffer(&[MemoryFlags::ReadWrite], 4000)?;
+    assert_eq!(second.as_ptr(), raw);
+    // Different flags: a new buffer
+    let third = executor.acquire_buffer(&[MemoryFlags::ReadOnly], 4000)?;
+    assert_ne!(third.as_ptr(), raw);
+
+    let mut data = vec![7u8; 4000];
+    executor.write_buffer(&second, &mut data).await?;
+    let mut back = vec![0u8; 4000];
+    executor.read_buffer(&second, &mut back).await?;
+    assert_eq!(back, data);
+
+    let stats = pool.stats();
+    assert_eq!((stats.hits, stats.misses), (1, 2));
+    assert_eq!(stats.bytes_held, 0);
+
+    // Only two idle buffers per class are kept
+    let extra: Vec<_> = (0..3).map(|_| executor.acquire_buffer(&[MemoryFlags::ReadWrite], 100)).collect::<Result<_, _>>()?;
+    drop(extra);
+    drop(second);
+    drop(third);
+    let stats = pool.stats();
+    assert_eq!(stats.buffers_held, 4);
+    assert_eq!(stats.evictions, 1);
+
+    pool.trim_to(4096);
+    assert!(pool.stats().bytes_held <= 4096);
+    pool.trim();
+    assert_eq!(pool.stats().buffers_held, 0);
+
+    // Sizes without a power-of-two class are rejected instead of wrapping to a tiny one
+    assert_eq!(BufferPool::size_class(usize::MAX), None);
+    assert!(matches!(
+        executor.acquire_buffer(&[MemoryFlags::ReadWrite], usize::MAX),
+        Err(ClError::Wrapper(WrapperError::AllocationTooLarge { requested: usize::MAX, .. }))
+    ));
+
+    // Detached buffers never go back
+    let kept = executor.acquire_buffer(&[MemoryFlags::ReadWrite], 512)?.detach();
+    drop(kept);
+    assert_eq!(pool.stats().buffers_held, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_memory_budget_and_accounting() -> Result<(), ClError> {
+    let executor = AsyncExecutor::new_best_platform()?.with_memory_budget(1024 * 1024);
+    let baseline = executor.memory_usage();
+    assert_eq!(baseline.in_use, 0);
+    assert_eq!(baseline.budget, Some(1024 * 1024));
+
+    let a = executor.create_buffer(&[MemoryFlags::ReadWrite], 512 * 1024, std::ptr::null_mut())?;
+    let b = executor.create_typed_buffer::<f32>(&[MemoryFlags::ReadWrite], 64 * 1024)?;
+    let usage = executor.memory_usage();
+    assert_eq!(usage.in_use, 768 * 1024);
+    assert_eq!(usage.live_objects, 2);
+
+    match executor.create_buffer(&[MemoryFlags::ReadWrite], 512 * 1024, std::ptr::null_mut()) {
+        Err(ClError::Wrapper(WrapperError::MemoryBudgetExceeded { requested, in_use, budget })) => {
+            assert_eq!((requested, in_use, budget), (512 * 1024, 768 * 1024, 1024 * 1024));
+        }
+        other => panic!("expected MemoryBudgetExceeded, got {:?}", other.map(|_| ())),
+    }
+
+    // Images are sized from their description before they are allocated
+    use easy_async_opencl3::cl_types::cl_image::pixel::Rgba;
+    match executor.create_image_2d::<Rgba<f32>>(&[MemoryFlags::ReadWrite], 512, 512) {
+        Err(ClError::Wrapper(WrapperError::MemoryBudgetExceeded { requested, .. })) => assert_eq!(requested, 512 * 512 * 16),
+        other => panic!("expected MemoryBudgetExceeded, got {:?}", other.map(|_| ())),
+    }
+
+    let max_alloc = usage.max_alloc;
+    executor.set_memory_budget(None);
+    let in_use = executor.memory_usage().in_use;
+    assert!(matches!(
+        executor.create_buffer(&[MemoryFlags::ReadWrite], max_alloc + 1, std::ptr::null_mut()),
+        Err(ClError::Wrapper(WrapperError::AllocationTooLarge { in_use: reported, .. })) if reported == in_use
+    ));
+
+    // Bytes come back once OpenCL destroys the objects
+    drop(a);
+    drop(b);
+    for _ in 0..100 {
+        if executor.memory_usage().in_use == 0 {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    let usage = executor.memory_usage();
+    assert_eq!((usage.in_use, usage.live_objects), (0, 0));
+    assert_eq!(usage.peak, 768 * 1024);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_memory_object_introspection() -> Result<(), ClError> {
+    use easy_async_opencl3::cl_types::{
+        cl_image::{image_desc::ClImageDesc, image_formats::ClImageFormats, image_type::ClImageType},
+        mem_object_type::MemObjectType,
+    };
+
+    let executor = AsyncExecutor::new_best_platform()?;
+    let size = 64 * 1024;
+    let buffer = executor.create_buffer(&[MemoryFlags::ReadWrite, MemoryFlags::AllocHostPtr], size, std::ptr::null_mut())?;
+    assert_eq!(buffer.get_mem_type()?, MemObjectType::Buffer);
+    assert_eq!(buffer.get_size()?, size);
+    assert_eq!(buffer.get_flags()?, vec![MemoryFlags::ReadWrite, MemoryFlags::AllocHostPtr]);
+    assert!(buffer.get_associated_memobject()?.is_none());
+    assert_eq!(buffer.get_offset()?, 0);
+    assert_eq!(buffer.get_context()?.as_ptr(), executor.get_context().as_ptr());
+
+    let references = buffer.get_reference_count()?;
+    let copy = buffer.clone();
+    assert_eq!(buffer.get_reference_count()?, references + 1);
+    drop(copy);
+
+    {
+        let _view = executor.map_buffer::<u8>(&buffer, ..16).await?;
+        assert_eq!(buffer.get_map_count()?, 1);
+    }
+
+    let half = size / 2;
+    let sub = buffer.create_sub_buffer(&[MemoryFlags::ReadWrite], half, half)?;
+    assert_eq!(sub.get_offset()?, half);
+    assert_eq!(sub.get_associated_memobject()?.map(|parent| parent.as_ptr()), Some(buffer.as_ptr()));
+    assert!(buffer.to_string().contains(&format!("size: {}", size)));
+
+    let desc = ClImageDesc {
+        image_type: ClImageType::Image2D,
+        image_width: Some(32),
+        image_height: Some(32),
+        ..Default::default()
+    };
+    let image = executor.create_image(&[MemoryFlags::ReadOnly], &ClImageFormats::rgba_unorm_int8(), &desc, std::ptr::null_mut())?;
+    assert_eq!(image.get_mem_type()?, MemObjectType::Image2D);
+    assert_eq!((image.get_width()?, image.get_height()?), (32, 32));
+    assert_eq!(image.get_element_size()?, 4);
+    assert_eq!(image.get_flags()?, vec![MemoryFlags::ReadOnly]);
+    println!("{}\n{}", buffer, image);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_host_owning_buffers() -> Result<(), ClError> {
+    use std::sync::Arc;
+
+    let executor = AsyncExecutor::new_best_platform()?;
+    let src = "kernel void add(global const int* a, global int* b) { size_t i = get_global_id(0); b[i] += a[i]; }";
+    let program = executor.build_program(src.to_string(), None)?;
+    let kernel = executor.create_kernel(&program, "add")?;
+
+    let size = 1024;
+    let shared: Arc<[i32]> = (0..size as i32).collect::<Vec<_>>().into();
+    let a = executor.buffer_from_arc(&[MemoryFlags::ReadWrite], shared.clone())?;
+    assert_eq!(a.get_flags()?, vec![MemoryFlags::ReadOnly, MemoryFlags::UseHostPtr, MemoryFlags::HostReadOnly]);
+    // The host can't write into the shared data either
+    assert!(executor.write_buffer(&a, &mut vec![0i32; size]).await.is_err());
+    let b = executor.buffer_from_vec(&[MemoryFlags::ReadWrite], vec![100i32; size].into_boxed_slice())?;
+    assert_eq!(b.len(), size);
+
+    executor.create_task(&kernel)
+        .arg_buffer(0, &a)
+        .arg_buffer(1, &b)
+        .global_work_dims(size, 1, 1)
+        .run()
+        .await?;
+
+    // A clone keeps the buffer alive, so the Vec can't be taken back yet
+    let extra = b.as_buffer().clone();
+    assert!(!b.is_unique());
+    let b = match b.into_vec() {
+        Err(b) => b,
+        Ok(_) => panic!("buffer is still referenced"),
+    };
+    drop(extra);
+
+    // So does a sub-buffer
+    let sub = b.create_sub_buffer(&[MemoryFlags::ReadWrite], 0, 64)?;
+    let b = b.into_vec().err().expect("sub-buffer still references the buffer");
+    drop(sub);
+
+    let data = b.into_vec().ok().expect("sole owner gets the Vec back").await?;
+    assert!(data.iter().enumerate().all(|(i, v)| *v == 100 + i as i32));
+
+    // Shared data is never given back, and outlives the buffer
+    assert!(a.into_vec().is_err());
+    assert_eq!(shared[10], 10);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_image_format_negotiation() -> Result<(), ClError> {
+    use easy_async_opencl3::cl_types::cl_image::{
+        format_negotiation::{NegotiatedFormat, PixelLayout},
+        image_channel_data_type::ClImageChannelType,
+        image_channel_order::ClImageChannelOrder,
+        image_desc::ClImageDesc,
+        image_formats::ClImageFormats,
+        image_type::ClImageType,
+    };
+
+    // RGB u8 isn't a valid format, so it becomes RGBA u8, then RGBA float
+    let rgb8 = PixelLayout::new(ClImageChannelOrder::RGB, ClImageChannelType::UnormInt8);
+    let negotiated = NegotiatedFormat::negotiate(&[ClImageFormats::rgba_float(), ClImageFormats::rgba_unorm_int8()], rgb8).unwrap();
+    assert_eq!(negotiated.format(), ClImageFormats::rgba_unorm_int8());
+    assert!(negotiated.needs_swizzle() && !negotiated.needs_conversion());
+    assert_eq!(negotiated.to_device(&[1, 2, 3, 4, 5, 6])?, vec![1, 2, 3, 255, 4, 5, 6, 255]);
+    assert_eq!(negotiated.to_host(&[1, 2, 3, 255])?, vec![1, 2, 3]);
+
+    let negotiated = NegotiatedFormat::negotiate(&[ClImageFormats::rgba_float()], rgb8).unwrap();
+    assert!(negotiated.needs_conversion());
+    let device = negotiated.to_device(&[255, 0, 51])?;
+    let floats: Vec<f32> = device.chunks_exact(4).map(|b| f32::from_ne_bytes(b.try_into().unwrap())).collect();
+    assert_eq!(floats, vec![1.0, 0.0, 0.2, 1.0]);
+    assert_eq!(negotiated.to_host(&device)?, vec![255, 0, 51]);
+    assert!(matches!(
+        negotiated.to_device(&[1, 2]),
+        Err(ClError::Wrapper(WrapperError::BufferLengthMismatch { expected: 0, actual: 2 }))
+    ));
+
+    let bgra = PixelLayout::new(ClImageChannelOrder::BGRA, ClImageChannelType::UnormInt8);
+    let negotiated = NegotiatedFormat::negotiate(&[ClImageFormats::rgba_unorm_int8()], bgra).unwrap();
+    assert_eq!(negotiated.to_device(&[1, 2, 3, 4])?, vec![3, 2, 1, 4]);
+    let depth = PixelLayout::new(ClImageChannelOrder::Depth, ClImageChannelType::Float);
+    assert!(NegotiatedFormat::negotiate(&[ClImageFormats::rgba_float()], depth).is_none());
+
+    // Against the device: upload RGB pixels in whatever format was chosen and read them back
+    let executor = AsyncExecutor::new_best_platform()?;
+    let negotiated = executor.negotiate_image_format(&[MemoryFlags::ReadWrite], ClImageType::Image2D, rgb8)?;
+    let rgb: Vec<u8> = (0..16 * 16 * 3).map(|i| (i % 251) as u8).collect();
+    let mut pixels = negotiated.to_device(&rgb)?;
+    let desc = ClImageDesc {
+        image_type: ClImageType::Image2D,
+        image_width: Some(16),
+        image_height: Some(16),
+        ..Default::default()
+    };
+    let image = executor.create_image(&[MemoryFlags::ReadWrite], &negotiated.format(), &desc, std::ptr::null_mut())?;
+    executor.write_image(&image, &mut pixels, [0, 0, 0], [16, 16, 1]).await?;
+    let mut read_back = vec![0u8; pixels.len()];
+    executor.read_image(&image, &mut read_back, [0, 0, 0], [16, 16, 1]).await?;
+    assert_eq!(negotiated.to_host(&read_back)?, rgb);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_image_transfers_check_host_size() -> Result<(), ClError> {
+    use easy_async_opencl3::cl_types::cl_image::{image_desc::ClImageDesc, image_formats::ClImageFormats, image_type::ClImageType};
+
+    let executor = AsyncExecutor::new_best_platform()?;
+    let desc = ClImageDesc {
+        image_type: ClImageType::Image2D,
+        image_width: Some(8),
+        image_height: Some(8),
+        ..Default::default()
+    };
+    let image = executor.create_image(&[MemoryFlags::ReadWrite], &ClImageFormats::rgba_float(), &desc, std::ptr::null_mut())?;
+    assert_eq!(image.host_region_size([8, 8, 1], 0, 0)?, 8 * 8 * 16);
+    assert_eq!(image.host_region_size([4, 2, 1], 256, 0)?, 256 + 4 * 16);
+
+    // One pixel short
+    let mut small = vec![0.0f32; 8 * 8 * 4 - 4];
+    let too_small = |result: Result<(), ClError>| {
+        matches!(result, Err(ClError::Wrapper(WrapperError::HostBufferTooSmall { required: 1024, provided: 1008 })))
+    };
+    assert!(too_small(executor.read_image(&image, &mut small, [0, 0, 0], [8, 8, 1]).await.map(|_| ())));
+    assert!(too_small(executor.write_image(&image, &mut small, [0, 0, 0], [8, 8, 1]).await.map(|_| ())));
+
+    // The queue methods take the region as Vecs, and check both
+    let queue = &executor.get_queues()[0];
+    assert!(too_small(queue.read_image(image.clone(), vec![0, 0, 0], vec![8, 8, 1], 0, 0, &mut small, None).await.map(|_| ())));
+    let result = queue.write_image(image.clone(), vec![0, 0], vec![8, 8, 1], 0, 0, &small, None).await;
+    assert!(matches!(result, Err(ClError::Wrapper(WrapperError::InvalidImageCoordinates { origin_len: 2, region_len: 3 }))));
+
+    // Pitches that would wrap the required size around are rejected, not trusted
+    let overflow = queue.read_image(image.clone(), vec![0, 0, 0], vec![8, 3, 1], usize::MAX / 2 + 1, 0, &mut small, None).await;
+    assert!(matches!(overflow, Err(ClError::Wrapper(WrapperError::HostRegionOverflow { row_pitch, .. })) if row_pitch == usize::MAX / 2 + 1));
+
+    // The task fails before its kernel runs
+    let src = "kernel void touch(write_only image2d_t img) { write_imagef(img, (int2)(get_global_id(0), get_global_id(1)), (float4)(1.0f)); }";
+    let program = executor.build_program(src.to_string(), None)?;
+    let kernel = executor.create_kernel(&program, "touch")?;
+    let result = executor.create_task(&kernel)
+        .arg_image(0, &image)
+        .global_work_dims(8, 8, 1)
+        .read_image(&image, &mut small, [0, 0, 0], [8, 8, 1])
+        .run()
+        .await;
+    assert!(too_small(result.map(|_| ())));
+
+    let result = executor.create_pipeline()
+        .add_stage(&kernel, 8, 8, 1)
+        .arg_image(0, &image)
+        .finish()
+        .read_image(&image, &mut small, [0, 0, 0], [8, 8, 1])
+        .run()
+        .await;
+    assert!(too_small(result.map(|_| ())));
+
+    // Float images take `f32` colors only (`[u8; 4]` or `[f64; 4]` don't compile)
+    let result = executor.fill_image(&image, [1u32; 4], [0, 0, 0], [8, 8, 1]).await;
+    assert!(matches!(result, Err(ClError::Wrapper(WrapperError::FillColorMismatch { color_type: "u32", .. }))));
+
+    let mut exact = vec![0.0f32; 8 * 8 * 4];
+    executor.create_task(&kernel)
+        .arg_image(0, &image)
+        .global_work_dims(8, 8, 1)
+        .read_image(&image, &mut exact, [0, 0, 0], [8, 8, 1])
+        .run()
+        .await?;
+    assert!(exact.iter().all(|v| *v == 1.0));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_mip_levels() -> Result<(), ClError> {
+    use easy_async_opencl3::cl_types::cl_image::{image_desc::ClImageDesc, image_formats::ClImageFormats, image_type::ClImageType};
+
+    let executor = AsyncExecutor::new_best_platform()?;
+    let desc = ClImageDesc {
+        image_type: ClImageType::Image2D,
+        image_width: Some(8),
+        image_height: Some(4),
+        num_mip_levels: Some(4),
+        ..Default::default()
+    };
+    // Mipmapped images need cl_khr_mipmap_image
+    let image = match executor.create_image(&[MemoryFlags::ReadWrite], &ClImageFormats::rgba_float(), &desc, std::ptr::null_mut()) {
+        Ok(image) => image,
+        Err(ClError::Api(_)) => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    assert!(image.is_mipmapped()?);
+    assert_eq!(image.mip_level_region(1)?, [4, 2, 1]);
+    assert_eq!(image.mip_level_region(3)?, [1, 1, 1]);
+    assert_eq!(image.mip_origin([1, 2, 0], 3)?, [1, 2, 3, 0]);
+    assert!(matches!(
+        image.mip_level_region(4),
+        Err(ClError::Wrapper(WrapperError::MipLevelOutOfRange { level: 4, levels: 4 }))
+    ));
+
+    // Columns alternate between 0 and 1, so every coarser level averages to 0.5
+    let base: Vec<f32> = (0..8 * 4).flat_map(|i| [(i % 2) as f32; 4]).collect();
+    executor.write_image_level(&image, 0, &base).await?;
+    executor.generate_mipmaps(&image).await?;
+
+    let mut level1 = vec![0.0f32; 4 * 2 * 4];
+    executor.read_image_level(&image, 1, &mut level1).await?;
+    assert!(level1.iter().all(|v| (v - 0.5).abs() < 1e-6));
+
+    let mut too_small = vec![0.0f32; 4];
+    assert!(matches!(
+        executor.read_image_level(&image, 1, &mut too_small).await,
+        Err(ClError::Wrapper(WrapperError::HostBufferTooSmall { required: 128, provided: 16 }))
+    ));
+
+    let last = executor.extract_mip_level(&image, 3).await?;
+    assert!(!last.is_mipmapped()?);
+    let mut pixel = [0.0f32; 4];
+    executor.read_image(&last, &mut pixel, [0, 0, 0], [1, 1, 1]).await?;
+    assert!(pixel.iter().all(|v| (v - 0.5).abs() < 1e-6));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_images_over_buffers() -> Result<(), ClError> {
+    use easy_async_opencl3::cl_types::cl_image::image_formats::ClImageFormats;
+
+    let executor = AsyncExecutor::new_best_platform()?;
+    let format = ClImageFormats::rgba_float();
+    let pixels: Vec<f32> = (0..64 * 4).map(|i| i as f32).collect();
+    let buffer = executor.typed_buffer_from_slice(&[MemoryFlags::ReadWrite], &pixels)?;
+
+    assert!(matches!(
+        executor.image_from_buffer_1d(&[MemoryFlags::ReadWrite], &format, &buffer, 65),
+        Err(ClError::Wrapper(WrapperError::ImageExceedsBuffer { required: 1040, buffer_size: 1024 }))
+    ));
+    assert!(matches!(
+        executor.image_from_buffer_1d(&[MemoryFlags::ReadWrite], &format, &buffer, usize::MAX / 8),
+        Err(ClError::Wrapper(WrapperError::ImageExceedsBuffer { required: usize::MAX, buffer_size: 1024 }))
+    ));
+
+    let image = executor.image_from_buffer_1d(&[MemoryFlags::ReadWrite], &format, &buffer, 64)?;
+    assert!(image.get_associated_memobject()?.is_some());
+    // The image keeps the buffer's memory alive on its own
+    drop(buffer);
+    let mut read_back = vec![0.0f32; 64 * 4];
+    executor.read_image(&image, &mut read_back, [0, 0, 0], [64, 1, 1]).await?;
+    assert_eq!(read_back, pixels);
+
+    let buffer = executor.create_buffer(&[MemoryFlags::ReadWrite], 1 << 16, std::ptr::null_mut())?;
+    assert!(matches!(
+        executor.image_from_buffer_2d(&[MemoryFlags::ReadWrite], &format, &buffer, 16, 16, Some(8)),
+        Err(ClError::Wrapper(WrapperError::InvalidImagePitch { row_pitch: 8, min_pitch: 256, .. }))
+    ));
+    assert!(matches!(
+        executor.image_from_buffer_2d(&[MemoryFlags::ReadWrite], &format, &buffer, 16, 4096, None),
+        Err(ClError::Wrapper(WrapperError::ImageExceedsBuffer { .. }))
+    ));
+    // A pitch whose rows add up past `usize::MAX` doesn't wrap around to a size that fits
+    assert!(matches!(
+        executor.image_from_buffer_2d(&[MemoryFlags::ReadWrite], &format, &buffer, 16, 3, Some(usize::MAX / 2 + 1)),
+        Err(ClError::Wrapper(WrapperError::ImageExceedsBuffer { required: usize::MAX, .. }))
+    ));
+    // 2D images over buffers are optional in OpenCL 3.0
+    let image = match executor.image_from_buffer_2d(&[MemoryFlags::ReadWrite], &format, &buffer, 16, 16, None) {
+        Ok(image) => image,
+        Err(ClError::Api(_)) => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    let row_pitch = image.get_row_pitch()?;
+    assert!(row_pitch >= 256);
+    executor.fill_image(&image, [2.0f32; 4], [0, 0, 0], [16, 16, 1]).await?;
+    let mut row = vec![0.0f32; 16 * 4];
+    executor.read_buffer(&buffer, &mut row).await?;
+    assert!(row.iter().all(|v| *v == 2.0));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_typed_images() -> Result<(), ClError> {
+    use easy_async_opencl3::cl_types::cl_image::{ClImage, pixel::{R, Rgba}, typed_image::ClImage2D};
+
+    let executor = AsyncExecutor::new_best_platform()?;
+    let pixels: Vec<Rgba<u8>> = (0..8 * 4).map(|i| Rgba([i as u8, 0, 0, 255])).collect();
+    let image = executor.image_2d_from_pixels(&[MemoryFlags::ReadWrite], 8, 4, &pixels)?;
+    assert_eq!((image.width(), image.height()), (8, 4));
+    assert_eq!(image.to_vec().await?, pixels);
+
+    let tile = [Rgba([9u8, 9, 9, 9]); 4];
+    image.write([6, 2], [2, 2], &tile).await?;
+    let mut read_back = [Rgba([0u8; 4]); 4];
+    image.read([6, 2], [2, 2], &mut read_back).await?;
+    assert_eq!(read_back, tile);
+    assert!(matches!(
+        image.read([7, 2], [2, 2], &mut read_back).await,
+        Err(ClError::Wrapper(WrapperError::ImageRegionOutOfBounds { .. }))
+    ));
+    assert!(matches!(
+        image.write([0, 0], [2, 2], &tile[..3]).await,
+        Err(ClError::Wrapper(WrapperError::BufferLengthMismatch { expected: 4, actual: 3 }))
+    ));
+
+    let untyped: ClImage = image.into_image();
+    let queue = &executor.get_queues()[0];
+    assert!(matches!(
+        ClImage2D::<R<f32>>::from_image(untyped.clone(), queue),
+        Err(ClError::Wrapper(WrapperError::IncompatibleImageFormat { .. }))
+    ));
+    assert!(ClImage2D::<Rgba<u8>>::from_image(untyped, queue).is_ok());
+
+    let layers = executor.create_image_2d_array::<R<f32>>(&[MemoryFlags::ReadWrite], 4, 4, 3)?;
+    let layer: Vec<R<f32>> = (0..16).map(|i| R(i as f32)).collect();
+    layers.write_layer(1, &layer).await?;
+    let mut read_back = vec![R(0.0f32); 16];
+    layers.read_layer(1, &mut read_back).await?;
+    assert_eq!(read_back, layer);
+
+    let volume = executor.create_image_3d::<R<f32>>(&[MemoryFlags::ReadWrite], [4, 4, 4])?;
+    volume.write([0, 0, 3], [4, 4, 1], &layer).await?;
+    assert_eq!(volume.to_vec().await?[48..], layer[..]);
+
+    let voxels: Vec<R<f32>> = (0..32).map(|i| R(i as f32)).collect();
+    let volume = executor.image_3d_from_pixels(&[MemoryFlags::ReadWrite], [4, 4, 2], &voxels)?;
+    assert_eq!(volume.to_vec().await?, voxels);
+    let layers = executor.image_2d_array_from_pixels(&[MemoryFlags::ReadWrite], 4, 4, 2, &voxels)?;
+    layers.read_layer(1, &mut read_back).await?;
+    assert_eq!(read_back, voxels[16..]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_image_ops() -> Result<(), ClError> {
+    use easy_async_opencl3::async_executor::image_ops::{ColorConversion, ResizeFilter};
+    use easy_async_opencl3::cl_types::cl_image::{pixel::{R, Rgba}, typed_image::ClImage2D};
+
+    let executor = AsyncExecutor::new_best_platform()?;
+    let queue = &executor.get_queues()[0];
+    let pixels: Vec<Rgba<f32>> = (0..8 * 8).map(|i| Rgba([(i % 8) as f32 / 8.0, 0.5, 0.25, 1.0])).collect();
+    let image = executor.image_2d_from_pixels(&[MemoryFlags::ReadWrite], 8, 8, &pixels)?;
+
+    // Nearest downscaling by 2 keeps every other column
+    let small = executor.resize_image(&image, 4, 4, ResizeFilter::Nearest).await?;
+    let small = ClImage2D::<Rgba<f32>>::from_image(small, queue)?.to_vec().await?;
+    assert_eq!(small[..4].iter().map(|p| p.0[0]).collect::<Vec<_>>(), [0.125, 0.375, 0.625, 0.875]);
+    let large = executor.resize_image(&image, 16, 16, ResizeFilter::Bilinear).await?;
+    assert_eq!((large.get_width()?, large.get_height()?), (16, 16));
+
+    // A uniform image has no edges and is unchanged by a blur
+    let flat = executor.image_2d_from_pixels(&[MemoryFlags::ReadWrite], 8, 8, &[Rgba([0.5f32; 4]); 64])?;
+    let blurred = ClImage2D::<Rgba<f32>>::from_image(executor.gaussian_blur(&flat, 1.5).await?, queue)?.to_vec().await?;
+    assert!(blurred.iter().all(|p| p.0.iter().all(|c| (c - 0.5).abs() < 1e-4)));
+    // A huge sigma is truncated instead of allocating without bound
+    let wide = ClImage2D::<Rgba<f32>>::from_image(executor.gaussian_blur(&flat, 1e9).await?, queue)?.to_vec().await?;
+    assert!(wide.iter().all(|p| p.0.iter().all(|c| (c - 0.5).abs() < 1e-4)));
+    assert!(matches!(
+        executor.gaussian_blur(&flat, f32::NAN).await,
+        Err(ClError::Wrapper(WrapperError::InvalidBlurSigma { .. }))
+    ));
+    let edges = ClImage2D::<Rgba<f32>>::from_image(executor.sobel(&flat).await?, queue)?.to_vec().await?;
+    assert!(edges.iter().all(|p| p.0[0].abs() < 1e-4));
+
+    let yuv = executor.convert_color(&image, ColorConversion::RgbToYuv).await?;
+    let rgb = executor.convert_color(&yuv, ColorConversion::YuvToRgb).await?;
+    let rgb = ClImage2D::<Rgba<f32>>::from_image(rgb, queue)?.to_vec().await?;
+    assert!(rgb.iter().zip(&pixels).all(|(a, b)| a.0.iter().zip(b.0).all(|(x, y)| (x - y).abs() < 1e-2)));
+
+    // Equalizing 4 gray levels spreads them up to white
+    let gray: Vec<R<f32>> = (0..64).map(|i| R((i % 4) as f32 * 0.1)).collect();
+    let gray = executor.image_2d_from_pixels(&[MemoryFlags::ReadWrite], 8, 8, &gray)?;
+    let equalized = ClImage2D::<R<f32>>::from_image(executor.equalize_histogram(&gray).await?, queue)?.to_vec().await?;
+    assert_eq!(equalized.iter().map(|p| p.0).fold(0.0f32, f32::max), 1.0);
+    assert!(matches!(
+        executor.convert_color(&gray, ColorConversion::RgbToGray).await,
+        Err(ClError::Wrapper(WrapperError::UnsupportedImageOperation { operation: "convert_color", .. }))
+    ));
+
+    let integer = executor.create_image_2d::<Rgba<u32>>(&[MemoryFlags::ReadWrite], 8, 8)?;
+    assert!(matches!(
+        executor.sobel(&integer).await,
+        Err(ClError::Wrapper(WrapperError::UnsupportedImageOperation { operation: "sobel", .. }))
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_image_format_catalog() {
+    use easy_async_opencl3::cl_types::cl_image::{
+        image_channel_data_type::ClImageChannelType as Type, image_channel_order::ClImageChannelOrder as Order, image_formats::ClImageFormats,
+    };
+
+    let rgba8 = ClImageFormats::new(Order::RGBA, Type::UnormInt8).unwrap();
+    assert_eq!(rgba8, ClImageFormats::rgba_unorm_int8());
+    assert_eq!(rgba8.bytes_per_pixel(), Some(4));
+    assert_eq!(ClImageFormats::new(Order::RGx, Type::Float).unwrap().bytes_per_pixel(), Some(12));
+    assert_eq!(ClImageFormats::new(Order::RGB, Type::UnormShort565).unwrap().bytes_per_pixel(), Some(2));
+    assert_eq!(ClImageFormats::new(Order::RGBx, Type::UnormInt101010).unwrap().bytes_per_pixel(), Some(4));
+    assert_eq!(ClImageFormats::new(Order::RGBA, Type::UnormInt101010_2).unwrap().bytes_per_pixel(), Some(4));
+    assert_eq!(ClImageFormats::new(Order::SBgra, Type::UnormInt8).unwrap().bytes_per_pixel(), Some(4));
+
+    for (order, channel_type) in [
+        (Order::RGBA, Type::UnormShort565),
+        (Order::RGB, Type::UnormInt8),
+        (Order::Luminance, Type::UnsignedInt8),
+        (Order::Depth, Type::UnormInt8),
+        (Order::BGRA, Type::Float),
+        (Order::SRgba, Type::UnormInt16),
+        (Order::SBgra, Type::Float),
+        (Order::RGB, Type::UnormInt101010_2),
+    ] {
+        assert!(matches!(
+            ClImageFormats::new(order, channel_type),
+            Err(ClError::Wrapper(WrapperError::InvalidImageFormat { .. }))
+        ));
+        let invalid = ClImageFormats { image_channel_order: order, image_channel_data_type: channel_type };
+        assert_eq!(invalid.bytes_per_pixel(), None);
+        assert!(cl3::ext::cl_image_format::try_from(invalid).is_err());
+    }
+
+    let all = ClImageFormats::all();
+    assert!(all.iter().all(|format| format.bytes_per_pixel().is_some()));
+    assert!(all.contains(&ClImageFormats::rgba_float()));
+    // Core since OpenCL 2.0 and 2.1, not vendor extensions
+    assert_eq!(Order::from(cl3::ext::CL_sBGRA), Order::SBgra);
+    assert_eq!(Type::from(cl3::ext::CL_UNORM_INT_101010_2), Type::UnormInt101010_2);
+    for format in all {
+        let raw = cl3::ext::cl_image_format::try_from(format).unwrap();
+        assert_eq!(ClImageFormats::try_from(raw).unwrap(), format);
+    }
+}
@@ -0,0 +1,34 @@
+#![cfg(feature = "image")]
+
+use easy_async_opencl3::{
+    async_executor::AsyncExecutor,
+    cl_types::memory_flags::MemoryFlags,
+    error::{ClError, wrapper_error::WrapperError},
+};
+use image::{DynamicImage, Luma, Rgb, RgbImage, Rgba, RgbaImage};
+
+#[tokio::test]
+async fn test_image_round_trip() -> Result<(), ClError> {
+    let executor = AsyncExecutor::new_best_platform()?;
+
+    let rgba = RgbaImage::from_fn(64, 32, |x, y| Rgba([x as u8, y as u8, (x + y) as u8, 255]));
+    let cl_image = executor.upload_image(&[MemoryFlags::ReadOnly], &rgba)?;
+    assert_eq!(cl_image.get_width()?, 64);
+    assert_eq!(cl_image.get_height()?, 32);
+    let back = executor.download_image::<Rgba<u8>>(&cl_image).await?;
+    assert_eq!(back, rgba);
+
+    // RGB has no 8-bit OpenCL format, so it's stored as RGBA and converted back
+    let rgb = RgbImage::from_fn(16, 16, |x, y| Rgb([x as u8 * 10, y as u8 * 10, 7]));
+    let cl_image = executor.upload_image(&[MemoryFlags::ReadOnly], &rgb)?;
+    assert_eq!(executor.download_image::<Rgb<u8>>(&cl_image).await?, rgb);
+    match executor.download_dynamic_image(&cl_image).await? {
+        DynamicImage::ImageRgba8(image) => assert_eq!(image.get_pixel(3, 5), &Rgba([30, 50, 7, 255])),
+        other => panic!("unexpected variant {:?}", other.color()),
+    }
+
+    let result = executor.download_image::<Luma<u16>>(&cl_image).await;
+    assert!(matches!(result, Err(ClError::Wrapper(WrapperError::IncompatibleImageFormat { .. }))));
+
+    Ok(())
+}
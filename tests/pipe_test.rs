@@ -99,3 +99,22 @@ async fn test_pipes_producer_consumer() -> Result<(), ClError> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_pipe_introspection() -> Result<(), ClError> {
+    use easy_async_opencl3::cl_types::mem_object_type::MemObjectType;
+
+    let executor = AsyncExecutor::new_best_platform()?;
+    let device = executor.get_context().get_devices()?.first().cloned().unwrap();
+    if device.get_opencl_version() < OpenCLVersion::V2_0 || !device.get_pipe_support().unwrap_or(false) {
+        return Ok(());
+    }
+
+    let pipe = ClPipe::new(&executor.get_context(), &[MemoryFlags::ReadWrite], 4, 128)?;
+    assert_eq!(pipe.get_mem_type()?, MemObjectType::Pipe);
+    assert_eq!(pipe.get_packet_size()?, 4);
+    assert_eq!(pipe.get_max_packets()?, 128);
+    assert!(pipe.to_string().contains("max packets: 128"));
+
+    Ok(())
+}
@@ -0,0 +1,99 @@
+#![cfg(feature = "pipeline_config")]
+
+use easy_async_opencl3::{
+    async_executor::{AsyncExecutor, pipeline_config::PipelineDefinition},
+    cl_types::memory_flags::MemoryFlags,
+    error::{ClError, wrapper_error::WrapperError},
+};
+
+const DEFINITION: &str = r#"
+[[programs]]
+name = "math"
+source = """
+kernel void scale(global float* data, float factor) { size_t i = get_global_id(0); data[i] *= factor; }
+kernel void offset(global float* data, int amount) { size_t i = get_global_id(0); data[i] += amount; }
+"""
+
+[[buffers]]
+name = "data"
+elements = 1024
+type = "f32"
+flags = ["ReadWrite"]
+
+[[stages]]
+program = "math"
+kernel = "scale"
+global = [1024]
+args = [
+    { kind = "buffer", name = "data" },
+    { kind = "scalar", type = "f32", value = 2.0 },
+]
+
+[[stages]]
+program = "math"
+kernel = "offset"
+global = [1024]
+args = [
+    { kind = "buffer", name = "data" },
+    { kind = "scalar", type = "i32", value = 5 },
+]
+"#;
+
+fn invalid_entry(result: Result<PipelineDefinition, ClError>) -> String {
+    match result {
+        Err(ClError::Wrapper(WrapperError::InvalidPipelineDefinition { entry, .. })) => entry,
+        other => panic!("expected an invalid definition, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_pipeline_definition_validation() {
+    assert!(PipelineDefinition::from_toml_str(DEFINITION).is_ok());
+
+    let unknown_buffer = DEFINITION.replacen(r#"{ kind = "buffer", name = "data" },
+    { kind = "scalar", type = "i32""#, r#"{ kind = "buffer", name = "missing" },
+    { kind = "scalar", type = "i32""#, 1);
+    assert_eq!(invalid_entry(PipelineDefinition::from_toml_str(&unknown_buffer)), "stages[1].args[0]");
+
+    let bad_flag = DEFINITION.replace(r#"flags = ["ReadWrite"]"#, r#"flags = ["ReadWrite", "Sometimes"]"#);
+    assert_eq!(invalid_entry(PipelineDefinition::from_toml_str(&bad_flag)), "buffers[0].flags[1]");
+    assert!(matches!(
+        "Sometimes".parse::<MemoryFlags>(),
+        Err(ClError::Wrapper(WrapperError::UnknownMemoryFlag { flag })) if flag == "Sometimes"
+    ));
+
+    let host_ptr = DEFINITION.replace(r#"flags = ["ReadWrite"]"#, r#"flags = ["ReadWrite", "CopyHostPtr"]"#);
+    assert_eq!(invalid_entry(PipelineDefinition::from_toml_str(&host_ptr)), "buffers.data.flags");
+
+    let huge = DEFINITION.replace("elements = 1024", &format!("elements = {}", u64::MAX / 2));
+    assert_eq!(invalid_entry(PipelineDefinition::from_toml_str(&huge)), "buffers[0]");
+
+    let json = r#"{
+        "programs": [{ "name": "p", "source": "kernel void k(global int* a) {}" }],
+        "stages": [{ "program": "p", "kernel": "k", "global": [16], "args": [{ "kind": "scalar", "type": "u8", "value": 300 }] }]
+    }"#;
+    assert_eq!(invalid_entry(PipelineDefinition::from_json_str(json)), "stages[0].args[0]");
+}
+
+#[tokio::test]
+async fn test_pipeline_from_definition() -> Result<(), ClError> {
+    let executor = AsyncExecutor::new_best_platform()?;
+    let pipeline = PipelineDefinition::from_toml_str(DEFINITION)?.instantiate(&executor)?;
+
+    let input: Vec<f32> = (0..1024).map(|i| i as f32).collect();
+    pipeline.write_buffer("data", &input).await?;
+    pipeline.run().await?;
+
+    let mut output = vec![0.0f32; 1024];
+    pipeline.read_buffer("data", &mut output).await?;
+    assert!(output.iter().zip(&input).all(|(o, i)| *o == i * 2.0 + 5.0));
+
+    // Device errors say which entry caused them
+    let broken = DEFINITION.replace("data[i] *= factor;", "data[i] *= undeclared;");
+    match PipelineDefinition::from_toml_str(&broken)?.instantiate(&executor) {
+        Err(ClError::Wrapper(WrapperError::InvalidPipelineDefinition { entry, .. })) => assert_eq!(entry, "programs.math"),
+        other => panic!("expected a build error for 'math', got {:?}", other.map(|_| ()).err()),
+    }
+
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use easy_async_opencl3::{
-    async_executor::AsyncExecutor,
+    async_executor::{AsyncExecutor, profiling::{CommandKind, ProfileSummary}},
     cl_types::memory_flags::MemoryFlags,
     error::ClError,
 };
@@ -48,3 +48,180 @@ async fn test_simple_pipeline() -> Result<(), ClError> {
     
     Ok(())
 }
+
+#[tokio::test]
+async fn test_pipeline_detailed_profiling() -> Result<(), ClError> {
+    let executor = AsyncExecutor::new_best_platform_with_options(true)?;
+
+    let add_src = "kernel void add(global float* a, global float* b) { size_t i = get_global_id(0); a[i] += b[i]; }";
+    let mul_src = "kernel void mul(global float* a, float factor) { size_t i = get_global_id(0); a[i] *= factor; }";
+    let k_add = executor.create_kernel(&executor.build_program(add_src.to_string(), None)?, "add")?;
+    let k_mul = executor.create_kernel(&executor.build_program(mul_src.to_string(), None)?, "mul")?;
+
+    let size = 1024;
+    let mut data_a = vec![1.0f32; size];
+    let data_b = vec![1.0f32; size];
+    let buf_a = executor.create_buffer(&[MemoryFlags::ReadWrite, MemoryFlags::CopyHostPtr], size * 4, data_a.as_mut_ptr() as *mut c_void)?;
+    let buf_b = executor.create_buffer(&[MemoryFlags::ReadOnly, MemoryFlags::CopyHostPtr], size * 4, data_b.as_ptr() as *mut c_void)?;
+
+    let mut profiles = Vec::new();
+    for _ in 0..3 {
+        let report = executor.create_pipeline()
+            .add_stage(&k_add, size, 1, 1)
+                .arg_buffer(0, &buf_a)
+                .arg_buffer(1, &buf_b)
+                .finish()
+            .add_stage(&k_mul, size, 1, 1)
+                .arg_buffer(0, &buf_a)
+                .arg_scalar(1, 2.0f32)
+                .on_device(0)
+                .finish()
+            .read_buffer(&buf_a, &mut data_a)
+            .run()
+            .await?;
+
+        let profile = report.detailed_report()?;
+        assert!(profile.stage(Some(0)).any(|c| c.kind == CommandKind::Kernel));
+        assert!(profile.stage(Some(1)).any(|c| c.kind == CommandKind::Kernel));
+        // A pinned stage first migrates its buffers
+        assert!(profile.stage(Some(1)).any(|c| c.kind == CommandKind::Migration));
+        assert!(profile.stage(None).any(|c| c.kind == CommandKind::Read));
+        profiles.push(profile);
+    }
+
+    let summary = ProfileSummary::from_reports(&profiles);
+    println!("{}", summary);
+    assert!(summary.entries.iter().all(|e| e.execution.count == 3));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_local_args_in_tasks_and_stages() -> Result<(), ClError> {
+    let executor = AsyncExecutor::new_best_platform()?;
+
+    // Reverses every work-group through a __local scratch buffer
+    let src = "kernel void reverse_groups(global const float* input, global float* output, local float* scratch) {
+        size_t l = get_local_id(0);
+        size_t n = get_local_size(0);
+        scratch[l] = input[get_global_id(0)];
+        barrier(CLK_LOCAL_MEM_FENCE);
+        output[get_global_id(0)] = scratch[n - 1 - l];
+    }";
+    let program = executor.build_program(src.to_string(), None)?;
+    let kernel = executor.create_kernel(&program, "reverse_groups")?;
+
+    let size = 1024;
+    let group = 64;
+    let mut input: Vec<f32> = (0..size).map(|i| i as f32).collect();
+    let buf_in = executor.create_buffer(&[MemoryFlags::ReadOnly, MemoryFlags::CopyHostPtr], size * 4, input.as_mut_ptr() as *mut c_void)?;
+    let buf_tmp = executor.create_buffer(&[MemoryFlags::ReadWrite], size * 4, std::ptr::null_mut())?;
+    let buf_out = executor.create_buffer(&[MemoryFlags::ReadWrite], size * 4, std::ptr::null_mut())?;
+
+    let mut from_task = vec![0.0f32; size];
+    executor.create_task(&kernel)
+        .arg_buffer(0, &buf_in)
+        .arg_buffer(1, &buf_tmp)
+        .arg_local(2, group * 4)
+        .global_work_dims(size, 1, 1)
+        .local_work_dims(group, 1, 1)
+        .read_buffer(&buf_tmp, &mut from_task)
+        .run()
+        .await?;
+
+    // Reversing twice restores the input
+    let mut from_pipeline = vec![0.0f32; size];
+    executor.create_pipeline()
+        .add_stage(&kernel, size, 1, 1)
+            .arg_buffer(0, &buf_tmp)
+            .arg_buffer(1, &buf_out)
+            .arg_local(2, group * 4)
+            .local_work_dims(group, 1, 1)
+            .finish()
+        .read_buffer(&buf_out, &mut from_pipeline)
+        .run()
+        .await?;
+
+    for (i, value) in from_task.iter().enumerate() {
+        let base = i / group * group;
+        assert_eq!(*value, input[base + group - 1 - (i - base)]);
+    }
+    assert_eq!(from_pipeline, input);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_pipeline_stage_device_placement() -> Result<(), ClError> {
+    let executor = AsyncExecutor::new_best_platform_with_options(true)?;
+    let first = 0;
+    let last = executor.get_queues().len() - 1;
+
+    let src = "kernel void add_one(global float* a) { a[get_global_id(0)] += 1.0f; }
+               kernel void square(global float* a) { size_t i = get_global_id(0); a[i] *= a[i]; }";
+    let program = executor.build_program(src.to_string(), None)?;
+    let k_add = executor.create_kernel(&program, "add_one")?;
+    let k_square = executor.create_kernel(&program, "square")?;
+
+    let size = 4096;
+    let mut data: Vec<f32> = (0..size).map(|i| (i % 16) as f32).collect();
+    let buf = executor.create_buffer(&[MemoryFlags::ReadWrite, MemoryFlags::CopyHostPtr], size * 4, data.as_mut_ptr() as *mut c_void)?;
+
+    let mut result = vec![0.0f32; size];
+    let report = executor.create_pipeline()
+        .add_stage(&k_add, size, 1, 1)
+            .arg_buffer(0, &buf)
+            .on_device(first)
+            .finish()
+        .add_stage(&k_square, size, 1, 1)
+            .arg_buffer(0, &buf)
+            .on_device(last)
+            .finish()
+        .read_buffer(&buf, &mut result)
+        .run()
+        .await?;
+
+    assert_eq!(report.stage_reports[0].kernel_device_indices, vec![first]);
+    assert_eq!(report.stage_reports[1].kernel_device_indices, vec![last]);
+    assert_eq!(report.final_read_device_indices, vec![last]);
+    assert!(result.iter().zip(&data).all(|(r, d)| *r == (d + 1.0) * (d + 1.0)));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_migrate_mem_objects_and_pre_migrated_task() -> Result<(), ClError> {
+    use easy_async_opencl3::cl_types::mem_object::MigrationFlags;
+
+    let executor = AsyncExecutor::new_best_platform()?;
+    let src = "kernel void double_it(global const float* a, global float* b) { size_t i = get_global_id(0); b[i] = a[i] * 2.0f; }";
+    let program = executor.build_program(src.to_string(), None)?;
+    let kernel = executor.create_kernel(&program, "double_it")?;
+
+    let size = 8192;
+    let mut input: Vec<f32> = (0..size).map(|i| i as f32).collect();
+    let a = executor.create_buffer(&[MemoryFlags::ReadOnly, MemoryFlags::CopyHostPtr], size * 4, input.as_mut_ptr() as *mut c_void)?;
+    let b = executor.create_typed_buffer::<f32>(&[MemoryFlags::ReadWrite], size)?;
+
+    // Explicit migrations keep the contents (unless told otherwise)
+    let last_queue = executor.get_queues().last().unwrap();
+    executor.migrate_mem_objects(&[&a, &b], last_queue, &[]).await?;
+    executor.migrate_mem_objects(&[&b], last_queue, &[MigrationFlags::ContentUndefined]).await?;
+    executor.migrate_mem_objects(&[&a], last_queue, &[MigrationFlags::ToHost]).await?;
+    let mut check = vec![0.0f32; size];
+    executor.read_buffer(&a, &mut check).await?;
+    assert_eq!(check, input);
+
+    let mut result = vec![0.0f32; size];
+    executor.create_task(&kernel)
+        .arg_buffer(0, &a)
+        .arg_buffer(1, &b)
+        .global_work_dims(size, 1, 1)
+        .pre_migrate()
+        .read_buffer(&b, &mut result)
+        .run()
+        .await?;
+
+    for (i, value) in result.iter().enumerate() {
+        assert_eq!(*value, i as f32 * 2.0);
+    }
+    Ok(())
+}
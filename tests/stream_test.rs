@@ -0,0 +1,39 @@
+use easy_async_opencl3::{
+    async_executor::AsyncExecutor,
+    error::ClError,
+};
+use futures::StreamExt;
+
+#[tokio::test]
+async fn test_double_buffered_stream() -> Result<(), ClError> {
+    let executor = AsyncExecutor::new_best_platform()?;
+
+    let src = "kernel void scale(global const float* input, global float* output, float factor) { size_t i = get_global_id(0); output[i] = input[i] * factor; }";
+    let program = executor.build_program(src.to_string(), None)?;
+    let kernel = executor.create_kernel(&program, "scale")?;
+
+    // Chunks of different sizes, as they would arrive from a socket or a file reader
+    let chunks: Vec<Vec<f32>> = (0..8)
+        .map(|i| vec![i as f32; 1024 + i * 128])
+        .collect();
+
+    let results: Vec<Result<Vec<f32>, ClError>> = executor
+        .create_stream(&kernel)
+        .input_arg(0)
+        .output_arg(1)
+        .arg_scalar(2, 3.0f32)
+        .depth(3)
+        .run::<f32, f32, _, _>(futures::stream::iter(chunks.clone()))?
+        .collect()
+        .await;
+
+    assert_eq!(results.len(), chunks.len());
+    for (chunk, result) in chunks.iter().zip(results) {
+        let result = result?;
+        assert_eq!(result.len(), chunk.len());
+        assert!(result.iter().zip(chunk).all(|(r, c)| *r == c * 3.0));
+    }
+
+    println!("Stream: processed {} chunks", chunks.len());
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use easy_async_opencl3::{
+    async_executor::AsyncExecutor,
+    cl_types::{cl_device::opencl_version::OpenCLVersion, cl_svm_buffer::svm_mode::SvmMode, memory_flags::MemoryFlags},
+    error::{ClError, wrapper_error::WrapperError},
+};
+
+#[tokio::test]
+async fn test_fine_grain_svm() -> Result<(), ClError> {
+    let executor = AsyncExecutor::new_best_platform()?;
+    let device = executor.get_context().get_devices()?.first().cloned().unwrap();
+    if device.get_opencl_version() < OpenCLVersion::V2_0 {
+        return Ok(());
+    }
+
+    let mut coarse = executor.create_svm_buffer::<f32>(&[MemoryFlags::ReadWrite], 16)?;
+    assert_eq!(coarse.mode(), SvmMode::Coarse);
+    assert!(matches!(coarse.as_mut_slice(), Err(ClError::Wrapper(WrapperError::SvmMapRequired))));
+
+    let capabilities = device.get_svm_capabilities()?;
+    let mut buffer = match executor.create_svm_buffer_with_mode::<f32>(&[MemoryFlags::ReadWrite], 256, SvmMode::FineGrain) {
+        Ok(buffer) => buffer,
+        Err(ClError::Wrapper(WrapperError::SvmModeUnsupported { mode: SvmMode::FineGrain, .. })) => {
+            assert!(!SvmMode::FineGrain.is_supported_by(&capabilities));
+            return Ok(());
+        }
+        Err(e) => return Err(e),
+    };
+
+    // Host writes before the launch are seen by the kernel, and its writes after `run` returns
+    buffer.as_mut_slice()?.iter_mut().enumerate().for_each(|(i, x)| *x = i as f32);
+    let program = executor.build_program("kernel void twice(global float* data) { data[get_global_id(0)] *= 2.0f; }".to_string(), None)?;
+    let kernel = executor.create_kernel(&program, "twice")?;
+    executor.create_task(&kernel).arg_svm(0, &buffer).global_work_dims(256, 1, 1).run().await?;
+    // No command using the buffer is left running
+    let result = unsafe { buffer.as_slice()? };
+    assert!(result.iter().enumerate().all(|(i, &x)| x == 2.0 * i as f32));
+
+    if !SvmMode::FineGrainAtomics.is_supported_by(&capabilities) {
+        assert!(matches!(
+            executor.create_svm_buffer_with_mode::<u32>(&[MemoryFlags::ReadWrite], 1, SvmMode::FineGrainAtomics),
+            Err(ClError::Wrapper(WrapperError::SvmModeUnsupported { .. }))
+        ));
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_svm_transfers() -> Result<(), ClError> {
+    let executor = AsyncExecutor::new_best_platform()?;
+    let device = executor.get_context().get_devices()?.first().cloned().unwrap();
+    if device.get_opencl_version() < OpenCLVersion::V2_0 {
+        return Ok(());
+    }
+
+    // Coarse-grain buffers are staged without mapping
+    let mut src = executor.create_svm_buffer::<u32>(&[MemoryFlags::ReadWrite], 64)?;
+    let mut dst = executor.create_svm_buffer::<u32>(&[MemoryFlags::ReadWrite], 64)?;
+    assert_eq!((executor.memory_usage().in_use, executor.memory_usage().live_objects), (512, 2));
+    let data: Vec<u32> = (0..64).collect();
+    executor.svm_write(&mut src, 0, &data).await?;
+    executor.svm_fill(&mut dst, 7u32).await?;
+    executor.svm_copy(&src, 8, &mut dst, 0, 16).await?;
+
+    let mut read_back = vec![0u32; 64];
+    executor.svm_read(&dst, 0, &mut read_back).await?;
+    assert_eq!(read_back[..16], data[8..24]);
+    assert!(read_back[16..].iter().all(|&x| x == 7));
+
+    assert!(matches!(
+        executor.svm_write(&mut src, 60, &data[..8]).await,
+        Err(ClError::Wrapper(WrapperError::BufferRangeOutOfBounds { offset: 60, len: 8, buffer_len: 64 }))
+    ));
+    assert!(matches!(
+        executor.svm_copy(&src, 0, &mut dst, 56, 16).await,
+        Err(ClError::Wrapper(WrapperError::BufferRangeOutOfBounds { .. }))
+    ));
+
+    #[cfg(feature = "CL_VERSION_2_1")]
+    if device.get_opencl_version() >= OpenCLVersion::V2_1 {
+        executor.migrate_svm(&dst, &executor.get_queues()[0], &[]).await?;
+    }
+
+    executor.free_svm(src).await?;
+    executor.free_svm(dst).await?;
+    assert_eq!((executor.memory_usage().in_use, executor.memory_usage().live_objects), (0, 0));
+    Ok(())
+}